@@ -0,0 +1,48 @@
+//! A general-purpose cancellation registry for long-running commands.
+//!
+//! The frontend hands each cancellable call an `operation_id` it made up
+//! itself; the command registers an [`AtomicBool`] flag for that id in
+//! [`CancellationRegistry`] and polls it between files or batches.
+//! `cancel_operation` flips the flag from the frontend; the running command
+//! notices on its next poll, stops early, and returns whatever it had
+//! already computed with `cancelled: true` rather than an error, so partial
+//! progress isn't thrown away. Cancelling an id that's unknown or already
+//! finished is a no-op.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+#[derive(Default)]
+pub struct CancellationRegistry {
+    tokens: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+impl CancellationRegistry {
+    /// Registers a fresh flag for `operation_id`, replacing any previous
+    /// (presumably already-finished) flag registered under the same id.
+    pub fn register(&self, operation_id: &str) -> Arc<AtomicBool> {
+        let token = Arc::new(AtomicBool::new(false));
+        if let Ok(mut tokens) = self.tokens.lock() {
+            tokens.insert(operation_id.to_string(), Arc::clone(&token));
+        }
+        token
+    }
+
+    /// Removes `operation_id`'s flag once its command has returned, so a
+    /// `cancel_operation` call arriving after completion has nothing to hit.
+    pub fn unregister(&self, operation_id: &str) {
+        if let Ok(mut tokens) = self.tokens.lock() {
+            tokens.remove(operation_id);
+        }
+    }
+
+    /// Flips the flag for `operation_id`, if it's still registered.
+    pub fn cancel(&self, operation_id: &str) {
+        if let Ok(tokens) = self.tokens.lock() {
+            if let Some(token) = tokens.get(operation_id) {
+                token.store(true, Ordering::SeqCst);
+            }
+        }
+    }
+}
@@ -0,0 +1,196 @@
+//! Duplicate and near-duplicate content detection across a set of files.
+//!
+//! Each file is read once (skipping anything over `MAX_DEDUPE_FILE_SIZE`),
+//! reduced to whitespace-collapsed text, and fingerprinted two ways: a
+//! content hash for exact duplicates, and a set of word shingles for a
+//! cheap Jaccard-similarity estimate of near-duplicates. Reading and
+//! fingerprinting run in parallel; the (much smaller) pairwise comparison
+//! afterwards runs on the collected fingerprints.
+
+use std::collections::{HashMap, HashSet};
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Files over this size are skipped rather than re-read for dedupe, so a
+/// handful of huge files don't dominate the scan.
+const MAX_DEDUPE_FILE_SIZE: u64 = 2 * 1024 * 1024;
+/// Word-shingle width used for the near-duplicate similarity estimate.
+const SHINGLE_SIZE: usize = 5;
+/// Files at or above this Jaccard similarity are grouped as near-duplicates.
+const SIMILARITY_THRESHOLD: f32 = 0.9;
+
+/// A set of paths judged duplicate (`similarity: 1.0`) or near-duplicate
+/// (the lowest pairwise Jaccard similarity that put them in this group).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateGroup {
+    pub paths: Vec<String>,
+    pub similarity: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct DuplicateContentResult {
+    pub groups: Vec<DuplicateGroup>,
+}
+
+struct FileFingerprint {
+    path: String,
+    normalized_hash: String,
+    shingles: HashSet<u64>,
+}
+
+/// Read and fingerprint `paths` in parallel, then group exact and
+/// near-duplicates (`SIMILARITY_THRESHOLD` and above).
+pub fn find_duplicate_content(paths: &[String]) -> DuplicateContentResult {
+    let fingerprints: Vec<FileFingerprint> = paths
+        .par_iter()
+        .filter_map(|path| fingerprint_file(path))
+        .collect();
+
+    let mut groups = Vec::new();
+    let mut grouped: HashSet<usize> = HashSet::new();
+
+    let mut by_hash: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (index, fp) in fingerprints.iter().enumerate() {
+        by_hash.entry(fp.normalized_hash.as_str()).or_default().push(index);
+    }
+    for indices in by_hash.into_values() {
+        if indices.len() > 1 {
+            groups.push(DuplicateGroup {
+                paths: indices.iter().map(|&i| fingerprints[i].path.clone()).collect(),
+                similarity: 1.0,
+            });
+            grouped.extend(indices);
+        }
+    }
+
+    let remaining: Vec<usize> = (0..fingerprints.len()).filter(|i| !grouped.contains(i)).collect();
+    let mut visited: HashSet<usize> = HashSet::new();
+    for &i in &remaining {
+        if visited.contains(&i) {
+            continue;
+        }
+        let mut cluster = vec![i];
+        let mut lowest_similarity = 1.0f32;
+        for &j in &remaining {
+            if i == j || visited.contains(&j) {
+                continue;
+            }
+            let similarity = jaccard_similarity(&fingerprints[i].shingles, &fingerprints[j].shingles);
+            if similarity >= SIMILARITY_THRESHOLD {
+                cluster.push(j);
+                lowest_similarity = lowest_similarity.min(similarity);
+            }
+        }
+        if cluster.len() > 1 {
+            for &index in &cluster {
+                visited.insert(index);
+            }
+            groups.push(DuplicateGroup {
+                paths: cluster.iter().map(|&idx| fingerprints[idx].path.clone()).collect(),
+                similarity: lowest_similarity,
+            });
+        }
+    }
+
+    DuplicateContentResult { groups }
+}
+
+fn fingerprint_file(path: &str) -> Option<FileFingerprint> {
+    let size = std::fs::metadata(path).ok()?.len();
+    if size > MAX_DEDUPE_FILE_SIZE {
+        return None;
+    }
+    let content = std::fs::read_to_string(path).ok()?;
+    let normalized = normalize_whitespace(&content);
+    if normalized.is_empty() {
+        return None;
+    }
+
+    Some(FileFingerprint {
+        path: path.to_string(),
+        normalized_hash: blake3::hash(normalized.as_bytes()).to_hex().to_string(),
+        shingles: word_shingles(&normalized, SHINGLE_SIZE),
+    })
+}
+
+fn normalize_whitespace(content: &str) -> String {
+    content.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Hashes of each `size`-word sliding window (or the whole text, if it's
+/// shorter than `size` words) over already-whitespace-normalized text.
+fn word_shingles(normalized: &str, size: usize) -> HashSet<u64> {
+    let words: Vec<&str> = normalized.split(' ').collect();
+    if words.len() < size {
+        return [hash_shingle(&words.join(" "))].into_iter().collect();
+    }
+    words.windows(size).map(|w| hash_shingle(&w.join(" "))).collect()
+}
+
+fn hash_shingle(text: &str) -> u64 {
+    let hash = blake3::hash(text.as_bytes());
+    u64::from_le_bytes(hash.as_bytes()[..8].try_into().unwrap())
+}
+
+fn jaccard_similarity(a: &HashSet<u64>, b: &HashSet<u64>) -> f32 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let intersection = a.intersection(b).count();
+    let union = a.union(b).count();
+    intersection as f32 / union as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutils::TestDir;
+
+    #[test]
+    fn groups_exact_duplicates_ignoring_whitespace_differences() {
+        let dir = TestDir::new("prompt_pack_lite_dupes_exact");
+        let a = dir.write("a.txt", "hello   world\nfoo");
+        let b = dir.write("b.txt", "hello world foo");
+        let c = dir.write("c.txt", "something entirely different");
+
+        let result = find_duplicate_content(&[a.clone(), b.clone(), c.clone()]);
+
+        assert_eq!(result.groups.len(), 1);
+        let group = &result.groups[0];
+        assert_eq!(group.similarity, 1.0);
+        assert!(group.paths.contains(&a));
+        assert!(group.paths.contains(&b));
+        assert!(!group.paths.contains(&c));
+    }
+
+    #[test]
+    fn groups_near_duplicates_above_similarity_threshold() {
+        let dir = TestDir::new("prompt_pack_lite_dupes_near");
+        let base = "the quick brown fox jumps over the lazy dog and keeps running";
+        let a = dir.write("a.txt", base);
+        let b = dir.write("b.txt", &format!("{base} today"));
+        let c = dir.write("c.txt", "completely unrelated content about database migrations");
+
+        let result = find_duplicate_content(&[a.clone(), b.clone(), c.clone()]);
+
+        assert_eq!(result.groups.len(), 1);
+        let group = &result.groups[0];
+        assert!(group.similarity >= SIMILARITY_THRESHOLD);
+        assert!(group.paths.contains(&a));
+        assert!(group.paths.contains(&b));
+        assert!(!group.paths.contains(&c));
+    }
+
+    #[test]
+    fn skips_files_over_the_size_cap() {
+        let dir = TestDir::new("prompt_pack_lite_dupes_huge");
+        let huge_content = "x".repeat((MAX_DEDUPE_FILE_SIZE + 1) as usize);
+        let huge = dir.write("huge.txt", &huge_content);
+        let small = dir.write("small.txt", "just some text");
+
+        let result = find_duplicate_content(&[huge, small]);
+
+        assert!(result.groups.is_empty());
+    }
+}
@@ -0,0 +1,514 @@
+//! Cross-file dependency graph extraction using tree-sitter.
+//!
+//! Parses each visited file with the same tree-sitter grammars the skeleton
+//! extractors use, pulls out its import specifiers, and resolves the ones
+//! that point at other project files (relative `./`/`../` imports,
+//! tsconfig-style path aliases, Python package-relative imports, and Rust
+//! `mod` declarations) into an adjacency list rooted at the caller's entry
+//! files. Specifiers that don't resolve to a file on disk (external
+//! packages, unknown aliases, ...) are kept separately instead of dropped.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tree_sitter::{Node, Parser};
+
+use crate::skeleton::common::get_node_text;
+
+/// Adjacency list of a project's local import graph, rooted at the entry
+/// files passed to `build_import_graph`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ImportGraphResult {
+    /// Relative path (from `root`) -> relative paths of files it imports.
+    pub graph: HashMap<String, Vec<String>>,
+    /// Relative path -> import specifiers that couldn't be resolved to a
+    /// project file (external packages, unknown aliases, missing files).
+    pub unresolved: HashMap<String, Vec<String>>,
+}
+
+/// tsconfig.json's `compilerOptions.baseUrl`/`paths`, resolved once per call
+/// and reused for every TypeScript/JavaScript file visited.
+struct TsPathAliases {
+    base_url: PathBuf,
+    paths: Vec<(String, Vec<String>)>,
+}
+
+/// Parse a file's local (project-relative) imports and resolve them,
+/// breadth-first, starting from `entry_paths`. `max_depth` bounds how many
+/// hops away from an entry a file's own imports get extracted (`Some(0)`
+/// expands only the entries themselves); files one hop past the limit still
+/// appear as resolved targets, they just aren't expanded further. `None`
+/// walks the whole reachable graph. `entry_paths` and all graph keys are
+/// slash-separated paths relative to `root`.
+pub fn build_import_graph(root: &Path, entry_paths: &[String], max_depth: Option<usize>) -> ImportGraphResult {
+    let ts_aliases = load_ts_path_aliases(root);
+
+    let mut result = ImportGraphResult::default();
+    let mut visited: HashSet<String> = HashSet::new();
+    let mut queue: VecDeque<(String, usize)> = VecDeque::new();
+
+    for entry in entry_paths {
+        let normalized = normalize_rel_path(entry);
+        if visited.insert(normalized.clone()) {
+            queue.push_back((normalized, 0));
+        }
+    }
+
+    while let Some((rel_path, depth)) = queue.pop_front() {
+        if max_depth.is_some_and(|max| depth > max) {
+            continue;
+        }
+
+        let abs_path = root.join(&rel_path);
+        let Ok(content) = std::fs::read_to_string(&abs_path) else {
+            continue;
+        };
+        let specifiers = extract_import_specifiers(&content, &abs_path);
+
+        let mut resolved = Vec::new();
+        let mut unresolved = Vec::new();
+        for import_ref in specifiers {
+            match resolve_specifier(root, &abs_path, &import_ref.specifier, ts_aliases.as_ref()) {
+                Some(target) => {
+                    if !resolved.contains(&target) {
+                        resolved.push(target);
+                    }
+                }
+                // Optional refs are best-effort guesses (e.g. `from . import
+                // foo` might name a submodule `foo.py` or just an attribute
+                // of the package's `__init__.py`) — only report a genuine
+                // failure to resolve, not a guess that didn't pan out.
+                None if !import_ref.optional => unresolved.push(import_ref.specifier),
+                None => {}
+            }
+        }
+
+        if !resolved.is_empty() {
+            result.graph.insert(rel_path.clone(), resolved.clone());
+        }
+        if !unresolved.is_empty() {
+            result.unresolved.insert(rel_path.clone(), unresolved);
+        }
+
+        let next_depth = depth + 1;
+        for target in resolved {
+            if visited.insert(target.clone()) {
+                queue.push_back((target, next_depth));
+            }
+        }
+    }
+
+    result
+}
+
+fn normalize_rel_path(path: &str) -> String {
+    path.replace('\\', "/")
+}
+
+// ============ Import Specifier Extraction ============
+
+/// A raw import specifier as written in source, plus whether a failed
+/// resolution should be reported (a real external/unresolvable import) or
+/// silently dropped (a best-effort guess, see `resolve_python_specifier`).
+struct ImportRef {
+    specifier: String,
+    optional: bool,
+}
+
+impl ImportRef {
+    fn required(specifier: String) -> Self {
+        Self { specifier, optional: false }
+    }
+
+    fn optional(specifier: String) -> Self {
+        Self { specifier, optional: true }
+    }
+}
+
+fn extract_import_specifiers(content: &str, abs_path: &Path) -> Vec<ImportRef> {
+    let extension = abs_path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    match extension.to_lowercase().as_str() {
+        "ts" | "mts" | "cts" | "tsx" | "js" | "mjs" | "cjs" | "jsx" => {
+            extract_js_ts_imports(content, extension)
+        }
+        "py" | "pyw" | "pyi" => extract_python_imports(content),
+        "rs" => extract_rust_mod_declarations(content),
+        _ => Vec::new(),
+    }
+}
+
+fn extract_js_ts_imports(content: &str, extension: &str) -> Vec<ImportRef> {
+    let language = match extension.to_lowercase().as_str() {
+        "tsx" => tree_sitter_typescript::LANGUAGE_TSX.into(),
+        "ts" | "mts" | "cts" => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+        _ => tree_sitter_javascript::LANGUAGE.into(),
+    };
+    let mut parser = Parser::new();
+    if parser.set_language(&language).is_err() {
+        return Vec::new();
+    }
+    let Some(tree) = parser.parse(content, None) else {
+        return Vec::new();
+    };
+
+    let mut specifiers = Vec::new();
+    let source = content.as_bytes();
+    let mut cursor = tree.root_node().walk();
+    for child in tree.root_node().children(&mut cursor) {
+        // Covers `import ... from "..."` and re-exports (`export ... from "..."`).
+        if !matches!(child.kind(), "import_statement" | "import_declaration" | "export_statement") {
+            continue;
+        }
+        let Some(source_node) = child.child_by_field_name("source") else {
+            continue;
+        };
+        if let Some(specifier) = js_string_literal(source_node, source) {
+            specifiers.push(ImportRef::required(specifier));
+        }
+    }
+    specifiers
+}
+
+fn js_string_literal(node: Node, source: &[u8]) -> Option<String> {
+    let raw = get_node_text(node, source).trim();
+    if raw.len() < 2 || raw.contains("${") {
+        return None;
+    }
+    let first = raw.chars().next()?;
+    let last = raw.chars().last()?;
+    if (first == '"' && last == '"') || (first == '\'' && last == '\'') || (first == '`' && last == '`') {
+        Some(raw[1..raw.len() - 1].to_string())
+    } else {
+        None
+    }
+}
+
+/// Python imports, expressed as the raw dotted module path the caller wrote
+/// (leading dots preserved for relative imports, e.g. `.foo.bar`, `..baz`).
+fn extract_python_imports(content: &str) -> Vec<ImportRef> {
+    let mut parser = Parser::new();
+    if parser.set_language(&tree_sitter_python::LANGUAGE.into()).is_err() {
+        return Vec::new();
+    }
+    let Some(tree) = parser.parse(content, None) else {
+        return Vec::new();
+    };
+
+    let mut specifiers = Vec::new();
+    let source = content.as_bytes();
+    let mut cursor = tree.root_node().walk();
+    for child in tree.root_node().children(&mut cursor) {
+        match child.kind() {
+            "import_statement" => {
+                let mut name_cursor = child.walk();
+                for name in child.children_by_field_name("name", &mut name_cursor) {
+                    if let Some(dotted) = python_dotted_name(name, source) {
+                        specifiers.push(ImportRef::required(dotted));
+                    }
+                }
+            }
+            "import_from_statement" => {
+                let Some(module) = child.child_by_field_name("module_name") else {
+                    continue;
+                };
+                let Some(base) = python_module_name_text(module, source) else {
+                    continue;
+                };
+                specifiers.push(ImportRef::required(base.clone()));
+
+                // `from pkg import sub` is ambiguous from the AST alone: `sub`
+                // might be a submodule (`pkg/sub.py`) or just an attribute of
+                // `pkg/__init__.py`. Guess the submodule form too; the guess
+                // is optional so a miss doesn't get reported as unresolved.
+                let mut name_cursor = child.walk();
+                for name in child.children_by_field_name("name", &mut name_cursor) {
+                    if let Some(leaf) = python_dotted_name(name, source) {
+                        let joined = if base.ends_with('.') {
+                            format!("{base}{leaf}")
+                        } else {
+                            format!("{base}.{leaf}")
+                        };
+                        specifiers.push(ImportRef::optional(joined));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    specifiers
+}
+
+fn python_dotted_name(node: Node, source: &[u8]) -> Option<String> {
+    let name = if node.kind() == "aliased_import" {
+        node.child_by_field_name("name")?
+    } else {
+        node
+    };
+    Some(get_node_text(name, source).to_string())
+}
+
+fn python_module_name_text(node: Node, source: &[u8]) -> Option<String> {
+    match node.kind() {
+        "dotted_name" => Some(get_node_text(node, source).to_string()),
+        "relative_import" => Some(get_node_text(node, source).to_string()),
+        _ => None,
+    }
+}
+
+/// Rust `mod foo;` declarations (a `mod_item` with no inline body).
+fn extract_rust_mod_declarations(content: &str) -> Vec<ImportRef> {
+    let mut parser = Parser::new();
+    if parser.set_language(&tree_sitter_rust::LANGUAGE.into()).is_err() {
+        return Vec::new();
+    }
+    let Some(tree) = parser.parse(content, None) else {
+        return Vec::new();
+    };
+
+    let mut names = Vec::new();
+    let source = content.as_bytes();
+    let mut cursor = tree.root_node().walk();
+    for child in tree.root_node().children(&mut cursor) {
+        if child.kind() == "mod_item" && child.child_by_field_name("body").is_none() {
+            if let Some(name) = child.child_by_field_name("name") {
+                names.push(ImportRef::required(get_node_text(name, source).to_string()));
+            }
+        }
+    }
+    names
+}
+
+// ============ Specifier Resolution ============
+
+fn resolve_specifier(root: &Path, from_file: &Path, specifier: &str, ts_aliases: Option<&TsPathAliases>) -> Option<String> {
+    let extension = from_file.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+    match extension.as_str() {
+        "ts" | "mts" | "cts" | "tsx" | "js" | "mjs" | "cjs" | "jsx" => {
+            resolve_js_ts_specifier(root, from_file, specifier, ts_aliases)
+        }
+        "py" | "pyw" | "pyi" => resolve_python_specifier(root, from_file, specifier),
+        "rs" => resolve_rust_mod(root, from_file, specifier),
+        _ => None,
+    }
+}
+
+const JS_TS_EXTENSIONS: &[&str] = &["ts", "tsx", "mts", "cts", "js", "jsx", "mjs", "cjs"];
+
+fn resolve_js_ts_specifier(root: &Path, from_file: &Path, specifier: &str, ts_aliases: Option<&TsPathAliases>) -> Option<String> {
+    let candidate_base = if specifier.starts_with("./") || specifier.starts_with("../") {
+        from_file.parent()?.join(specifier)
+    } else if let Some(aliases) = ts_aliases {
+        resolve_ts_alias(aliases, specifier)?
+    } else {
+        return None;
+    };
+
+    resolve_js_ts_file(root, &candidate_base)
+}
+
+/// Try `<path>`, `<path>.<ext>`, and `<path>/index.<ext>` for each extension
+/// the project uses, returning the first match as a root-relative path.
+fn resolve_js_ts_file(root: &Path, candidate: &Path) -> Option<String> {
+    if candidate.is_file() {
+        return to_rel_path(root, candidate);
+    }
+    for ext in JS_TS_EXTENSIONS {
+        let with_ext = append_extension(candidate, ext);
+        if with_ext.is_file() {
+            return to_rel_path(root, &with_ext);
+        }
+    }
+    for ext in JS_TS_EXTENSIONS {
+        let index = candidate.join(format!("index.{ext}"));
+        if index.is_file() {
+            return to_rel_path(root, &index);
+        }
+    }
+    None
+}
+
+fn append_extension(path: &Path, ext: &str) -> PathBuf {
+    let mut with_ext = path.as_os_str().to_owned();
+    with_ext.push(".");
+    with_ext.push(ext);
+    PathBuf::from(with_ext)
+}
+
+fn resolve_ts_alias(aliases: &TsPathAliases, specifier: &str) -> Option<PathBuf> {
+    for (pattern, targets) in &aliases.paths {
+        let Some(prefix) = pattern.strip_suffix('*') else {
+            if pattern == specifier {
+                return targets.first().map(|t| aliases.base_url.join(t));
+            }
+            continue;
+        };
+        if let Some(rest) = specifier.strip_prefix(prefix) {
+            let target = targets.first()?.replace('*', rest);
+            return Some(aliases.base_url.join(target));
+        }
+    }
+    None
+}
+
+fn load_ts_path_aliases(root: &Path) -> Option<TsPathAliases> {
+    let tsconfig_path = root.join("tsconfig.json");
+    let raw = std::fs::read_to_string(&tsconfig_path).ok()?;
+    let json: serde_json::Value = serde_json::from_str(&raw).ok()?;
+    let compiler_options = json.get("compilerOptions")?;
+
+    let base_url = compiler_options
+        .get("baseUrl")
+        .and_then(|v| v.as_str())
+        .map(|b| root.join(b))
+        .unwrap_or_else(|| root.to_path_buf());
+
+    let paths_obj = compiler_options.get("paths")?.as_object()?;
+    let mut paths = Vec::new();
+    for (pattern, targets) in paths_obj {
+        let targets: Vec<String> = targets
+            .as_array()?
+            .iter()
+            .filter_map(|t| t.as_str().map(|s| s.to_string()))
+            .collect();
+        if !targets.is_empty() {
+            paths.push((pattern.clone(), targets));
+        }
+    }
+
+    Some(TsPathAliases { base_url, paths })
+}
+
+fn resolve_python_specifier(root: &Path, from_file: &Path, specifier: &str) -> Option<String> {
+    let base_dir = if let Some(rest) = specifier.strip_prefix('.') {
+        // Relative import: leading dots walk up from the current file's
+        // package directory (one dot = the current package).
+        let mut dir = from_file.parent()?.to_path_buf();
+        let mut remaining = rest;
+        let mut extra_dots = 0;
+        while let Some(next) = remaining.strip_prefix('.') {
+            extra_dots += 1;
+            remaining = next;
+        }
+        for _ in 0..extra_dots {
+            dir = dir.parent()?.to_path_buf();
+        }
+        let module_path = remaining.replace('.', "/");
+        if module_path.is_empty() { dir } else { dir.join(module_path) }
+    } else {
+        // Absolute/package-relative import: resolve against the project root.
+        root.join(specifier.replace('.', "/"))
+    };
+
+    if base_dir.with_extension("py").is_file() {
+        return to_rel_path(root, &base_dir.with_extension("py"));
+    }
+    let init = base_dir.join("__init__.py");
+    if init.is_file() {
+        return to_rel_path(root, &init);
+    }
+    None
+}
+
+fn resolve_rust_mod(root: &Path, from_file: &Path, mod_name: &str) -> Option<String> {
+    let dir = from_file.parent()?;
+    let sibling = dir.join(format!("{mod_name}.rs"));
+    if sibling.is_file() {
+        return to_rel_path(root, &sibling);
+    }
+    let nested = dir.join(mod_name).join("mod.rs");
+    if nested.is_file() {
+        return to_rel_path(root, &nested);
+    }
+    None
+}
+
+fn to_rel_path(root: &Path, abs: &Path) -> Option<String> {
+    let rel = abs.strip_prefix(root).ok()?;
+    Some(rel.to_string_lossy().replace('\\', "/"))
+}
+
+// ============ Tests ============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutils::TestDir;
+
+    #[test]
+    fn resolves_relative_ts_imports_and_reports_external_ones() {
+        let dir = TestDir::new("prompt_pack_lite_import_graph_ts");
+        dir.write("src/index.ts", "import { helper } from './helper';\nimport React from 'react';\n");
+        dir.write("src/helper.ts", "export function helper() {}\n");
+
+        let result = build_import_graph(dir.path.as_path(), &["src/index.ts".to_string()], None);
+
+        assert_eq!(result.graph.get("src/index.ts"), Some(&vec!["src/helper.ts".to_string()]));
+        assert_eq!(result.unresolved.get("src/index.ts"), Some(&vec!["react".to_string()]));
+    }
+
+    #[test]
+    fn resolves_tsconfig_path_aliases() {
+        let dir = TestDir::new("prompt_pack_lite_import_graph_alias");
+        dir.write(
+            "tsconfig.json",
+            r#"{ "compilerOptions": { "baseUrl": ".", "paths": { "@/*": ["src/*"] } } }"#,
+        );
+        dir.write("src/index.ts", "import { helper } from '@/utils/helper';\n");
+        dir.write("src/utils/helper.ts", "export function helper() {}\n");
+
+        let result = build_import_graph(dir.path.as_path(), &["src/index.ts".to_string()], None);
+
+        assert_eq!(result.graph.get("src/index.ts"), Some(&vec!["src/utils/helper.ts".to_string()]));
+    }
+
+    #[test]
+    fn resolves_python_relative_and_package_imports() {
+        let dir = TestDir::new("prompt_pack_lite_import_graph_py");
+        dir.write("pkg/__init__.py", "");
+        dir.write("pkg/main.py", "from . import utils\nfrom ..other import thing\nimport pkg.utils\n");
+        dir.write("pkg/utils.py", "");
+        dir.write("other.py", "");
+
+        let result = build_import_graph(dir.path.as_path(), &["pkg/main.py".to_string()], None);
+
+        let imports = result.graph.get("pkg/main.py").expect("pkg/main.py should resolve some imports");
+        assert!(imports.contains(&"pkg/utils.py".to_string()));
+        assert!(imports.contains(&"other.py".to_string()));
+    }
+
+    #[test]
+    fn resolves_rust_mod_declarations() {
+        let dir = TestDir::new("prompt_pack_lite_import_graph_rs");
+        dir.write("src/lib.rs", "mod helper;\nmod nested;\n");
+        dir.write("src/helper.rs", "");
+        dir.write("src/nested/mod.rs", "");
+
+        let result = build_import_graph(dir.path.as_path(), &["src/lib.rs".to_string()], None);
+
+        let imports = result.graph.get("src/lib.rs").expect("src/lib.rs should resolve its mods");
+        assert!(imports.contains(&"src/helper.rs".to_string()));
+        assert!(imports.contains(&"src/nested/mod.rs".to_string()));
+    }
+
+    #[test]
+    fn respects_max_depth() {
+        let dir = TestDir::new("prompt_pack_lite_import_graph_depth");
+        dir.write("a.ts", "import './b';\n");
+        dir.write("b.ts", "import './c';\n");
+        dir.write("c.ts", "export const x = 1;\n");
+
+        // max_depth: 1 hop from the entry means `a.ts` and its direct import
+        // `b.ts` both get their own imports extracted, but `c.ts` (2 hops
+        // away) is only recorded as a value under `b.ts`, not expanded.
+        let result = build_import_graph(dir.path.as_path(), &["a.ts".to_string()], Some(1));
+
+        assert!(result.graph.contains_key("a.ts"));
+        assert!(result.graph.contains_key("b.ts"));
+        assert!(!result.graph.contains_key("c.ts"));
+
+        let no_expansion = build_import_graph(dir.path.as_path(), &["a.ts".to_string()], Some(0));
+        assert!(no_expansion.graph.contains_key("a.ts"));
+        assert!(!no_expansion.graph.contains_key("b.ts"));
+    }
+}
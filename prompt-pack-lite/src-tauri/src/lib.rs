@@ -1,9 +1,9 @@
 use serde::{Serialize, Deserialize};
 use ignore::WalkBuilder;
-use std::collections::{HashSet, HashMap};
-use std::path::Path;
+use std::collections::{HashSet, HashMap, VecDeque, BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::time::{Duration, Instant, UNIX_EPOCH};
 use tauri::{State, Emitter, Manager};
 use notify::{Watcher, RecommendedWatcher, RecursiveMode, Event};
@@ -11,12 +11,22 @@ use tiktoken_rs::{cl100k_base, CoreBPE};
 use similar::{ChangeTag, TextDiff};
 use once_cell::sync::Lazy;
 use rayon::prelude::*;
+use tauri_plugin_clipboard_manager::ClipboardExt;
 
+mod cancellation;
+mod duplicates;
+mod import_graph;
+mod secrets;
+mod selection_rules;
 mod skeleton;
 mod skeleton_legacy;
 
+use cancellation::CancellationRegistry;
+
 #[cfg(test)]
 mod skeleton_tests;
+#[cfg(test)]
+mod testutils;
 
 // Initialize tokenizer once at startup to avoid blocking on first use
 static TOKENIZER: Lazy<CoreBPE> = Lazy::new(|| {
@@ -37,10 +47,95 @@ struct SkeletonCacheEntry {
     result: SkeletonResult,
 }
 
+#[derive(Clone, Copy)]
+struct LineCountCacheEntry {
+    file_size: u64,
+    modified_unix_nanos: u128,
+    line_count: usize,
+}
+
 static TOKEN_COUNT_CACHE: Lazy<Mutex<HashMap<String, TokenCacheEntry>>> =
     Lazy::new(|| Mutex::new(HashMap::new()));
 static SKELETON_CACHE: Lazy<Mutex<HashMap<String, SkeletonCacheEntry>>> =
     Lazy::new(|| Mutex::new(HashMap::new()));
+// Keyed by path, holding (size, mtime) alongside the counted lines so a
+// rescan after a watcher event only recounts files whose fingerprint
+// actually changed, instead of reopening every file on every scan.
+static LINE_COUNT_CACHE: Lazy<Mutex<HashMap<String, LineCountCacheEntry>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+// Insertion order for the skeleton cache, oldest first, so we can evict
+// beyond the size caps below without scanning modified times.
+static SKELETON_CACHE_ORDER: Lazy<Mutex<VecDeque<String>>> =
+    Lazy::new(|| Mutex::new(VecDeque::new()));
+
+const SKELETON_CACHE_MAX_ENTRIES: usize = 500;
+const SKELETON_CACHE_MAX_BYTES: usize = 50 * 1024 * 1024;
+
+/// Insert a skeleton cache entry, evicting the oldest entries once the
+/// entry-count or approximate byte-size cap is exceeded.
+fn skeleton_cache_insert(path: String, entry: SkeletonCacheEntry) {
+    let (Ok(mut cache), Ok(mut order)) = (SKELETON_CACHE.lock(), SKELETON_CACHE_ORDER.lock()) else {
+        return;
+    };
+
+    if cache.contains_key(&path) {
+        order.retain(|p| p != &path);
+    }
+    order.push_back(path.clone());
+    cache.insert(path, entry);
+
+    let mut total_bytes: usize = cache.values().map(|e| e.result.skeleton.len()).sum();
+    while cache.len() > SKELETON_CACHE_MAX_ENTRIES || total_bytes > SKELETON_CACHE_MAX_BYTES {
+        let Some(oldest) = order.pop_front() else { break };
+        if let Some(evicted) = cache.remove(&oldest) {
+            total_bytes = total_bytes.saturating_sub(evicted.result.skeleton.len());
+        }
+    }
+}
+
+/// Remove a single path from the skeleton cache, e.g. when the watcher
+/// reports it changed on disk.
+fn skeleton_cache_invalidate(path: &str) {
+    if let (Ok(mut cache), Ok(mut order)) = (SKELETON_CACHE.lock(), SKELETON_CACHE_ORDER.lock()) {
+        if cache.remove(path).is_some() {
+            order.retain(|p| p != path);
+        }
+    }
+}
+
+/// Drop a single path's cached line count, e.g. when the watcher reports it
+/// changed on disk. The fingerprint check in `cached_line_count` would catch
+/// a stale entry on its own, but a rename can reuse an inode's old (size,
+/// mtime) pair on some filesystems, so we invalidate eagerly instead of
+/// relying on that alone.
+fn line_count_cache_invalidate(path: &str) {
+    if let Ok(mut cache) = LINE_COUNT_CACHE.lock() {
+        cache.remove(path);
+    }
+}
+
+/// `count_lines`, but backed by `LINE_COUNT_CACHE`: a cache hit whose
+/// (size, mtime) fingerprint still matches the file on disk is returned
+/// without reopening it; anything else falls through to a fresh count,
+/// which is then cached under the current fingerprint.
+fn cached_line_count(path: &Path) -> Option<usize> {
+    let key = path.to_string_lossy().to_string();
+    let (size, modified_unix_nanos) = file_fingerprint(path)?;
+
+    if let Ok(cache) = LINE_COUNT_CACHE.lock() {
+        if let Some(entry) = cache.get(&key) {
+            if entry.file_size == size && entry.modified_unix_nanos == modified_unix_nanos {
+                return Some(entry.line_count);
+            }
+        }
+    }
+
+    let line_count = count_lines(path)?;
+    if let Ok(mut cache) = LINE_COUNT_CACHE.lock() {
+        cache.insert(key, LineCountCacheEntry { file_size: size, modified_unix_nanos, line_count });
+    }
+    Some(line_count)
+}
 
 const IGNORED_DIR_NAMES: &[&str] = &[
     "node_modules",
@@ -107,14 +202,124 @@ const IGNORED_FILE_SUFFIXES: &[&str] = &[
     ".log", ".map", ".cache", ".min.js", ".min.css", ".bak", ".lock", ".icns",
 ];
 
+/// Runtime-configurable ignore rules, seeded with the same defaults as the
+/// old `IGNORED_DIR_NAMES` / `IGNORED_FILE_NAMES` / `IGNORED_FILE_SUFFIXES`
+/// constants but adjustable at runtime via `get_ignore_config` /
+/// `set_ignore_config`, so a user who wants their vendored folder or `.csv`
+/// fixtures included isn't stuck with the built-in defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct IgnoreConfig {
+    dirs: Vec<String>,
+    file_names: Vec<String>,
+    file_suffixes: Vec<String>,
+}
+
+impl Default for IgnoreConfig {
+    fn default() -> Self {
+        Self {
+            dirs: IGNORED_DIR_NAMES.iter().map(|s| s.to_string()).collect(),
+            file_names: IGNORED_FILE_NAMES.iter().map(|s| s.to_string()).collect(),
+            file_suffixes: IGNORED_FILE_SUFFIXES.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+}
+
+/// The live ignore config, shared by `scan_project_entries`, the watcher
+/// callback, and the `get_ignore_config` / `set_ignore_config` commands —
+/// same "static `Lazy<Mutex<..>>`" shape as `SKELETON_CACHE`, since ignore
+/// rules are read from deep inside free functions that don't carry a
+/// `tauri::State` handle.
+static IGNORE_CONFIG: Lazy<Mutex<IgnoreConfig>> = Lazy::new(|| Mutex::new(IgnoreConfig::default()));
+
+/// Rejects ignore-rule entries that would blank out the whole tree, like an
+/// empty string (matches every name via `ends_with`/`contains`) or `/`
+/// (meaningless for a bare-name filter but easy to paste in by mistake).
+fn validate_ignore_config(config: &IgnoreConfig) -> Result<(), String> {
+    let all = config.dirs.iter()
+        .chain(config.file_names.iter())
+        .chain(config.file_suffixes.iter());
+    for entry in all {
+        if entry.trim().is_empty() || entry.trim() == "/" {
+            return Err(format!("Invalid ignore rule: {:?}", entry));
+        }
+    }
+    Ok(())
+}
+
+/// Where the ignore config is persisted: `ignore_config.json` in the app's
+/// config dir, created on first save.
+fn ignore_config_file_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let dir = app.path().app_config_dir().map_err(|e| e.to_string())?;
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir.join("ignore_config.json"))
+}
+
+/// Loads the persisted ignore config, falling back to defaults if none was
+/// ever saved.
+fn load_ignore_config_from_disk(app: &tauri::AppHandle) -> Result<IgnoreConfig, String> {
+    let path = ignore_config_file_path(app)?;
+    if !path.exists() {
+        return Ok(IgnoreConfig::default());
+    }
+    let content = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&content).map_err(|e| e.to_string())
+}
+
+fn save_ignore_config_to_disk(app: &tauri::AppHandle, config: &IgnoreConfig) -> Result<(), String> {
+    let path = ignore_config_file_path(app)?;
+    let json = serde_json::to_string_pretty(config).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| e.to_string())
+}
+
+/// Returns the current ignore config, so the frontend can show what's being
+/// skipped and let the user edit it.
+#[tauri::command]
+fn get_ignore_config() -> Result<IgnoreConfig, String> {
+    Ok(IGNORE_CONFIG.lock().map_err(|_| "Lock error")?.clone())
+}
+
+/// Replaces the ignore config, persists it to disk, and updates the live
+/// static so a rescan (of an already-selected project, no re-selection
+/// needed) immediately reflects the new rules.
+#[tauri::command]
+fn set_ignore_config(app: tauri::AppHandle, config: IgnoreConfig) -> Result<(), String> {
+    validate_ignore_config(&config)?;
+    save_ignore_config_to_disk(&app, &config)?;
+    *IGNORE_CONFIG.lock().map_err(|_| "Lock error")? = config;
+    Ok(())
+}
+
 struct WatcherState {
     watcher: Mutex<Option<RecommendedWatcher>>,
+    debounce_ms: Mutex<u64>,
+    roots: Mutex<Vec<String>>,
+}
+
+/// Default debounce window for `watch_project`, chosen to coalesce the
+/// burst of events a single save typically produces without feeling
+/// laggy to a human watching the UI update.
+const DEFAULT_WATCH_DEBOUNCE_MS: u64 = 500;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct WatchConfig {
+    debounce_ms: u64,
 }
 
 struct SnapshotState {
     snapshot: Mutex<HashMap<String, String>>,
 }
 
+struct LanguageOverrides {
+    overrides: Mutex<HashMap<String, skeleton::SupportedLanguage>>,
+}
+
+/// Lets the frontend cancel an in-flight `estimate_skeletons` call. Checked
+/// between files rather than tied to the Tauri command's own future, since
+/// the actual work happens inside a blocking rayon `par_iter`.
+struct SkeletonEstimateState {
+    cancelled: AtomicBool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 struct ScanMetrics {
     duration_ms: f64,
@@ -167,16 +372,92 @@ struct PerfMetricsState {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
-struct FileEntry {
-    path: String,
-    relative_path: String,
-    is_dir: bool,
+pub(crate) struct FileEntry {
+    pub(crate) path: String,
+    pub(crate) relative_path: String,
+    pub(crate) is_dir: bool,
+    is_symlink: bool,
     size: u64,
     line_count: Option<usize>,
+    content_hash: Option<String>,
+    git_status: Option<GitFileStatus>,
+    last_commit_epoch: Option<i64>,
+    /// Whether the first few KB of the file look machine-generated or
+    /// minified (an `@generated` header, or an unusually long average line
+    /// length), so the frontend can deselect it by default.
+    is_generated: bool,
+    /// Raw OS bytes of `path`, populated only when the name isn't valid
+    /// UTF-8 and `path` is therefore a lossy (`\u{fffd}`-substituted)
+    /// approximation of it. Callers that need to open the real file (e.g.
+    /// `read_file_content`) should prefer this over `path` when present.
+    path_bytes: Option<Vec<u8>>,
+    /// Set when the file's content isn't plain UTF-8 (e.g. `"UTF-16LE"` for
+    /// a BOM-prefixed or heuristically detected Windows-generated file), so
+    /// the frontend can flag it. `None` for the overwhelming common case.
+    detected_encoding: Option<String>,
+}
+
+/// `scan_project`'s return value. `cancelled` is set when the caller passed
+/// an `operation_id` that was cancelled mid-walk (via `cancel_operation`);
+/// `entries` still holds whatever roots finished before that happened,
+/// rather than being thrown away.
+#[derive(Debug, Serialize, Deserialize)]
+struct ScanResult {
+    entries: Vec<FileEntry>,
+    cancelled: bool,
+}
+
+/// Raw bytes of `path`'s file name, but only when `path.to_string_lossy()`
+/// doesn't round-trip back to the original bytes — i.e. only when the
+/// lossy string actually lost information. Unix-only: Windows paths are
+/// UTF-16 already and don't have this failure mode.
+fn path_bytes_if_lossy(path: &Path) -> Option<Vec<u8>> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        let raw = path.as_os_str().as_bytes();
+        if raw == path.to_string_lossy().as_bytes() {
+            None
+        } else {
+            Some(raw.to_vec())
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        None
+    }
+}
+
+/// User-supplied adjustments to the ignored-directory set, merged onto
+/// `IGNORED_DIR_NAMES`: `add` extends it (e.g. `"__generated__"`), `remove`
+/// carves out defaults the caller wants scanned after all (e.g. `"vendor"`).
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct IgnoredDirOverrides {
+    add: Vec<String>,
+    remove: Vec<String>,
+}
+
+/// Merges `overrides` onto the live `IGNORE_CONFIG.dirs` to produce the
+/// effective ignored-directory set for one scan. `remove` is applied before
+/// `add`, so a name present in both ends up ignored.
+fn effective_ignored_dirs(overrides: Option<&IgnoredDirOverrides>) -> HashSet<String> {
+    let mut dirs: HashSet<String> = IGNORE_CONFIG
+        .lock()
+        .map(|config| config.dirs.iter().cloned().collect())
+        .unwrap_or_default();
+    if let Some(overrides) = overrides {
+        for name in &overrides.remove {
+            dirs.remove(&name.to_lowercase());
+        }
+        for name in &overrides.add {
+            dirs.insert(name.to_lowercase());
+        }
+    }
+    dirs
 }
 
-fn is_ignored_dir(name_lower: &str, path: &Path) -> bool {
-    if IGNORED_DIR_NAMES.iter().any(|dir| dir == &name_lower) {
+fn is_ignored_dir(name_lower: &str, path: &Path, ignored_dirs: &HashSet<String>) -> bool {
+    if ignored_dirs.contains(name_lower) {
         return true;
     }
     if name_lower == "icons" && path_has_component(path, "src-tauri") {
@@ -195,19 +476,111 @@ fn path_has_component(path: &Path, component: &str) -> bool {
 }
 
 fn is_ignored_file(name_lower: &str) -> bool {
-    if IGNORED_FILE_NAMES.iter().any(|name| name == &name_lower) {
+    let Ok(config) = IGNORE_CONFIG.lock() else {
+        return false;
+    };
+    if config.file_names.iter().any(|name| name == name_lower) {
         return true;
     }
-    IGNORED_FILE_SUFFIXES.iter().any(|ext| name_lower.ends_with(ext))
+    config.file_suffixes.iter().any(|ext| name_lower.ends_with(ext.as_str()))
+}
+
+/// Whether `path` (relative to a watched root) falls under an ignored
+/// directory or matches an ignored file name/suffix, per the live
+/// `IGNORE_CONFIG` — used to keep watcher events from firing for changes
+/// inside e.g. `node_modules` the same way `scan_project_entries` already
+/// skips them.
+fn is_ignored_by_config(path: &Path) -> bool {
+    if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+        if is_ignored_file(&name.to_lowercase()) {
+            return true;
+        }
+    }
+    let ignored_dirs = effective_ignored_dirs(None);
+    path.components().any(|part| {
+        part.as_os_str()
+            .to_str()
+            .map(|s| ignored_dirs.contains(&s.to_lowercase()))
+            .unwrap_or(false)
+    })
+}
+
+/// Whether enough time has passed since the last emitted watch event to
+/// emit another one. Pulled out of the watcher callback so the debounce
+/// math is testable without spinning up a real filesystem watcher.
+fn debounce_elapsed(last_emit: Instant, debounce: Duration) -> bool {
+    last_emit.elapsed() >= debounce
+}
+
+/// Payload kind for a `project-files-changed` event. Distinct from
+/// [`ChangeKind`], which describes a git-diff status rather than a raw
+/// filesystem watch event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum FileChangeKind {
+    Created,
+    Modified,
+    Deleted,
+    Renamed,
+}
+
+/// One affected path from a `project-files-changed` event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct FileChange {
+    path: String,
+    kind: FileChangeKind,
+    /// Which watched root `path` falls under, so a multi-root workspace can
+    /// tell apart files that share a relative path across roots. `None` if
+    /// it doesn't fall under any currently-watched root (e.g. a stale event
+    /// racing a root's removal).
+    root: Option<String>,
+}
+
+/// Which watched root a changed path falls under, so a `FileChange` from a
+/// multi-root watch can be attributed correctly. Picks the longest matching
+/// root in case one watched root is nested inside another.
+fn root_for_changed_path(changed: &Path, roots: &[String]) -> Option<String> {
+    roots
+        .iter()
+        .filter(|root| changed.starts_with(Path::new(root.as_str())))
+        .max_by_key(|root| root.len())
+        .cloned()
+}
+
+/// Payload for a `project-lost` event: the watched root that disappeared
+/// out from under the watcher (deleted, or its drive unmounted/ejected).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ProjectLostPayload {
+    path: String,
+}
+
+/// Whether a `notify` error means the watched root itself is gone, rather
+/// than a transient per-event problem. Covers the case explicitly (where
+/// the platform backend reports it) and falls back to the wrapped I/O
+/// error's kind, since some backends surface root removal as a plain
+/// `NotFound`.
+fn is_root_gone_error(err: &notify::Error) -> bool {
+    match &err.kind {
+        notify::ErrorKind::PathNotFound => true,
+        notify::ErrorKind::Io(io_err) => io_err.kind() == std::io::ErrorKind::NotFound,
+        _ => false,
+    }
 }
 
-fn should_emit(event: &Event) -> bool {
+/// Whether a watch event is worth surfacing, and if so, which
+/// [`FileChangeKind`] it represents. Access and pure-metadata events are
+/// noise (e.g. an editor bumping mtime without touching content) and are
+/// filtered out.
+fn should_emit(event: &Event) -> Option<FileChangeKind> {
     use notify::event::ModifyKind;
 
     match event.kind {
-        notify::EventKind::Access(_) => false,
-        notify::EventKind::Modify(ModifyKind::Metadata(_)) => false,
-        _ => true,
+        notify::EventKind::Access(_) => None,
+        notify::EventKind::Modify(ModifyKind::Metadata(_)) => None,
+        notify::EventKind::Create(_) => Some(FileChangeKind::Created),
+        notify::EventKind::Remove(_) => Some(FileChangeKind::Deleted),
+        notify::EventKind::Modify(ModifyKind::Name(_)) => Some(FileChangeKind::Renamed),
+        _ => Some(FileChangeKind::Modified),
     }
 }
 
@@ -215,6 +588,330 @@ fn normalize_relative_path(relative: &Path) -> String {
     relative.to_string_lossy().replace('\\', "/")
 }
 
+/// Default cap on file size (in bytes) before we skip counting its lines
+/// during a scan, so large generated files don't freeze the walk.
+const DEFAULT_MAX_SCAN_FILE_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Data-ish extensions where a line count isn't meaningful, so we skip
+/// counting even if the caller raises the size threshold high enough to
+/// otherwise include them.
+const LINE_COUNT_SKIP_EXTENSIONS: &[&str] = &["csv", "tsv"];
+
+fn is_line_count_skipped(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|ext| LINE_COUNT_SKIP_EXTENSIONS.iter().any(|skip| skip.eq_ignore_ascii_case(ext)))
+        .unwrap_or(false)
+}
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16_LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16_BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+/// Strip a leading UTF-8 byte order mark, if present, so it doesn't show up
+/// as a stray character at the start of file content or line counts.
+fn strip_bom(bytes: &[u8]) -> &[u8] {
+    bytes.strip_prefix(UTF8_BOM).unwrap_or(bytes)
+}
+
+/// Windows tools sometimes emit UTF-16LE without a BOM. ASCII-range text
+/// encoded that way has a NUL byte after every other byte, which real UTF-8
+/// text essentially never does (NUL only appears there for the codepoint
+/// U+0000). Sampling `BINARY_SNIFF_BYTES` keeps this cheap for big files.
+fn looks_like_utf16le_without_bom(bytes: &[u8]) -> bool {
+    let sample = &bytes[..bytes.len().min(BINARY_SNIFF_BYTES)];
+    if sample.len() < 4 {
+        return false;
+    }
+    sample.iter().skip(1).step_by(2).all(|&b| b == 0) && sample.iter().step_by(2).any(|&b| b != 0)
+}
+
+/// Sniffs `bytes` for a byte-order mark or the UTF-16LE-without-BOM
+/// heuristic above, decoding to UTF-8 and reporting which encoding was
+/// used. Falls back to (lossy) UTF-8 when nothing looks like UTF-16 -
+/// covers the vast majority of files without running full charset
+/// detection on every read.
+fn decode_file_bytes(bytes: &[u8]) -> (String, &'static encoding_rs::Encoding) {
+    let guess = if looks_like_utf16le_without_bom(bytes) {
+        encoding_rs::UTF_16LE
+    } else {
+        encoding_rs::UTF_8
+    };
+    // `decode` sniffs for a BOM itself and uses the encoding it names
+    // instead of `guess` when one is present, so this also covers UTF-8,
+    // UTF-16LE, and UTF-16BE BOMs without any extra handling here.
+    let (decoded, encoding, _had_errors) = guess.decode(bytes);
+    (decoded.into_owned(), encoding)
+}
+
+/// Human-readable label for `read_whole_file`/`skeletonize_file`'s detected
+/// encoding, for `FileEntry::detected_encoding`. `None` for plain UTF-8
+/// (the overwhelming common case) so the field stays absent from most
+/// entries.
+fn detected_encoding_label(encoding: &'static encoding_rs::Encoding) -> Option<String> {
+    if encoding == encoding_rs::UTF_8 {
+        None
+    } else {
+        Some(encoding.name().to_string())
+    }
+}
+
+/// Count lines in a file the way `wc -l` would, without loading the whole
+/// file into memory as a `String` (source files may not even be valid UTF-8).
+fn count_lines(path: &Path) -> Option<usize> {
+    use std::io::Read;
+
+    let mut file = std::fs::File::open(path).ok()?;
+    let mut buf = [0u8; 8192];
+    let mut count = 0usize;
+    let mut saw_bytes = false;
+    let mut ended_with_newline = true;
+    let mut first_chunk = true;
+
+    loop {
+        let n = file.read(&mut buf).ok()?;
+        if n == 0 {
+            break;
+        }
+        let mut chunk = &buf[..n];
+        if first_chunk {
+            chunk = strip_bom(chunk);
+            first_chunk = false;
+        }
+        if chunk.is_empty() {
+            continue;
+        }
+        saw_bytes = true;
+        count += chunk.iter().filter(|&&b| b == b'\n').count();
+        ended_with_newline = chunk[chunk.len() - 1] == b'\n';
+    }
+
+    if saw_bytes && !ended_with_newline {
+        count += 1;
+    }
+
+    Some(count)
+}
+
+/// How much of a file to sample when checking for generated/minified
+/// content, so flagging a multi-megabyte bundle stays cheap.
+const GENERATED_FILE_SAMPLE_SIZE: usize = 8192;
+
+/// Average line length above which a file is treated as minified rather
+/// than hand-written source.
+const MINIFIED_AVG_LINE_LEN: usize = 500;
+
+/// Whether `sample` (typically the first few KB of a file) looks like
+/// machine-generated or minified content: an explicit `@generated`/
+/// `GENERATED` header, or an average line length far beyond anything a
+/// person would hand-write.
+fn looks_generated(sample: &[u8]) -> bool {
+    let text = String::from_utf8_lossy(sample);
+
+    if text.lines().take(5).any(|line| {
+        let line = line.trim_start_matches(['/', '#', '*', ' ', '\t']);
+        line.starts_with("@generated") || line.starts_with("GENERATED")
+    }) {
+        return true;
+    }
+
+    let line_count = text.lines().count();
+    if line_count == 0 {
+        return false;
+    }
+    let avg_line_len = text.len() / line_count;
+    avg_line_len > MINIFIED_AVG_LINE_LEN
+}
+
+/// Sample the first few KB of `path` and check whether it looks
+/// machine-generated or minified. Cheap by design: never reads more than
+/// `GENERATED_FILE_SAMPLE_SIZE` bytes.
+fn is_generated_file(path: &Path) -> bool {
+    use std::io::Read;
+
+    let Ok(mut file) = std::fs::File::open(path) else {
+        return false;
+    };
+    let mut buf = vec![0u8; GENERATED_FILE_SAMPLE_SIZE];
+    let Ok(n) = file.read(&mut buf) else {
+        return false;
+    };
+    looks_generated(&buf[..n])
+}
+
+/// Samples the first `BINARY_SNIFF_BYTES` of `path` to report a non-UTF-8
+/// encoding for `FileEntry::detected_encoding`, without reading (let alone
+/// transcoding) the whole file during a scan.
+fn scan_detected_encoding(path: &Path) -> Option<String> {
+    use std::io::Read;
+
+    let mut file = std::fs::File::open(path).ok()?;
+    let mut buf = vec![0u8; BINARY_SNIFF_BYTES];
+    let n = file.read(&mut buf).ok()?;
+    let (_content, encoding) = decode_file_bytes(&buf[..n]);
+    detected_encoding_label(encoding)
+}
+
+/// Cap on file size before we skip computing a content hash during a scan,
+/// so hashing a handful of huge generated files doesn't slow the walk down.
+const MAX_HASH_FILE_SIZE: u64 = 1024 * 1024;
+
+/// Compute a short (16 hex char) content hash for change detection,
+/// skipping files over `MAX_HASH_FILE_SIZE`.
+fn compute_content_hash(path: &Path, size: u64) -> Option<String> {
+    if size > MAX_HASH_FILE_SIZE {
+        return None;
+    }
+    let content = std::fs::read(path).ok()?;
+    Some(blake3::hash(&content).to_hex()[..16].to_string())
+}
+
+/// Cap on total time spent running `git log` per-file lookups during a
+/// single scan. Once exceeded, remaining files get `last_commit_epoch: None`
+/// instead of stalling the whole walk on a repo with deep history.
+const GIT_LOG_TIME_BUDGET: Duration = Duration::from_millis(800);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum GitFileStatus {
+    Modified,
+    Untracked,
+    Staged,
+    Clean,
+}
+
+/// Find the top-level directory of the git repository containing `path`, if
+/// any. Returns `None` outside a repo (including when `git` isn't installed).
+fn find_git_root(path: &Path) -> Option<PathBuf> {
+    let output = std::process::Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .arg("rev-parse")
+        .arg("--show-toplevel")
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let root = String::from_utf8(output.stdout).ok()?;
+    Some(PathBuf::from(root.trim()))
+}
+
+/// Parse `git status --porcelain=v1 -z` into a map of repo-relative path to
+/// status. Renames are recorded under their new path only, which is enough
+/// for a "what's dirty" annotation.
+fn git_status_map(git_root: &Path) -> HashMap<String, GitFileStatus> {
+    let mut map = HashMap::new();
+
+    let Ok(output) = std::process::Command::new("git")
+        .arg("-C")
+        .arg(git_root)
+        .arg("status")
+        .arg("--porcelain=v1")
+        .arg("--untracked-files=all")
+        .arg("-z")
+        .output()
+    else {
+        return map;
+    };
+    if !output.status.success() {
+        return map;
+    }
+
+    let raw = output.stdout;
+    let mut fields = raw.split(|&b| b == 0).filter(|f| !f.is_empty());
+    while let Some(entry) = fields.next() {
+        if entry.len() < 3 {
+            continue;
+        }
+        let index_status = entry[0];
+        let worktree_status = entry[1];
+        let path = String::from_utf8_lossy(&entry[3..]).to_string();
+
+        // Renames/copies carry an extra NUL-separated "from" path field.
+        if index_status == b'R' || index_status == b'C' {
+            fields.next();
+        }
+
+        let status = if index_status == b'?' && worktree_status == b'?' {
+            GitFileStatus::Untracked
+        } else if worktree_status != b' ' {
+            GitFileStatus::Modified
+        } else if index_status != b' ' {
+            GitFileStatus::Staged
+        } else {
+            GitFileStatus::Clean
+        };
+
+        map.insert(path, status);
+    }
+
+    map
+}
+
+/// Epoch seconds of the last commit touching `relative_path`, giving up once
+/// `deadline` passes so a large history doesn't stall the scan.
+fn last_commit_epoch(git_root: &Path, relative_path: &str, deadline: Instant) -> Option<i64> {
+    if Instant::now() >= deadline {
+        return None;
+    }
+    let output = std::process::Command::new("git")
+        .arg("-C")
+        .arg(git_root)
+        .arg("log")
+        .arg("-1")
+        .arg("--format=%ct")
+        .arg("--")
+        .arg(relative_path)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout).ok()?.trim().parse().ok()
+}
+
+/// Identity of the file a path resolves to, used both to detect cycles when
+/// following symlinked directories and to spot two directory entries that
+/// are really the same underlying file (e.g. a case-insensitive filesystem
+/// alias). On Unix this is the (device, inode) pair, which is stable even
+/// if the same target is reached through different paths; elsewhere we
+/// fall back to the canonicalized path.
+fn file_identity_key(path: &Path) -> Option<String> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        std::fs::metadata(path)
+            .ok()
+            .map(|m| format!("{}:{}", m.dev(), m.ino()))
+    }
+    #[cfg(not(unix))]
+    {
+        std::fs::canonicalize(path)
+            .ok()
+            .map(|p| p.to_string_lossy().to_string())
+    }
+}
+
+/// Drop later entries that resolve to the same underlying file as an
+/// earlier one, e.g. "Readme.md" and "README.md" both surfacing on a
+/// case-insensitive filesystem. Directories are left alone: two directory
+/// entries sharing an identity would mean one is a symlink, which is
+/// already reported on its own via `is_symlink`. `entries` must already be
+/// sorted so the kept survivor is deterministic.
+fn dedupe_file_identity_aliases(entries: &mut Vec<FileEntry>) {
+    let mut seen: HashSet<String> = HashSet::new();
+    entries.retain(|entry| {
+        if entry.is_dir {
+            return true;
+        }
+        match file_identity_key(Path::new(&entry.path)) {
+            Some(key) => seen.insert(key),
+            None => true,
+        }
+    });
+}
+
 fn file_fingerprint(path: &Path) -> Option<(u64, u128)> {
     let metadata = path.metadata().ok()?;
     let modified_unix_nanos = metadata
@@ -226,36 +923,124 @@ fn file_fingerprint(path: &Path) -> Option<(u64, u128)> {
     Some((metadata.len(), modified_unix_nanos))
 }
 
-fn scan_project_entries(path: &Path) -> Result<Vec<FileEntry>, String> {
+/// Non-fatal issues surfaced by a scan, alongside the file list itself.
+#[derive(Debug, Clone, Copy, Default)]
+struct ScanWarnings {
+    limit_hit: bool,
+    broken_symlinks: usize,
+    symlink_cycles: usize,
+}
+
+/// Returns the scanned entries along with any non-fatal warnings (the
+/// `max_files` limit being hit, broken symlinks skipped), so the caller
+/// can surface them to the user.
+///
+/// Symlink policy: by default (`follow_symlinks: false`), symlinked
+/// directories are not descended into — they're reported as a single
+/// entry with `is_symlink: true`, same as any other directory. When
+/// `follow_symlinks` is enabled, symlinked directories are followed, and
+/// their targets are tracked by canonical identity (`file_identity_key`)
+/// so a link cycle can't make the walk recurse forever. Useful for
+/// monorepos that symlink in shared packages, but since it can walk
+/// straight out of the project root into whatever large tree the link
+/// targets, callers should treat it as an opt-in, not a default.
+fn scan_project_entries(
+    path: &Path,
+    max_file_size: u64,
+    include_git_status: bool,
+    max_depth: Option<usize>,
+    max_files: Option<usize>,
+    min_size: Option<u64>,
+    max_size: Option<u64>,
+    follow_symlinks: Option<bool>,
+    ignored_dirs: Arc<HashSet<String>>,
+    include_lines: bool,
+) -> Result<(Vec<FileEntry>, ScanWarnings), String> {
     if !path.exists() {
         return Err("Path does not exist".to_string());
     }
 
+    if let (Some(min), Some(max)) = (min_size, max_size) {
+        if min > max {
+            return Err(format!(
+                "min_size ({}) must be <= max_size ({})",
+                min, max
+            ));
+        }
+    }
+
     let root = path.to_path_buf();
     let (tx, rx) = std::sync::mpsc::channel::<FileEntry>();
+    let file_counter = AtomicUsize::new(0);
+    let limit_hit = std::sync::atomic::AtomicBool::new(false);
+    let broken_symlinks = AtomicUsize::new(0);
+    let symlink_cycles = Arc::new(AtomicUsize::new(0));
+    let follow_links = follow_symlinks.unwrap_or(false);
+    let visited_links: Arc<Mutex<HashSet<String>>> = Arc::new(Mutex::new(HashSet::new()));
+
+    // Detect the repo once up front (if requested) so per-file annotation is
+    // a cheap map lookup instead of a git invocation per file.
+    let git_root = if include_git_status { find_git_root(&root) } else { None };
+    let status_map = git_root
+        .as_deref()
+        .map(git_status_map)
+        .unwrap_or_default();
+    let git_log_deadline = Instant::now() + GIT_LOG_TIME_BUDGET;
 
     let walker = WalkBuilder::new(&root)
         .standard_filters(true)
-        .filter_entry(|entry| {
-            let name = entry.file_name().to_string_lossy();
-            let name_lower = name.to_lowercase();
-            let is_dir = entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false);
+        .max_depth(max_depth)
+        .follow_links(follow_links)
+        .filter_entry({
+            let visited_links = Arc::clone(&visited_links);
+            let symlink_cycles = Arc::clone(&symlink_cycles);
+            let ignored_dirs = Arc::clone(&ignored_dirs);
+            move |entry| {
+                let name = entry.file_name().to_string_lossy();
+                let name_lower = name.to_lowercase();
+                let is_dir = entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false);
 
-            if is_dir {
-                return !is_ignored_dir(&name_lower, entry.path());
-            }
+                if is_dir {
+                    if is_ignored_dir(&name_lower, entry.path(), &ignored_dirs) {
+                        return false;
+                    }
 
-            if is_ignored_file(&name_lower) {
-                return false;
-            }
+                    // Break symlink cycles: once we've descended into a given
+                    // target (by device+inode / canonical path), don't do it
+                    // again via a different link.
+                    if follow_links && entry.path_is_symlink() {
+                        if let Some(key) = file_identity_key(entry.path()) {
+                            let already_visited = visited_links
+                                .lock()
+                                .map(|mut visited| !visited.insert(key))
+                                .unwrap_or(false);
+                            if already_visited {
+                                symlink_cycles.fetch_add(1, Ordering::SeqCst);
+                                return false;
+                            }
+                        }
+                    }
+
+                    return true;
+                }
+
+                if is_ignored_file(&name_lower) {
+                    return false;
+                }
 
-            !is_ignored_dir(&name_lower, entry.path())
+                !is_ignored_dir(&name_lower, entry.path(), &ignored_dirs)
+            }
         })
         .build_parallel();
 
     walker.run(|| {
         let tx = tx.clone();
         let root = root.clone();
+        let git_root = git_root.clone();
+        let status_map = &status_map;
+        let file_counter = &file_counter;
+        let limit_hit = &limit_hit;
+        let broken_symlinks = &broken_symlinks;
 
         Box::new(move |result| {
             match result {
@@ -266,15 +1051,98 @@ fn scan_project_entries(path: &Path) -> Result<Vec<FileEntry>, String> {
                     }
 
                     if let Ok(relative) = p.strip_prefix(&root) {
+                        let is_symlink = entry.path_is_symlink();
+                        let relative_str = normalize_relative_path(relative);
+
+                        // A symlink whose target no longer exists shows up here
+                        // as a metadata error; count it as a warning instead of
+                        // silently dropping the entry with no trace.
+                        if is_symlink && std::fs::metadata(p).is_err() {
+                            broken_symlinks.fetch_add(1, Ordering::SeqCst);
+                            return ignore::WalkState::Continue;
+                        }
+
                         let is_dir = p.is_dir();
                         let size = p.metadata().map(|m| m.len()).unwrap_or(0);
 
+                        // Submodules show up as a directory containing a `.git`
+                        // file (not a dir). Report the entry itself as clean
+                        // but don't descend into it.
+                        if is_dir && git_root.is_some() && p.join(".git").is_file() {
+                            let _ = tx.send(FileEntry {
+                                path: p.to_string_lossy().to_string(),
+                                relative_path: relative_str,
+                                is_dir,
+                                is_symlink,
+                                size,
+                                line_count: None,
+                                content_hash: None,
+                                git_status: Some(GitFileStatus::Clean),
+                                last_commit_epoch: None,
+                                is_generated: false,
+                                path_bytes: path_bytes_if_lossy(p),
+                                detected_encoding: None,
+                            });
+                            return ignore::WalkState::Skip;
+                        }
+
+                        // The file-count limit only applies to files, not
+                        // directories; check-and-increment atomically so
+                        // concurrent walker threads can't overshoot it.
+                        if !is_dir {
+                            if let Some(limit) = max_files {
+                                let allowed = file_counter
+                                    .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |count| {
+                                        if count < limit { Some(count + 1) } else { None }
+                                    })
+                                    .is_ok();
+                                if !allowed {
+                                    limit_hit.store(true, Ordering::SeqCst);
+                                    return ignore::WalkState::Quit;
+                                }
+                            }
+                        }
+
+                        let line_count = if is_dir || !include_lines {
+                            None
+                        } else if size > max_file_size {
+                            None
+                        } else if is_line_count_skipped(p) {
+                            None
+                        } else {
+                            cached_line_count(p)
+                        };
+
+                        let content_hash = if is_dir {
+                            None
+                        } else {
+                            compute_content_hash(p, size)
+                        };
+
+                        let is_generated = !is_dir && is_generated_file(p);
+                        let detected_encoding = if is_dir { None } else { scan_detected_encoding(p) };
+
+                        let (git_status, last_commit_epoch) = match (&git_root, is_dir) {
+                            (Some(git_root), false) => (
+                                Some(status_map.get(&relative_str).copied().unwrap_or(GitFileStatus::Clean)),
+                                last_commit_epoch(git_root, &relative_str, git_log_deadline),
+                            ),
+                            _ => (None, None),
+                        };
+
                         let _ = tx.send(FileEntry {
                             path: p.to_string_lossy().to_string(),
-                            relative_path: normalize_relative_path(relative),
+                            relative_path: relative_str,
                             is_dir,
+                            is_symlink,
                             size,
-                            line_count: None,
+                            line_count,
+                            content_hash,
+                            git_status,
+                            last_commit_epoch,
+                            is_generated,
+                            path_bytes: path_bytes_if_lossy(p),
+                            detected_encoding,
                         });
                     }
                 }
@@ -288,7 +1156,15 @@ fn scan_project_entries(path: &Path) -> Result<Vec<FileEntry>, String> {
     // Drop the original sender so the channel closes once all walker threads finish.
     drop(tx);
     let mut entries: Vec<FileEntry> = rx.into_iter().collect();
-	
+
+    // Size filtering only applies to files; directories are never dropped
+    // by this check, even if every file inside them is filtered out.
+    entries.retain(|entry| {
+        entry.is_dir
+            || (min_size.map_or(true, |min| entry.size >= min)
+                && max_size.map_or(true, |max| entry.size <= max))
+    });
+
     let mut keep_dirs: HashSet<String> = HashSet::new();
     for entry in entries.iter().filter(|e| !e.is_dir) {
         let mut current = Path::new(&entry.path).parent();
@@ -301,532 +1177,4565 @@ fn scan_project_entries(path: &Path) -> Result<Vec<FileEntry>, String> {
         }
     }
 
-    entries.retain(|entry| !entry.is_dir || keep_dirs.contains(&entry.path));
+    // Symlinked directories are exempt from the empty-dir prune below: we
+    // deliberately don't descend into them (unless `follow_symlinks` is on),
+    // so they'd never have a "kept child file" to justify their presence
+    // otherwise, even though they're a real entry worth reporting.
+    entries.retain(|entry| !entry.is_dir || entry.is_symlink || keep_dirs.contains(&entry.path));
     entries.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+    dedupe_file_identity_aliases(&mut entries);
 
-    Ok(entries)
+    Ok((
+        entries,
+        ScanWarnings {
+            limit_hit: limit_hit.load(Ordering::SeqCst),
+            broken_symlinks: broken_symlinks.load(Ordering::SeqCst),
+            symlink_cycles: symlink_cycles.load(Ordering::SeqCst),
+        },
+    ))
 }
 
-#[tauri::command]
-fn greet(name: &str) -> String {
-    format!("Hello, {}! You've been greeted from Rust!", name)
+/// Cheap aggregate stats about a project tree, for warning users before
+/// they trigger the more expensive full `scan_project`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct ScanStats {
+    total_files: usize,
+    total_dirs: usize,
+    total_bytes: u64,
+    by_extension: HashMap<String, (usize, u64)>,
+    ignored_count: usize,
 }
 
-#[tauri::command]
-async fn scan_project(path: String, perf: State<'_, PerfMetricsState>) -> Result<Vec<FileEntry>, String> {
-    let start = Instant::now();
-    let root_path = Path::new(&path);
-    let entries = scan_project_entries(root_path)?;
-
-    let file_count = entries.iter().filter(|e| !e.is_dir).count();
-    let dir_count = entries.iter().filter(|e| e.is_dir).count();
+enum ScanStatEvent {
+    File { size: u64, ext: Option<String> },
+    Dir,
+    Ignored,
+}
 
-    if let Ok(mut m) = perf.metrics.lock() {
-        m.scan = Some(ScanMetrics {
-            duration_ms: start.elapsed().as_secs_f64() * 1000.0,
-            file_count,
-            dir_count,
-        });
-        m.token_cache_size = TOKEN_COUNT_CACHE.lock().map(|c| c.len()).unwrap_or(0);
-        m.skeleton_cache_size = SKELETON_CACHE.lock().map(|c| c.len()).unwrap_or(0);
+/// Walk a project tree and aggregate counts/sizes without building the
+/// full `FileEntry` list or counting lines.
+fn scan_stats_entries(path: &Path) -> Result<ScanStats, String> {
+    if !path.exists() {
+        return Err("Path does not exist".to_string());
     }
 
-    Ok(entries)
-}
+    let root = path.to_path_buf();
+    let (tx, rx) = std::sync::mpsc::channel::<ScanStatEvent>();
+    let ignored_dirs = Arc::new(effective_ignored_dirs(None));
 
-#[tauri::command]
-async fn watch_project(
-    app: tauri::AppHandle,
-    path: String,
-    state: State<'_, WatcherState>,
-    perf: State<'_, PerfMetricsState>,
-) -> Result<(), String> {
-    let start = Instant::now();
-    let mut watcher_guard = state.watcher.lock().map_err(|_| "Failed to lock watcher state")?;
+    let walker = WalkBuilder::new(&root).standard_filters(true).build_parallel();
 
-    // Drop the old watcher before creating a new one.
-    let _ = watcher_guard.take();
+    walker.run(|| {
+        let tx = tx.clone();
+        let root = root.clone();
+        let ignored_dirs = Arc::clone(&ignored_dirs);
 
-    let debounce = Duration::from_millis(500);
-    let last_emit = Arc::new(Mutex::new(Instant::now()));
-    let last_emit_for_cb = last_emit.clone();
-    let app_handle = app.clone();
-    let mut watcher = notify::recommended_watcher(move |res: Result<Event, notify::Error>| {
-        match res {
-           Ok(event) => {
-               if !should_emit(&event) {
-                   return;
-               }
+        Box::new(move |result| {
+            match result {
+                Ok(entry) => {
+                    let p = entry.path();
+                    if p == root.as_path() {
+                        return ignore::WalkState::Continue;
+                    }
 
-               let mut last_emit = match last_emit_for_cb.lock() {
-                   Ok(guard) => guard,
-                   Err(poisoned) => poisoned.into_inner(),
-               };
-               if last_emit.elapsed() < debounce {
-                   return;
-               }
-               *last_emit = Instant::now();
-               let _ = app_handle.emit("project-change", ());
-           }
-           Err(e) => eprintln!("watch error: {:?}", e),
-        }
-    }).map_err(|e| e.to_string())?;
+                    let name = entry.file_name().to_string_lossy();
+                    let name_lower = name.to_lowercase();
+                    let is_dir = entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false);
 
-    // One recursive watcher on the root instead of one handle per directory.
-    watcher.watch(Path::new(&path), RecursiveMode::Recursive)
-        .map_err(|e| e.to_string())?;
+                    if is_dir {
+                        if is_ignored_dir(&name_lower, p, &ignored_dirs) {
+                            let _ = tx.send(ScanStatEvent::Ignored);
+                            return ignore::WalkState::Skip;
+                        }
+                        let _ = tx.send(ScanStatEvent::Dir);
+                        return ignore::WalkState::Continue;
+                    }
 
-    *watcher_guard = Some(watcher);
+                    if is_ignored_file(&name_lower) || is_ignored_dir(&name_lower, p, &ignored_dirs) {
+                        let _ = tx.send(ScanStatEvent::Ignored);
+                        return ignore::WalkState::Continue;
+                    }
 
-    if let Ok(mut m) = perf.metrics.lock() {
-        m.watch = Some(WatchMetrics {
-            duration_ms: start.elapsed().as_secs_f64() * 1000.0,
-            dirs_watched: 1,
-            used_cached_dirs: false,
-        });
+                    let size = p.metadata().map(|m| m.len()).unwrap_or(0);
+                    let ext = p.extension().and_then(|e| e.to_str()).map(|s| s.to_lowercase());
+                    let _ = tx.send(ScanStatEvent::File { size, ext });
+                }
+                Err(err) => eprintln!("Error walking path: {}", err),
+            }
+
+            ignore::WalkState::Continue
+        })
+    });
+
+    drop(tx);
+
+    let mut stats = ScanStats::default();
+    for event in rx {
+        match event {
+            ScanStatEvent::Dir => stats.total_dirs += 1,
+            ScanStatEvent::Ignored => stats.ignored_count += 1,
+            ScanStatEvent::File { size, ext } => {
+                stats.total_files += 1;
+                stats.total_bytes += size;
+                let key = ext.unwrap_or_default();
+                let counter = stats.by_extension.entry(key).or_insert((0, 0));
+                counter.0 += 1;
+                counter.1 += size;
+            }
+        }
     }
 
-    Ok(())
+    Ok(stats)
 }
 
+/// Dry-run scan: report what a full `scan_project` would find without
+/// building the file list, so the UI can warn before an expensive scan.
 #[tauri::command]
-async fn read_file_content(path: String) -> Result<String, String> {
-    std::fs::read_to_string(path).map_err(|e| e.to_string())
+async fn scan_stats(path: String) -> Result<ScanStats, String> {
+    let root_path = Path::new(&path);
+    scan_stats_entries(root_path)
 }
 
-/// Result of skeleton extraction, returned to frontend
-#[derive(Debug, Serialize, Deserialize, Clone)]
-struct SkeletonResult {
-    skeleton: String,
-    language: Option<String>,
-    original_lines: usize,
-    skeleton_lines: usize,
-    compression_ratio: f32,
+/// Line counts for a specific list of paths, computed independently of a
+/// full `scan_project` walk (and served from `LINE_COUNT_CACHE` when a
+/// path's fingerprint hasn't changed). The counterpart to `scan_project`'s
+/// `include_lines: false` fast pass: the frontend does a structural-only
+/// scan first, then calls this for the files a user actually expands into
+/// view. Applies the same size cap and skip-extension rules as a normal
+/// scan, so a caller can't force-count a file the scan itself would skip.
+#[tauri::command]
+async fn count_lines_for(
+    paths: Vec<String>,
+    max_file_size: Option<u64>,
+) -> Result<HashMap<String, Option<usize>>, String> {
+    let max_file_size = max_file_size.unwrap_or(DEFAULT_MAX_SCAN_FILE_SIZE);
+
+    Ok(paths
+        .par_iter()
+        .map(|path| {
+            let p = Path::new(path);
+            let size = p.metadata().map(|m| m.len()).unwrap_or(0);
+            let line_count = if size > max_file_size || is_line_count_skipped(p) {
+                None
+            } else {
+                cached_line_count(p)
+            };
+            (path.clone(), line_count)
+        })
+        .collect())
 }
 
-/// Skeletonize a file using AST-based extraction
-/// Returns structural signatures (imports, types, function signatures) without implementation details
 #[tauri::command]
-async fn skeletonize_file(path: String, perf: State<'_, PerfMetricsState>) -> Result<SkeletonResult, String> {
-    let start = Instant::now();
-    let mut cache_hit = false;
+fn greet(name: &str) -> String {
+    format!("Hello, {}! You've been greeted from Rust!", name)
+}
 
-    let fingerprint = file_fingerprint(Path::new(&path));
-    if let Some((file_size, modified_unix_nanos)) = fingerprint {
-        let cached = SKELETON_CACHE
-            .lock()
-            .ok()
-            .and_then(|cache| cache.get(&path).cloned());
-
-        if let Some(entry) = cached {
-            if entry.file_size == file_size && entry.modified_unix_nanos == modified_unix_nanos {
-                cache_hit = true;
-                if let Ok(mut m) = perf.metrics.lock() {
-                    m.skeleton_file = Some(SkeletonFileMetrics {
-                        duration_ms: start.elapsed().as_secs_f64() * 1000.0,
-                        cache_hit,
-                    });
-                    m.skeleton_cache_size = SKELETON_CACHE.lock().map(|c| c.len()).unwrap_or(0);
-                }
-                return Ok(entry.result);
+/// Derives a short label per root for a multi-root scan, from each root's
+/// final path component (e.g. `"lib"` for `/home/x/lib`), so
+/// `FileEntry::relative_path` can be prefixed as `"lib//src/..."` to
+/// disambiguate files that share a relative path across roots. Collisions
+/// (two roots with the same final component) are disambiguated with a
+/// numeric suffix.
+fn root_labels(roots: &[String]) -> Vec<String> {
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    roots
+        .iter()
+        .map(|root| {
+            let base = Path::new(root)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| root.clone());
+            let count = seen.entry(base.clone()).or_insert(0);
+            *count += 1;
+            if *count == 1 {
+                base
+            } else {
+                format!("{}-{}", base, count)
             }
-        }
-    }
-
-    // Read the file content
-    let content = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
-
-    // Extract file extension
-    let extension = Path::new(&path)
-        .extension()
-        .and_then(|e| e.to_str())
-        .unwrap_or("");
+        })
+        .collect()
+}
 
-    // Run skeletonization
-    let result = skeleton::skeletonize_with_path(&content, extension, Some(&path));
+#[tauri::command]
+async fn scan_project(
+    app: tauri::AppHandle,
+    path: String,
+    roots: Option<Vec<String>>,
+    max_file_size: Option<u64>,
+    include_git_status: Option<bool>,
+    max_depth: Option<usize>,
+    max_files: Option<usize>,
+    min_size: Option<u64>,
+    max_size: Option<u64>,
+    follow_symlinks: Option<bool>,
+    ignored_dirs: Option<IgnoredDirOverrides>,
+    // Whether to count lines during this scan. Defaults to `true` so
+    // existing callers keep their current behavior; the frontend can pass
+    // `false` for a fast structural-only pass and fill in line counts for
+    // visible folders afterwards via `count_lines_for`.
+    include_lines: Option<bool>,
+    operation_id: Option<String>,
+    perf: State<'_, PerfMetricsState>,
+    cancellation: State<'_, CancellationRegistry>,
+) -> Result<ScanResult, String> {
+    let start = Instant::now();
+    let ignored_dirs = Arc::new(effective_ignored_dirs(ignored_dirs.as_ref()));
+    let include_lines = include_lines.unwrap_or(true);
+    let token = operation_id.as_deref().map(|id| cancellation.register(id));
 
-    // Calculate compression ratio
-    let original_chars = content.len() as f32;
-    let skeleton_chars = result.skeleton.len() as f32;
-    let compression_ratio = if original_chars > 0.0 {
-        1.0 - (skeleton_chars / original_chars)
-    } else {
-        0.0
+    // A single explicit root behaves exactly as before (no label prefix); a
+    // `roots` list of more than one entry is a multi-root workspace scan.
+    let root_list = match &roots {
+        Some(rs) if !rs.is_empty() => rs.clone(),
+        _ => vec![path.clone()],
     };
+    let multi_root = root_list.len() > 1;
+    let labels = root_labels(&root_list);
 
-    let skeleton_result = SkeletonResult {
-        skeleton: result.skeleton,
-        language: result.language.map(|l| format!("{:?}", l)),
-        original_lines: result.original_lines,
-        skeleton_lines: result.skeleton_lines,
-        compression_ratio,
-    };
+    let mut entries = Vec::new();
+    let mut warnings = ScanWarnings::default();
+    let mut cancelled = false;
+    for (root_str, label) in root_list.iter().zip(labels.iter()) {
+        if token.as_ref().is_some_and(|t| t.load(Ordering::SeqCst)) {
+            cancelled = true;
+            break;
+        }
 
-    if let Some((file_size, modified_unix_nanos)) = fingerprint {
-        if let Ok(mut cache) = SKELETON_CACHE.lock() {
-            cache.insert(
-                path,
-                SkeletonCacheEntry {
-                    file_size,
-                    modified_unix_nanos,
-                    result: skeleton_result.clone(),
-                },
-            );
+        let (mut root_entries, root_warnings) = scan_project_entries(
+            Path::new(root_str),
+            max_file_size.unwrap_or(DEFAULT_MAX_SCAN_FILE_SIZE),
+            include_git_status.unwrap_or(false),
+            max_depth,
+            max_files,
+            min_size,
+            max_size,
+            follow_symlinks,
+            Arc::clone(&ignored_dirs),
+            include_lines,
+        )?;
+
+        if multi_root {
+            for entry in &mut root_entries {
+                entry.relative_path = format!("{}//{}", label, entry.relative_path);
+            }
         }
+
+        entries.append(&mut root_entries);
+        warnings.limit_hit |= root_warnings.limit_hit;
+        warnings.broken_symlinks += root_warnings.broken_symlinks;
+        warnings.symlink_cycles += root_warnings.symlink_cycles;
+    }
+
+    if warnings.limit_hit {
+        let _ = app.emit("scan-warning", "max_files limit reached");
     }
+    if warnings.broken_symlinks > 0 {
+        let _ = app.emit(
+            "scan-warning",
+            format!("{} broken symlink(s) skipped", warnings.broken_symlinks),
+        );
+    }
+    if warnings.symlink_cycles > 0 {
+        let _ = app.emit(
+            "scan-warning",
+            format!("{} symlink cycle(s) detected and skipped", warnings.symlink_cycles),
+        );
+    }
+
+    let file_count = entries.iter().filter(|e| !e.is_dir).count();
+    let dir_count = entries.iter().filter(|e| e.is_dir).count();
 
     if let Ok(mut m) = perf.metrics.lock() {
-        m.skeleton_file = Some(SkeletonFileMetrics {
+        m.scan = Some(ScanMetrics {
             duration_ms: start.elapsed().as_secs_f64() * 1000.0,
-            cache_hit,
+            file_count,
+            dir_count,
         });
+        m.token_cache_size = TOKEN_COUNT_CACHE.lock().map(|c| c.len()).unwrap_or(0);
         m.skeleton_cache_size = SKELETON_CACHE.lock().map(|c| c.len()).unwrap_or(0);
     }
 
-    Ok(skeleton_result)
+    if let Some(id) = operation_id.as_deref() {
+        cancellation.unregister(id);
+    }
+
+    Ok(ScanResult { entries, cancelled })
 }
 
-/// Batch skeletonize multiple files at once for efficiency
+/// Number of entries per `scan-batch` event emitted by `scan_project_streaming`.
+const SCAN_STREAM_BATCH_SIZE: usize = 200;
+
+/// Same walk as `scan_project`, but delivered as a series of `scan-batch`
+/// events (followed by `scan-complete`) instead of one large return value,
+/// so the UI can start showing files before the whole tree finishes walking.
 #[tauri::command]
-async fn skeletonize_files(paths: Vec<String>, perf: State<'_, PerfMetricsState>) -> Result<Vec<Result<SkeletonResult, String>>, String> {
-    let start = Instant::now();
-    let files_processed = paths.len();
-    let hit_counter = AtomicUsize::new(0);
+async fn scan_project_streaming(
+    app: tauri::AppHandle,
+    path: String,
+    max_file_size: Option<u64>,
+    include_git_status: Option<bool>,
+    max_depth: Option<usize>,
+    include_lines: Option<bool>,
+) -> Result<usize, String> {
+    let root_path = Path::new(&path);
+    let (entries, _warnings) = scan_project_entries(
+        root_path,
+        max_file_size.unwrap_or(DEFAULT_MAX_SCAN_FILE_SIZE),
+        include_git_status.unwrap_or(false),
+        max_depth,
+        None,
+        None,
+        None,
+        None,
+        Arc::new(effective_ignored_dirs(None)),
+        include_lines.unwrap_or(true),
+    )?;
 
-    let results: Vec<Result<SkeletonResult, String>> = paths.into_par_iter().map(|p| {
-        let fingerprint = file_fingerprint(Path::new(&p));
-        if let Some((file_size, modified_unix_nanos)) = fingerprint {
-            let cached = SKELETON_CACHE
-                .lock()
-                .ok()
-                .and_then(|cache| cache.get(&p).cloned());
+    let total = entries.len();
+    for batch in entries.chunks(SCAN_STREAM_BATCH_SIZE) {
+        app.emit("scan-batch", batch).map_err(|e| e.to_string())?;
+    }
+    app.emit("scan-complete", total).map_err(|e| e.to_string())?;
 
-            if let Some(entry) = cached {
-                if entry.file_size == file_size && entry.modified_unix_nanos == modified_unix_nanos {
-                    hit_counter.fetch_add(1, Ordering::Relaxed);
-                    return Ok(entry.result);
-                }
-            }
+    Ok(total)
+}
+
+/// Per-extension totals within a single directory, as reported by
+/// `compute_dir_stats`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct ExtensionDirStats {
+    files: usize,
+    lines: usize,
+}
+
+/// Aggregate totals for a single directory (including its subdirectories),
+/// as reported by `compute_dir_stats`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct DirStats {
+    files: usize,
+    lines: usize,
+    bytes: u64,
+    by_extension: HashMap<String, ExtensionDirStats>,
+}
+
+/// Every ancestor directory of `relative_path`, nearest-first, with the
+/// project root itself represented as an empty string.
+fn ancestor_dirs(relative_path: &str) -> Vec<String> {
+    let mut dirs = Vec::new();
+    let mut current = relative_path;
+    while let Some(idx) = current.rfind('/') {
+        current = &current[..idx];
+        dirs.push(current.to_string());
+    }
+    dirs.push(String::new());
+    dirs
+}
+
+/// Bottom-up per-directory rollup of an already-scanned `FileEntry` list.
+/// Reuses the line counts computed during the scan instead of re-reading
+/// files; entries whose `line_count` is `None` (skipped for being too
+/// large) contribute to `bytes` only.
+fn compute_dir_stats_from_entries(entries: &[FileEntry]) -> HashMap<String, DirStats> {
+    let mut by_dir: HashMap<String, DirStats> = HashMap::new();
+
+    for entry in entries {
+        if entry.is_dir {
+            continue;
         }
 
-        let content = std::fs::read_to_string(&p).map_err(|e| e.to_string())?;
-         let extension = Path::new(&p)
+        let ext = Path::new(&entry.relative_path)
             .extension()
             .and_then(|e| e.to_str())
-            .unwrap_or("");
-        let result = skeleton::skeletonize_with_path(&content, extension, Some(&p));
-
-        let original_chars = content.len() as f32;
-        let skeleton_chars = result.skeleton.len() as f32;
-        let compression_ratio = if original_chars > 0.0 {
-            1.0 - (skeleton_chars / original_chars)
-        } else {
-            0.0
-        };
+            .map(|s| s.to_lowercase())
+            .unwrap_or_default();
+        let lines = entry.line_count.unwrap_or(0);
 
-        let skeleton_result = SkeletonResult {
-            skeleton: result.skeleton,
-            language: result.language.map(|l| format!("{:?}", l)),
-            original_lines: result.original_lines,
-            skeleton_lines: result.skeleton_lines,
-            compression_ratio,
-        };
+        for dir in ancestor_dirs(&entry.relative_path) {
+            let stats = by_dir.entry(dir).or_default();
+            stats.files += 1;
+            stats.bytes += entry.size;
+            stats.lines += lines;
 
-        if let Some((file_size, modified_unix_nanos)) = fingerprint {
-            if let Ok(mut cache) = SKELETON_CACHE.lock() {
-                cache.insert(
-                    p.clone(),
-                    SkeletonCacheEntry {
-                        file_size,
-                        modified_unix_nanos,
-                        result: skeleton_result.clone(),
-                    },
-                );
-            }
+            let ext_stats = stats.by_extension.entry(ext.clone()).or_default();
+            ext_stats.files += 1;
+            ext_stats.lines += lines;
         }
+    }
 
-        Ok(skeleton_result)
-    }).collect();
+    by_dir
+}
 
-    let cache_hits = hit_counter.load(Ordering::Relaxed);
-    if let Ok(mut m) = perf.metrics.lock() {
-        m.skeleton_batch = Some(SkeletonBatchMetrics {
-            duration_ms: start.elapsed().as_secs_f64() * 1000.0,
-            files_processed,
-            cache_hits,
-            cache_misses: files_processed - cache_hits,
-        });
-        m.skeleton_cache_size = SKELETON_CACHE.lock().map(|c| c.len()).unwrap_or(0);
+/// Per-folder totals (file count, line count, byte count, by-extension
+/// breakdown) computed from a previous `scan_project`/`scan_project_streaming`
+/// result, so the UI can decide what to include without re-scanning disk.
+#[tauri::command]
+fn compute_dir_stats(entries: Vec<FileEntry>) -> HashMap<String, DirStats> {
+    compute_dir_stats_from_entries(&entries)
+}
+
+/// Folders with more direct children than this are truncated with a
+/// "... and N more" marker instead of listing every entry.
+const MAX_TREE_ENTRIES_PER_DIR: usize = 200;
+/// Nesting deeper than this collapses to a single "..." line.
+const MAX_TREE_DEPTH: usize = 32;
+
+/// One level of a directory tree, built from a flat list of relative paths.
+#[derive(Default)]
+struct TreeNode {
+    dirs: BTreeMap<String, TreeNode>,
+    files: BTreeSet<String>,
+}
+
+impl TreeNode {
+    fn insert(&mut self, components: &[&str]) {
+        match components.split_first() {
+            Some((head, [])) => {
+                self.files.insert(head.to_string());
+            }
+            Some((head, rest)) => {
+                self.dirs.entry(head.to_string()).or_default().insert(rest);
+            }
+            None => {}
+        }
     }
+}
 
-    Ok(results)
+enum TreeChild<'a> {
+    Dir(&'a String),
+    File(&'a String),
 }
 
-/// Count tokens for given text using cl100k_base encoding (GPT-3.5/4 tokenizer)
-#[tauri::command]
-fn count_tokens(text: String) -> Result<usize, String> {
-    Ok(TOKENIZER.encode_with_special_tokens(&text).len())
+/// Strip a `root` prefix from `path` if present, so callers can pass either
+/// paths already relative to `root` or absolute ones.
+pub(crate) fn to_tree_relative(path: &str, root: &str) -> String {
+    let normalized = path.replace('\\', "/");
+    let root_normalized = root.replace('\\', "/");
+    normalized
+        .strip_prefix(root_normalized.as_str())
+        .map(|rest| rest.trim_start_matches('/').to_string())
+        .unwrap_or(normalized)
 }
 
-/// Count tokens for multiple file paths, reading content from disk
-#[tauri::command]
-async fn count_tokens_for_files(paths: Vec<String>, perf: State<'_, PerfMetricsState>) -> Result<usize, String> {
-    let start = Instant::now();
-    let files_processed = paths.len();
+/// Render `paths` (project-relative, `/`-separated) as an ASCII directory
+/// tree, directories sorted before files at each level.
+fn render_tree(paths: &[String]) -> String {
+    let mut root = TreeNode::default();
+    for path in paths {
+        let components: Vec<&str> = path.split('/').filter(|c| !c.is_empty()).collect();
+        root.insert(&components);
+    }
+    let mut output = String::new();
+    render_tree_node(&root, "", 0, &mut output);
+    output
+}
 
-    let results: Vec<(usize, Option<(String, TokenCacheEntry)>)> = paths
-        .par_iter()
-        .map(|path| {
-            let (file_size, modified_unix_nanos) = match file_fingerprint(Path::new(path)) {
-                Some(fingerprint) => fingerprint,
-                None => return (0, None),
-            };
+fn render_tree_node(node: &TreeNode, prefix: &str, depth: usize, output: &mut String) {
+    if depth >= MAX_TREE_DEPTH {
+        output.push_str(prefix);
+        output.push_str("└── ...\n");
+        return;
+    }
 
-            let cached = TOKEN_COUNT_CACHE
-                .lock()
-                .ok()
-                .and_then(|cache| cache.get(path).copied());
+    let mut children: Vec<TreeChild> = node.dirs.keys().map(TreeChild::Dir).collect();
+    children.extend(node.files.iter().map(TreeChild::File));
 
-            if let Some(entry) = cached {
-                if entry.file_size == file_size && entry.modified_unix_nanos == modified_unix_nanos {
-                    return (entry.token_count, None);
-                }
+    let total = children.len();
+    let truncated = total > MAX_TREE_ENTRIES_PER_DIR;
+    let shown = total.min(MAX_TREE_ENTRIES_PER_DIR);
+
+    for (index, child) in children.into_iter().take(shown).enumerate() {
+        let is_last = index == shown - 1 && !truncated;
+        match child {
+            TreeChild::Dir(name) => {
+                write_tree_line(output, prefix, is_last, name);
+                let child_prefix = format!("{prefix}{}", if is_last { "    " } else { "│   " });
+                render_tree_node(&node.dirs[name], &child_prefix, depth + 1, output);
             }
+            TreeChild::File(name) => {
+                write_tree_line(output, prefix, is_last, name);
+            }
+        }
+    }
 
-            let content = match std::fs::read_to_string(path) {
-                Ok(content) => content,
-                Err(_) => return (0, None),
-            };
+    if truncated {
+        output.push_str(prefix);
+        output.push_str(&format!("└── ... and {} more\n", total - shown));
+    }
+}
 
-            let token_count = TOKENIZER.encode_with_special_tokens(&content).len();
+fn write_tree_line(output: &mut String, prefix: &str, is_last: bool, name: &str) {
+    output.push_str(prefix);
+    output.push_str(if is_last { "└── " } else { "├── " });
+    output.push_str(name);
+    output.push('\n');
+}
 
-            (
-                token_count,
-                Some((
-                    path.clone(),
-                    TokenCacheEntry {
-                        file_size,
-                        modified_unix_nanos,
-                        token_count,
-                    },
-                )),
-            )
-        })
-        .collect();
+/// Render the selected `paths` (relative to `root`, or absolute under it) as
+/// an ASCII directory tree for a quick "what am I packing" overview. Kept
+/// separate from `scan_project` so the frontend can render a tree of just
+/// the selected subset.
+#[tauri::command]
+fn build_tree(paths: Vec<String>, root: String) -> String {
+    let relative: Vec<String> = paths.iter().map(|p| to_tree_relative(p, &root)).collect();
+    render_tree(&relative)
+}
 
-    let total = results
+/// Options controlling `render_file_tree`'s output.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct FileTreeOptions {
+    /// Cap nesting depth; deeper directories collapse to a single "..." line.
+    /// Defaults to `MAX_TREE_DEPTH` when absent.
+    max_depth: Option<usize>,
+    /// Paths (relative to `root`, or absolute under it) that will be sent as
+    /// skeletons rather than full content, annotated with a "[skeleton]"
+    /// marker.
+    skeleton_paths: Vec<String>,
+    /// When true and `selected_paths` is non-empty, sibling files inside a
+    /// selected directory that weren't themselves selected are collapsed
+    /// into a single "… (N more files)" line instead of being listed
+    /// individually.
+    collapse_unselected_siblings: bool,
+}
+
+/// Per-file data carried by an `AnnotatedTreeNode` leaf.
+struct FileAnnotation {
+    line_count: Option<usize>,
+    is_skeleton: bool,
+    selected: bool,
+}
+
+/// Like `TreeNode`, but each file leaf carries a `FileAnnotation` so
+/// `render_file_tree` can print line counts, skeleton markers, and prune or
+/// collapse unselected branches.
+#[derive(Default)]
+struct AnnotatedTreeNode {
+    dirs: BTreeMap<String, AnnotatedTreeNode>,
+    files: BTreeMap<String, FileAnnotation>,
+}
+
+impl AnnotatedTreeNode {
+    fn insert(&mut self, components: &[&str], annotation: FileAnnotation) {
+        match components.split_first() {
+            Some((head, [])) => {
+                self.files.insert(head.to_string(), annotation);
+            }
+            Some((head, rest)) => {
+                self.dirs.entry(head.to_string()).or_default().insert(rest, annotation);
+            }
+            None => {}
+        }
+    }
+
+    /// Whether this subtree contains at least one selected file, so a
+    /// selection-filtered render can prune branches with nothing to show.
+    fn has_selected_descendant(&self) -> bool {
+        self.files.values().any(|f| f.selected) || self.dirs.values().any(|d| d.has_selected_descendant())
+    }
+}
+
+enum AnnotatedChild<'a> {
+    Dir(&'a String),
+    File(&'a String, &'a FileAnnotation),
+}
+
+/// Builds the display suffix for a file line: its line count (when known)
+/// and a skeleton marker (when it will be sent as a skeleton, not full
+/// content).
+fn file_annotation_suffix(annotation: &FileAnnotation) -> String {
+    let mut suffix = String::new();
+    if let Some(lines) = annotation.line_count {
+        suffix.push_str(&format!(" ({lines} line{})", if lines == 1 { "" } else { "s" }));
+    }
+    if annotation.is_skeleton {
+        suffix.push_str(" [skeleton]");
+    }
+    suffix
+}
+
+fn render_annotated_tree_node(
+    node: &AnnotatedTreeNode,
+    prefix: &str,
+    depth: usize,
+    options: &FileTreeOptions,
+    filtering: bool,
+    output: &mut String,
+) {
+    let max_depth = options.max_depth.unwrap_or(MAX_TREE_DEPTH);
+    if depth >= max_depth {
+        output.push_str(prefix);
+        output.push_str("└── ...\n");
+        return;
+    }
+
+    let dirs: Vec<AnnotatedChild> = node
+        .dirs
         .iter()
-        .map(|(token_count, _)| *token_count)
-        .sum::<usize>();
+        .filter(|(_, child)| !filtering || child.has_selected_descendant())
+        .map(|(name, _)| AnnotatedChild::Dir(name))
+        .collect();
 
-    let new_entries: Vec<(String, TokenCacheEntry)> =
-        results.into_iter().filter_map(|(_, entry)| entry).collect();
+    let (mut selected_files, unselected_files): (Vec<_>, Vec<_>) =
+        node.files.iter().partition(|(_, annotation)| annotation.selected);
+    let collapsed_count = if filtering && options.collapse_unselected_siblings {
+        unselected_files.len()
+    } else {
+        selected_files.extend(unselected_files);
+        0
+    };
 
-    let cache_misses = new_entries.len();
-    let cache_hits = files_processed - cache_misses;
+    let mut children = dirs;
+    children.extend(selected_files.into_iter().map(|(name, annotation)| AnnotatedChild::File(name, annotation)));
 
-    if !new_entries.is_empty() {
-        if let Ok(mut cache) = TOKEN_COUNT_CACHE.lock() {
-            cache.extend(new_entries);
+    let total = children.len() + usize::from(collapsed_count > 0);
+    let truncated = total > MAX_TREE_ENTRIES_PER_DIR;
+    let shown = children.len().min(MAX_TREE_ENTRIES_PER_DIR);
+    let last_shown_index = shown.saturating_sub(1);
+
+    for (index, child) in children.into_iter().take(shown).enumerate() {
+        let is_last = index == last_shown_index && collapsed_count == 0 && !truncated;
+        match child {
+            AnnotatedChild::Dir(name) => {
+                write_tree_line(output, prefix, is_last, name);
+                let child_prefix = format!("{prefix}{}", if is_last { "    " } else { "│   " });
+                render_annotated_tree_node(&node.dirs[name], &child_prefix, depth + 1, options, filtering, output);
+            }
+            AnnotatedChild::File(name, annotation) => {
+                let label = format!("{name}{}", file_annotation_suffix(annotation));
+                write_tree_line(output, prefix, is_last, &label);
+            }
         }
     }
 
-    if let Ok(mut m) = perf.metrics.lock() {
-        m.token_count = Some(TokenCountMetrics {
-            duration_ms: start.elapsed().as_secs_f64() * 1000.0,
-            files_processed,
-            cache_hits,
-            cache_misses,
-        });
-        m.token_cache_size = TOKEN_COUNT_CACHE.lock().map(|c| c.len()).unwrap_or(0);
+    if collapsed_count > 0 {
+        write_tree_line(output, prefix, !truncated, &format!("… ({collapsed_count} more files)"));
     }
 
-    Ok(total)
+    if truncated {
+        output.push_str(prefix);
+        output.push_str(&format!("└── ... and {} more\n", total - shown));
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct DiffLine {
-    #[serde(rename = "type")]
-    line_type: String,
-    line: String,
-    old_line_num: Option<usize>,
-    new_line_num: Option<usize>,
+/// Renders `entries` (from a prior `scan_project` call — this never re-walks
+/// the disk) as an annotated ASCII directory tree, so a generated prompt can
+/// show the model project structure before file contents. When
+/// `selected_paths` is empty, the whole project is shown; otherwise only the
+/// selected files and their ancestor directories are shown, with unselected
+/// siblings optionally collapsed per `options.collapse_unselected_siblings`.
+#[tauri::command]
+fn render_file_tree(
+    entries: Vec<FileEntry>,
+    root: String,
+    selected_paths: Vec<String>,
+    options: Option<FileTreeOptions>,
+) -> String {
+    let options = options.unwrap_or_default();
+    let skeleton_set: HashSet<String> =
+        options.skeleton_paths.iter().map(|p| to_tree_relative(p, &root)).collect();
+    let selected_set: HashSet<String> = selected_paths.iter().map(|p| to_tree_relative(p, &root)).collect();
+    let filtering = !selected_set.is_empty();
+
+    let mut tree = AnnotatedTreeNode::default();
+    for entry in entries.iter().filter(|e| !e.is_dir) {
+        let relative = to_tree_relative(&entry.path, &root);
+        let components: Vec<&str> = relative.split('/').filter(|c| !c.is_empty()).collect();
+        if components.is_empty() {
+            continue;
+        }
+        let annotation = FileAnnotation {
+            line_count: entry.line_count,
+            is_skeleton: skeleton_set.contains(&relative),
+            selected: !filtering || selected_set.contains(&relative),
+        };
+        tree.insert(&components, annotation);
+    }
+
+    let mut output = String::new();
+    render_annotated_tree_node(&tree, "", 0, &options, filtering, &mut output);
+    output
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct FileDiff {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ChangeKind {
+    Added,
+    Modified,
+    Deleted,
+    Renamed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ChangedFile {
     path: String,
-    relative_path: String,
-    previous: String,
-    current: String,
-    diff: Vec<DiffLine>,
+    old_path: Option<String>,
+    kind: ChangeKind,
 }
 
-/// Take a snapshot of current file contents for diff comparison
-#[tauri::command]
-async fn take_snapshot(paths: Vec<String>, state: State<'_, SnapshotState>) -> Result<usize, String> {
-    let mut snapshot = state.snapshot.lock().map_err(|_| "Lock error")?;
-    snapshot.clear();
-    
-    for path in &paths {
-        if let Ok(content) = std::fs::read_to_string(path) {
-            snapshot.insert(path.clone(), content);
+/// Pick a sensible default base ref when the caller doesn't specify one,
+/// preferring `main` and falling back to `master`.
+fn autodetect_base_ref(git_root: &Path) -> String {
+    for candidate in ["main", "master"] {
+        let verified = std::process::Command::new("git")
+            .arg("-C")
+            .arg(git_root)
+            .arg("rev-parse")
+            .arg("--verify")
+            .arg("--quiet")
+            .arg(candidate)
+            .output()
+            .map(|out| out.status.success())
+            .unwrap_or(false);
+        if verified {
+            return candidate.to_string();
         }
     }
-    
-    Ok(snapshot.len())
+    "main".to_string()
 }
 
-/// Get diffs between snapshot and current file contents
+/// List everything changed on the current branch (committed, staged,
+/// unstaged, and untracked) relative to `base_ref`, so the frontend can pack
+/// a "review this branch" prompt in one click.
 #[tauri::command]
-async fn get_diffs(paths: Vec<String>, root_path: String, state: State<'_, SnapshotState>) -> Result<Vec<FileDiff>, String> {
-    let snapshot = state.snapshot.lock().map_err(|_| "Lock error")?;
-    let root = Path::new(&root_path);
-    let mut diffs = Vec::new();
-    
-    for path in paths {
-        let Some(prev_content) = snapshot.get(&path) else { continue };
-        let Ok(curr_content) = std::fs::read_to_string(&path) else { continue };
-        
-        if prev_content == &curr_content { continue; }
-        
-        let text_diff = TextDiff::from_lines(prev_content, &curr_content);
-        let mut diff_lines = Vec::new();
-        let mut old_line = 1usize;
-        let mut new_line = 1usize;
-        
-        for change in text_diff.iter_all_changes() {
-            let line = change.value().trim_end_matches('\n').to_string();
-            match change.tag() {
-                ChangeTag::Equal => {
-                    diff_lines.push(DiffLine { line_type: "unchanged".into(), line, old_line_num: Some(old_line), new_line_num: Some(new_line) });
-                    old_line += 1;
-                    new_line += 1;
+async fn list_changed_files(root: String, base_ref: Option<String>) -> Result<Vec<ChangedFile>, String> {
+    let root_path = Path::new(&root);
+    let git_root = find_git_root(root_path).ok_or_else(|| "Not a git repository".to_string())?;
+    let base = base_ref.unwrap_or_else(|| autodetect_base_ref(&git_root));
+
+    let diff_output = std::process::Command::new("git")
+        .arg("-C")
+        .arg(&git_root)
+        .arg("diff")
+        .arg("--name-status")
+        .arg("-M")
+        .arg(&base)
+        .output()
+        .map_err(|e| e.to_string())?;
+    if !diff_output.status.success() {
+        return Err(String::from_utf8_lossy(&diff_output.stderr).to_string());
+    }
+
+    let mut changes = Vec::new();
+    for line in String::from_utf8_lossy(&diff_output.stdout).lines() {
+        let mut fields = line.split('\t');
+        let Some(status) = fields.next() else { continue };
+        match status.chars().next() {
+            Some('A') => {
+                if let Some(path) = fields.next() {
+                    changes.push(ChangedFile { path: path.to_string(), old_path: None, kind: ChangeKind::Added });
                 }
-                ChangeTag::Delete => {
-                    diff_lines.push(DiffLine { line_type: "removed".into(), line, old_line_num: Some(old_line), new_line_num: None });
-                    old_line += 1;
+            }
+            Some('M') => {
+                if let Some(path) = fields.next() {
+                    changes.push(ChangedFile { path: path.to_string(), old_path: None, kind: ChangeKind::Modified });
                 }
-                ChangeTag::Insert => {
-                    diff_lines.push(DiffLine { line_type: "added".into(), line, old_line_num: None, new_line_num: Some(new_line) });
-                    new_line += 1;
+            }
+            Some('D') => {
+                if let Some(path) = fields.next() {
+                    changes.push(ChangedFile { path: path.to_string(), old_path: None, kind: ChangeKind::Deleted });
+                }
+            }
+            Some('R') => {
+                if let (Some(old_path), Some(path)) = (fields.next(), fields.next()) {
+                    changes.push(ChangedFile { path: path.to_string(), old_path: Some(old_path.to_string()), kind: ChangeKind::Renamed });
                 }
             }
+            _ => {}
         }
-        
-        let relative_path = Path::new(&path).strip_prefix(root)
-            .map(|p| p.to_string_lossy().replace('\\', "/"))
-            .unwrap_or_else(|_| path.clone());
-        
-        diffs.push(FileDiff {
-            path: path.clone(),
-            relative_path,
-            previous: prev_content.clone(),
-            current: curr_content,
-            diff: diff_lines,
-        });
     }
-    
-    Ok(diffs)
+
+    // `git diff` only covers tracked files; untracked new files count as
+    // "added" for review-pack purposes too.
+    let status_output = std::process::Command::new("git")
+        .arg("-C")
+        .arg(&git_root)
+        .arg("status")
+        .arg("--porcelain=v1")
+        .arg("--untracked-files=all")
+        .output()
+        .map_err(|e| e.to_string())?;
+    if status_output.status.success() {
+        for line in String::from_utf8_lossy(&status_output.stdout).lines() {
+            if let Some(path) = line.strip_prefix("?? ") {
+                changes.push(ChangedFile { path: path.to_string(), old_path: None, kind: ChangeKind::Added });
+            }
+        }
+    }
+
+    Ok(changes)
 }
 
+/// Build a "select this file plus everything it imports locally" graph,
+/// starting from `entry_paths` (relative to `root`) and following resolvable
+/// local imports up to `max_depth` hops. Specifiers that don't resolve to a
+/// project file (external packages, unknown aliases, ...) are reported
+/// separately rather than silently dropped.
 #[tauri::command]
-fn get_perf_metrics(perf: State<'_, PerfMetricsState>) -> PerfMetrics {
-    let mut metrics = perf.metrics.lock().map(|m| m.clone()).unwrap_or_default();
-    metrics.token_cache_size = TOKEN_COUNT_CACHE.lock().map(|c| c.len()).unwrap_or(0);
-    metrics.skeleton_cache_size = SKELETON_CACHE.lock().map(|c| c.len()).unwrap_or(0);
-    metrics
+async fn build_import_graph(
+    root: String,
+    entry_paths: Vec<String>,
+    max_depth: Option<usize>,
+) -> Result<import_graph::ImportGraphResult, String> {
+    Ok(import_graph::build_import_graph(Path::new(&root), &entry_paths, max_depth))
 }
 
-/// Clear the snapshot
 #[tauri::command]
-async fn clear_snapshot(state: State<'_, SnapshotState>) -> Result<(), String> {
-    let mut snapshot = state.snapshot.lock().map_err(|_| "Lock error")?;
-    snapshot.clear();
-    Ok(())
-}
+async fn watch_project(
+    app: tauri::AppHandle,
+    paths: Vec<String>,
+    debounce_ms: Option<u64>,
+    state: State<'_, WatcherState>,
+    perf: State<'_, PerfMetricsState>,
+) -> Result<(), String> {
+    let start = Instant::now();
+    let mut watcher_guard = state.watcher.lock().map_err(|_| "Failed to lock watcher state")?;
 
-#[cfg_attr(mobile, tauri::mobile_entry_point)]
+    // Drop the old watcher before creating a new one.
+    let _ = watcher_guard.take();
 
-pub fn run() {
-    // Force tokenizer initialization at startup (downloads vocab on first run)
-    let _ = &*TOKENIZER;
+    let debounce_ms = debounce_ms.unwrap_or(DEFAULT_WATCH_DEBOUNCE_MS);
+    if let Ok(mut stored) = state.debounce_ms.lock() {
+        *stored = debounce_ms;
+    }
+    let debounce = Duration::from_millis(debounce_ms);
+    let last_emit = Arc::new(Mutex::new(Instant::now()));
+    let last_emit_for_cb = last_emit.clone();
+    let pending_changes: Arc<Mutex<Vec<FileChange>>> = Arc::new(Mutex::new(Vec::new()));
+    let pending_changes_for_cb = pending_changes.clone();
+    let app_handle = app.clone();
+    let roots_for_cb = paths.clone();
+    let mut watcher = notify::recommended_watcher(move |res: Result<Event, notify::Error>| {
+        match res {
+           Ok(event) => {
+               let Some(kind) = should_emit(&event) else {
+                   return;
+               };
 
-    tauri::Builder::default()
+               let relevant_paths: Vec<&PathBuf> = event.paths.iter()
+                   .filter(|p| !is_ignored_by_config(p))
+                   .collect();
+               if relevant_paths.is_empty() {
+                   return;
+               }
 
-        .plugin(tauri_plugin_fs::init())
+               for changed in &relevant_paths {
+                   if let Some(key) = changed.to_str() {
+                       skeleton_cache_invalidate(key);
+                       line_count_cache_invalidate(key);
+                   }
+               }
 
-        .plugin(tauri_plugin_dialog::init())
+               if let Ok(mut pending) = pending_changes_for_cb.lock() {
+                   for changed in &relevant_paths {
+                       let root = root_for_changed_path(changed, &roots_for_cb);
+                       pending.push(FileChange { path: changed.to_string_lossy().to_string(), kind, root });
+                   }
+               }
 
-        .plugin(tauri_plugin_opener::init())
+               let mut last_emit = match last_emit_for_cb.lock() {
+                   Ok(guard) => guard,
+                   Err(poisoned) => poisoned.into_inner(),
+               };
+               if !debounce_elapsed(*last_emit, debounce) {
+                   return;
+               }
+               *last_emit = Instant::now();
 
-        .plugin(tauri_plugin_clipboard_manager::init())
+               // Kept for backward compatibility alongside the richer
+               // "project-files-changed" batch below.
+               let _ = app_handle.emit("project-change", ());
 
-        .setup(|app| {
+               let batch = match pending_changes_for_cb.lock() {
+                   Ok(mut guard) => std::mem::take(&mut *guard),
+                   Err(poisoned) => std::mem::take(&mut *poisoned.into_inner()),
+               };
+               if !batch.is_empty() {
+                   let _ = app_handle.emit("project-files-changed", batch);
+               }
+           }
+           Err(e) => {
+               if is_root_gone_error(&e) {
+                   // The root disappeared (deleted, or its drive was
+                   // unmounted) — the watcher will keep erroring on it
+                   // forever otherwise, so drop it entirely and let the
+                   // frontend know rather than spamming stderr.
+                   let paths = if e.paths.is_empty() {
+                       vec![String::new()]
+                   } else {
+                       e.paths.iter().map(|p| p.to_string_lossy().to_string()).collect()
+                   };
+                   for path in paths {
+                       let _ = app_handle.emit("project-lost", ProjectLostPayload { path });
+                   }
+                   // Dropping the watcher from within its own callback
+                   // thread can deadlock some backends (e.g. inotify
+                   // joining the thread it's running on), so defer the
+                   // teardown to a fresh thread instead.
+                   let app_handle_for_drop = app_handle.clone();
+                   std::thread::spawn(move || {
+                       if let Some(watcher_state) = app_handle_for_drop.try_state::<WatcherState>() {
+                           let _ = take_watcher(&watcher_state);
+                       }
+                   });
+               } else {
+                   eprintln!("watch error: {:?}", e);
+               }
+           }
+        }
+    }).map_err(|e| e.to_string())?;
 
-            app.manage(WatcherState { watcher: Mutex::new(None) });
-            app.manage(SnapshotState { snapshot: Mutex::new(HashMap::new()) });
-            app.manage(PerfMetricsState { metrics: Mutex::new(PerfMetrics::default()) });
+    // One shared watcher across all roots instead of one handle per directory,
+    // so debouncing stays global regardless of how many roots are watched.
+    for root in &paths {
+        watcher.watch(Path::new(root), RecursiveMode::Recursive)
+            .map_err(|e| e.to_string())?;
+    }
 
-            Ok(())
+    *watcher_guard = Some(watcher);
 
-        })
+    if let Ok(mut roots) = state.roots.lock() {
+        *roots = paths.clone();
+    }
 
-        .invoke_handler(tauri::generate_handler![greet, scan_project, read_file_content, watch_project, skeletonize_file, skeletonize_files, count_tokens, count_tokens_for_files, take_snapshot, get_diffs, clear_snapshot, get_perf_metrics])
+    if let Ok(mut m) = perf.metrics.lock() {
+        m.watch = Some(WatchMetrics {
+            duration_ms: start.elapsed().as_secs_f64() * 1000.0,
+            dirs_watched: paths.len(),
+            used_cached_dirs: false,
+        });
+    }
 
-        .run(tauri::generate_context!())
+    Ok(())
+}
 
-        .expect("error while running tauri application");
+#[tauri::command]
+fn get_watch_config(state: State<'_, WatcherState>) -> Result<WatchConfig, String> {
+    let debounce_ms = *state.debounce_ms.lock().map_err(|_| "Failed to lock watcher state")?;
+    Ok(WatchConfig { debounce_ms })
+}
 
+#[tauri::command]
+fn get_watched_roots(state: State<'_, WatcherState>) -> Result<Vec<String>, String> {
+    let roots = state.roots.lock().map_err(|_| "Failed to lock watcher state")?;
+    Ok(roots.clone())
 }
 
-#[cfg(test)]
-mod lib_tests {
-    use super::*;
-    use std::path::{Path, PathBuf};
-    use std::time::{SystemTime, UNIX_EPOCH};
+/// Drop the active watcher (if any), so subsequent filesystem changes emit
+/// no events until `watch_project` is called again. Kept as a plain
+/// function, separate from the `#[tauri::command]` wrapper, so it's callable
+/// from tests without needing a real `AppHandle`.
+fn take_watcher(state: &WatcherState) -> Result<(), String> {
+    let mut watcher_guard = state.watcher.lock().map_err(|_| "Failed to lock watcher state")?;
+    let _ = watcher_guard.take();
 
-    struct TestDir {
-        path: PathBuf,
+    if let Ok(mut roots) = state.roots.lock() {
+        roots.clear();
     }
 
-    impl TestDir {
-        fn new(prefix: &str) -> Self {
-            let mut path = std::env::temp_dir();
-            let now = SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap()
-                .as_nanos();
-            path.push(format!("{}_{}_{}", prefix, std::process::id(), now));
-            std::fs::create_dir_all(&path).unwrap();
-            Self { path }
-        }
+    Ok(())
+}
 
-        fn path(&self) -> &Path {
+/// Stop watching entirely by dropping the watcher, so subsequent filesystem
+/// changes emit no events until `watch_project` is called again.
+#[tauri::command]
+fn unwatch_project(state: State<'_, WatcherState>) -> Result<(), String> {
+    take_watcher(&state)
+}
+
+/// Explicit alias for `unwatch_project`, named for users who want to
+/// release the underlying OS watch handles before something like
+/// unmounting a removable drive, rather than "unwatching" a project.
+#[tauri::command]
+fn stop_watching(state: State<'_, WatcherState>) -> Result<(), String> {
+    take_watcher(&state)
+}
+
+/// Stop watching a single root without tearing down the whole watcher, so
+/// the remaining roots keep reporting changes. Kept as a plain function for
+/// the same reason as `take_watcher`.
+fn drop_watch_root(state: &WatcherState, path: &str) -> Result<(), String> {
+    let mut watcher_guard = state.watcher.lock().map_err(|_| "Failed to lock watcher state")?;
+    if let Some(watcher) = watcher_guard.as_mut() {
+        watcher.unwatch(Path::new(path)).map_err(|e| e.to_string())?;
+    }
+
+    if let Ok(mut roots) = state.roots.lock() {
+        roots.retain(|root| root != path);
+    }
+
+    Ok(())
+}
+
+/// Stop watching a single root without tearing down the whole watcher, so
+/// the remaining roots keep reporting changes.
+#[tauri::command]
+fn unwatch_path(path: String, state: State<'_, WatcherState>) -> Result<(), String> {
+    drop_watch_root(&state, &path)
+}
+
+/// Default cap on how much of a file `read_file_content` will load into
+/// memory when the caller doesn't request a specific byte/line range.
+const DEFAULT_MAX_READ_SIZE: u64 = 5 * 1024 * 1024;
+
+/// How many leading bytes to sniff for a NUL byte when deciding whether a
+/// file is binary.
+const BINARY_SNIFF_BYTES: usize = 8192;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum ReadFileError {
+    Binary { message: String },
+    TooLarge { message: String, size: u64, max_size: u64 },
+    Io { message: String },
+}
+
+/// Optional windowing for `read_file_content`, so the frontend can page
+/// through a large file instead of loading it all at once. Byte-range and
+/// line-range are mutually exclusive; byte-range takes precedence.
+#[derive(Debug, Deserialize)]
+struct FileReadRange {
+    offset: Option<u64>,
+    max_bytes: Option<u64>,
+    start_line: Option<usize>,
+    end_line: Option<usize>,
+}
+
+/// Resolve the real filesystem path for a command argument, preferring the
+/// raw OS bytes carried alongside a `FileEntry` (see `path_bytes_if_lossy`)
+/// over the lossy display string when both are given, so a non-UTF8
+/// filename round-trips correctly instead of 404ing.
+fn resolve_actual_path(path: &str, path_bytes: Option<&Vec<u8>>) -> std::path::PathBuf {
+    #[cfg(unix)]
+    {
+        if let Some(bytes) = path_bytes {
+            use std::os::unix::ffi::OsStringExt;
+            return std::path::PathBuf::from(std::ffi::OsString::from_vec(bytes.clone()));
+        }
+    }
+    let _ = path_bytes;
+    std::path::PathBuf::from(path)
+}
+
+/// Builds a `SkeletonOptions` override from the tauri command's optional
+/// call-edge limit and full-file-threshold params, or `None` when all are
+/// unset so the default limits (and the skeleton cache) apply unchanged.
+fn skeleton_options_from_params(
+    max_call_edge_names: Option<usize>,
+    max_call_edge_nodes: Option<usize>,
+    full_below_lines: Option<usize>,
+    config: Option<SkeletonConfig>,
+) -> Option<skeleton::SkeletonOptions> {
+    let config = config.unwrap_or_default();
+    if max_call_edge_names.is_none()
+        && max_call_edge_nodes.is_none()
+        && full_below_lines.is_none()
+        && config.timeout_ms.is_none()
+        && config.max_skeleton_lines.is_none()
+        && config.max_skeleton_chars.is_none()
+        && config.max_member_names.is_none()
+        && config.max_def_line_len.is_none()
+        && config.include_line_numbers.is_none()
+        && config.env_file_redaction_mode.is_none()
+        && config.force_legacy_js.is_none()
+    {
+        return None;
+    }
+    let defaults = skeleton::SkeletonOptions::default();
+    Some(skeleton::SkeletonOptions {
+        max_call_edge_names: max_call_edge_names.unwrap_or(defaults.max_call_edge_names),
+        max_call_edge_nodes: max_call_edge_nodes.unwrap_or(defaults.max_call_edge_nodes),
+        max_skeleton_lines: config.max_skeleton_lines.unwrap_or(defaults.max_skeleton_lines),
+        max_skeleton_chars: config.max_skeleton_chars.unwrap_or(defaults.max_skeleton_chars),
+        max_member_names: config.max_member_names.unwrap_or(defaults.max_member_names),
+        max_def_line_len: config.max_def_line_len.unwrap_or(defaults.max_def_line_len),
+        full_below_lines: full_below_lines.or(defaults.full_below_lines),
+        timeout_ms: config.timeout_ms.or(defaults.timeout_ms),
+        include_line_numbers: config.include_line_numbers.unwrap_or(defaults.include_line_numbers),
+        line_number_width: defaults.line_number_width,
+        include_summary_header: defaults.include_summary_header,
+        env_redaction_mode: config.env_file_redaction_mode.unwrap_or(defaults.env_redaction_mode),
+        force_legacy_js: config.force_legacy_js.unwrap_or(defaults.force_legacy_js),
+    })
+}
+
+/// Per-call tuning for `skeletonize_file`/`skeletonize_files` grouped into a
+/// single struct, since parser-safety knobs like this are expected to grow
+/// over time without wanting to keep adding more loose `Option<T>` params.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+struct SkeletonConfig {
+    /// Bounds how long tree-sitter may spend parsing a single file before
+    /// giving up and falling back to `fallback_compress`, guarding against a
+    /// pathological input hanging the command indefinitely.
+    timeout_ms: Option<u64>,
+    /// Caps the number of lines kept in the rendered skeleton before a
+    /// truncation comment is appended.
+    max_skeleton_lines: Option<usize>,
+    /// Caps the total character count of the rendered skeleton.
+    max_skeleton_chars: Option<usize>,
+    /// Caps how many struct fields / enum variants / class attrs are listed
+    /// before the rest collapse into `...`.
+    max_member_names: Option<usize>,
+    /// Caps how long a single definition line (signature, field list, etc.)
+    /// may be before it's truncated.
+    max_def_line_len: Option<usize>,
+    /// When set, prefixes each rendered definition line with its original
+    /// source line number so an LLM can reference exact locations.
+    include_line_numbers: Option<bool>,
+    /// Controls how `.env` file values are rendered by `fallback_compress`.
+    /// Defaults to `SafeRedact`, which hides values for keys that look like
+    /// secrets while leaving the rest of the file readable.
+    env_file_redaction_mode: Option<skeleton::EnvRedactionMode>,
+    /// Forces JavaScript/TypeScript files through the legacy `skeleton_legacy`
+    /// extractor instead of the modular one, for callers who hit a case
+    /// where its output is still preferred.
+    force_legacy_js: Option<bool>,
+}
+
+fn looks_binary(bytes: &[u8]) -> bool {
+    let sniff_len = bytes.len().min(BINARY_SNIFF_BYTES);
+    bytes[..sniff_len].contains(&0)
+}
+
+fn read_whole_file(path: &Path) -> Result<String, ReadFileError> {
+    let bytes = std::fs::read(path).map_err(|e| ReadFileError::Io { message: e.to_string() })?;
+    let (content, _encoding) = decode_file_bytes(&bytes);
+    if looks_binary(content.as_bytes()) {
+        return Err(ReadFileError::Binary { message: "File appears to be binary".to_string() });
+    }
+    Ok(content)
+}
+
+fn read_file_byte_range(path: &Path, offset: u64, max_bytes: Option<u64>) -> Result<String, ReadFileError> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let mut file = std::fs::File::open(path).map_err(|e| ReadFileError::Io { message: e.to_string() })?;
+    file.seek(SeekFrom::Start(offset)).map_err(|e| ReadFileError::Io { message: e.to_string() })?;
+
+    let take = max_bytes.unwrap_or(DEFAULT_MAX_READ_SIZE);
+    let mut buf = Vec::new();
+    file.take(take).read_to_end(&mut buf).map_err(|e| ReadFileError::Io { message: e.to_string() })?;
+
+    if looks_binary(&buf) {
+        return Err(ReadFileError::Binary { message: "File appears to be binary".to_string() });
+    }
+    Ok(String::from_utf8_lossy(&buf).into_owned())
+}
+
+fn read_file_line_range(path: &Path, start_line: usize, end_line: usize) -> Result<String, ReadFileError> {
+    let bytes = std::fs::read(path).map_err(|e| ReadFileError::Io { message: e.to_string() })?;
+    if looks_binary(&bytes) {
+        return Err(ReadFileError::Binary { message: "File appears to be binary".to_string() });
+    }
+
+    let content = String::from_utf8_lossy(&bytes);
+    let start = start_line.max(1) - 1;
+    let selected: Vec<&str> = content.lines().skip(start).take(end_line.saturating_sub(start)).collect();
+    Ok(selected.join("\n"))
+}
+
+fn read_whole_file_with_limit(path: &Path, max_size: Option<u64>) -> Result<String, ReadFileError> {
+    let size = std::fs::metadata(path).map_err(|e| ReadFileError::Io { message: e.to_string() })?.len();
+    let limit = max_size.unwrap_or(DEFAULT_MAX_READ_SIZE);
+    if size > limit {
+        return Err(ReadFileError::TooLarge {
+            message: format!("File is {} bytes, exceeds the {} byte limit", size, limit),
+            size,
+            max_size: limit,
+        });
+    }
+
+    read_whole_file(path)
+}
+
+/// Read a file's content, guarding against binary files, oversized files,
+/// and invalid UTF-8 in otherwise-text files (replaced lossily rather than
+/// failing). An optional `range` lets the caller page through big files by
+/// byte offset or by line number instead of loading the whole thing. When
+/// `redact_secrets` is set, credential-shaped substrings (AWS keys, GitHub
+/// tokens, PEM blocks, generic `api_key = "..."` assignments) are scrubbed
+/// from the result before it's returned.
+#[tauri::command]
+async fn read_file_content(
+    path: String,
+    max_size: Option<u64>,
+    range: Option<FileReadRange>,
+    path_bytes: Option<Vec<u8>>,
+    redact_secrets: Option<bool>,
+) -> Result<String, ReadFileError> {
+    let resolved = resolve_actual_path(&path, path_bytes.as_ref());
+    let p = resolved.as_path();
+
+    let content = if let Some(range) = &range {
+        if let (Some(start_line), Some(end_line)) = (range.start_line, range.end_line) {
+            read_file_line_range(p, start_line, end_line)
+        } else if let Some(offset) = range.offset {
+            read_file_byte_range(p, offset, range.max_bytes)
+        } else {
+            read_whole_file_with_limit(p, max_size)
+        }
+    } else {
+        read_whole_file_with_limit(p, max_size)
+    }?;
+
+    if redact_secrets.unwrap_or(false) {
+        Ok(secrets::redact_secrets(&content).content)
+    } else {
+        Ok(content)
+    }
+}
+
+/// Scan `paths` for common credential shapes (AWS access keys, GitHub
+/// tokens, PEM private key blocks, generic `api_key = "..."` assignments)
+/// without modifying the files, reporting where each match was found.
+#[tauri::command]
+async fn scan_for_secrets(paths: Vec<String>) -> secrets::SecretScanResult {
+    secrets::scan_for_secrets(&paths)
+}
+
+/// Compute full content hashes for a set of files, e.g. so the watcher can
+/// tell a real content change apart from a metadata-only touch.
+#[tauri::command]
+async fn compute_file_hashes(paths: Vec<String>) -> Vec<(String, String)> {
+    paths
+        .into_par_iter()
+        .filter_map(|p| {
+            let content = std::fs::read(&p).ok()?;
+            let hash = blake3::hash(&content).to_hex().to_string();
+            Some((p, hash))
+        })
+        .collect()
+}
+
+/// Find exact and near-duplicate files among `paths` by normalized-content
+/// hashing and word-shingle similarity, so the UI can suggest dropping
+/// redundant copies before packing. Files that fail to read are skipped
+/// rather than failing the whole scan.
+#[tauri::command]
+async fn find_duplicate_content(paths: Vec<String>) -> duplicates::DuplicateContentResult {
+    duplicates::find_duplicate_content(&paths)
+}
+
+/// A single file's projected savings from switching it to skeleton mode, as
+/// reported by `estimate_skeletons`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SkeletonEstimate {
+    path: String,
+    estimated_skeleton_lines: usize,
+    compression_ratio: f64,
+}
+
+/// Files below this line count aren't worth skeletonizing (and aren't worth
+/// spending parse time estimating), so `estimate_skeletons` skips them.
+const MIN_LINES_FOR_SKELETON_ESTIMATE: usize = 80;
+
+/// For each of `paths` that's a text file at least `MIN_LINES_FOR_SKELETON_ESTIMATE`
+/// lines long and no bigger than `max_file_size`, run the real skeletonizer
+/// and report the projected line count and compression ratio, so the UI can
+/// sort by "biggest savings" and suggest skeleton mode for the worst
+/// offenders. Runs in parallel across files and checks `state.cancelled`
+/// between files so a fresh call (or `cancel_skeleton_estimates`) can cut
+/// a long-running estimate short.
+#[tauri::command]
+async fn estimate_skeletons(
+    paths: Vec<String>,
+    max_file_size: Option<u64>,
+    state: State<'_, SkeletonEstimateState>,
+) -> Result<Vec<SkeletonEstimate>, String> {
+    state.cancelled.store(false, Ordering::SeqCst);
+    let max_size = max_file_size.unwrap_or(DEFAULT_MAX_SCAN_FILE_SIZE);
+
+    let estimates: Vec<SkeletonEstimate> = paths
+        .into_par_iter()
+        .filter_map(|p| {
+            if state.cancelled.load(Ordering::SeqCst) {
+                return None;
+            }
+
+            let path = Path::new(&p);
+            let metadata = std::fs::metadata(path).ok()?;
+            if metadata.len() > max_size {
+                return None;
+            }
+
+            let content = std::fs::read_to_string(path).ok()?;
+            if content.lines().count() < MIN_LINES_FOR_SKELETON_ESTIMATE {
+                return None;
+            }
+
+            let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+            let result = skeleton::skeletonize(&content, extension, Some(&p));
+
+            Some(SkeletonEstimate {
+                path: p,
+                estimated_skeleton_lines: result.skeleton_lines,
+                compression_ratio: result.compression_ratio(),
+            })
+        })
+        .collect();
+
+    Ok(estimates)
+}
+
+/// Cancel an in-flight `estimate_skeletons` call.
+#[tauri::command]
+async fn cancel_skeleton_estimates(state: State<'_, SkeletonEstimateState>) -> Result<(), String> {
+    state.cancelled.store(true, Ordering::SeqCst);
+    Ok(())
+}
+
+/// Cancel an in-flight `scan_project`, `skeletonize_files`, or
+/// `generate_prompt` call started with this `operation_id`. A no-op if the
+/// id is unknown or its command has already returned.
+#[tauri::command]
+async fn cancel_operation(operation_id: String, cancellation: State<'_, CancellationRegistry>) -> Result<(), String> {
+    cancellation.cancel(&operation_id);
+    Ok(())
+}
+
+/// Result of skeleton extraction, returned to frontend.
+///
+/// Three different compression ratios are reported, since each answers a
+/// different question and none of them predicts the others:
+/// - `compression_ratio`: character-based (`1 - skeleton_chars / original_chars`).
+///   Cheap to compute and a decent proxy for "how much text disappeared".
+/// - `line_compression_ratio`: line-count-based, as used by `estimate_skeletons`
+///   for `SkeletonEstimate`. Useful for "how much shorter did the file get"
+///   framing, but misleading for languages with long lines.
+/// - `token_compression_ratio`: estimated-token-based, using the same
+///   tokenizer as `count_tokens`. This is the one that actually predicts
+///   prompt cost savings, since packing cost is billed in tokens, not
+///   characters or lines.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct SkeletonResult {
+    skeleton: String,
+    language: Option<String>,
+    original_lines: usize,
+    skeleton_lines: usize,
+    compression_ratio: f32,
+    token_compression_ratio: f32,
+    cache_hit: bool,
+    timed_out: bool,
+    parse_errors: bool,
+    /// Set when the file looked generated/minified and skeleton extraction
+    /// was skipped in favor of a one-line summary.
+    skipped_minified: bool,
+    /// Set when `skeleton::cap_output` cut the skeleton short to stay under
+    /// its configured line/char limits, meaning it's missing content that
+    /// would otherwise have been included.
+    truncated: bool,
+    /// Set by `skeletonize_files` when `dedup_identical_skeletons` is on and
+    /// this file's skeleton is byte-for-byte identical to an earlier file's -
+    /// holds that earlier file's path, and `skeleton` is replaced with a
+    /// short `// identical to <path>` marker.
+    identical_to: Option<String>,
+    /// Set when this path was listed in `raw_include` - `skeleton` holds the
+    /// file's content verbatim, bypassing tree-sitter and `fallback_compress`
+    /// entirely, regardless of `full_below_lines` or the file's size.
+    raw_included: bool,
+}
+
+/// `skeletonize_files`' return value. When `operation_id` is cancelled
+/// mid-batch, `results` holds only the files that finished before the
+/// cancellation was noticed (shorter than the input `paths`) and `cancelled`
+/// is set, rather than discarding that work and returning an error. When
+/// `token_budget` is exceeded, `results` stays the same length as the input
+/// `paths` but every file past the budget holds `Err("budget exhausted")`
+/// instead of being skeletonized, and `budget_exhausted` is set.
+/// `total_token_estimate` is the sum of tokenizer counts across every
+/// successfully skeletonized file, whether or not a budget was given.
+#[derive(Debug, Serialize, Deserialize)]
+struct SkeletonBatchCommandResult {
+    results: Vec<Result<SkeletonResult, String>>,
+    cancelled: bool,
+    budget_exhausted: bool,
+    total_token_estimate: usize,
+}
+
+/// `1 - skeleton_tokens / original_tokens`, estimated with the same tokenizer
+/// `count_tokens` uses, so the UI can show the ratio that actually predicts
+/// prompt cost savings rather than a character or line proxy.
+fn token_compression_ratio(original: &str, skeleton: &str) -> f32 {
+    let original_tokens = TOKENIZER.encode_with_special_tokens(original).len() as f32;
+    let skeleton_tokens = TOKENIZER.encode_with_special_tokens(skeleton).len() as f32;
+    if original_tokens > 0.0 {
+        1.0 - (skeleton_tokens / original_tokens)
+    } else {
+        0.0
+    }
+}
+
+/// Skeletonize a file using AST-based extraction
+/// Returns structural signatures (imports, types, function signatures) without implementation details
+#[tauri::command]
+async fn skeletonize_file(
+    app: tauri::AppHandle,
+    path: String,
+    perf: State<'_, PerfMetricsState>,
+    overrides: State<'_, LanguageOverrides>,
+    path_bytes: Option<Vec<u8>>,
+    max_call_edge_names: Option<usize>,
+    max_call_edge_nodes: Option<usize>,
+    full_below_lines: Option<usize>,
+    config: Option<SkeletonConfig>,
+) -> Result<SkeletonResult, String> {
+    let start = Instant::now();
+    let mut cache_hit = false;
+    let resolved = resolve_actual_path(&path, path_bytes.as_ref());
+    let options = skeleton_options_from_params(max_call_edge_names, max_call_edge_nodes, full_below_lines, config);
+
+    // A custom call-edge limit changes the output shape, so cached entries
+    // (which were built with the default limits) can't be reused for it.
+    let fingerprint = file_fingerprint(&resolved);
+    if options.is_none() {
+        if let Some((file_size, modified_unix_nanos)) = fingerprint {
+            let cached = SKELETON_CACHE
+                .lock()
+                .ok()
+                .and_then(|cache| cache.get(&path).cloned());
+
+            if let Some(entry) = cached {
+                if entry.file_size == file_size && entry.modified_unix_nanos == modified_unix_nanos {
+                    cache_hit = true;
+                    if let Ok(mut m) = perf.metrics.lock() {
+                        m.skeleton_file = Some(SkeletonFileMetrics {
+                            duration_ms: start.elapsed().as_secs_f64() * 1000.0,
+                            cache_hit,
+                        });
+                        m.skeleton_cache_size = SKELETON_CACHE.lock().map(|c| c.len()).unwrap_or(0);
+                    }
+                    return Ok(SkeletonResult { cache_hit, ..entry.result });
+                }
+            }
+        }
+    }
+
+    // Read the file content, transcoding BOM-prefixed or heuristically
+    // detected UTF-16 files to UTF-8 instead of failing outright.
+    let bytes = std::fs::read(&resolved).map_err(|e| e.to_string())?;
+    let (content, _encoding) = decode_file_bytes(&bytes);
+
+    // Extract file extension
+    let extension = resolved
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("");
+
+    // Run skeletonization
+    let override_map = overrides.overrides.lock().map_err(|_| "Lock error")?.clone();
+    let result = skeleton::skeletonize_with_overrides_and_options(&content, extension, Some(&path), &override_map, options);
+
+    // Calculate compression ratio
+    let original_chars = content.len() as f32;
+    let skeleton_chars = result.skeleton.len() as f32;
+    let compression_ratio = if original_chars > 0.0 {
+        1.0 - (skeleton_chars / original_chars)
+    } else {
+        0.0
+    };
+    let token_compression_ratio = token_compression_ratio(&content, &result.skeleton);
+
+    if result.parse_errors {
+        let _ = app.emit("scan-warning", format!("{}: parse errors, skeleton may be incomplete", path));
+    }
+
+    let skeleton_result = SkeletonResult {
+        skeleton: result.skeleton,
+        language: result.language.map(|l| format!("{:?}", l)),
+        original_lines: result.original_lines,
+        skeleton_lines: result.skeleton_lines,
+        compression_ratio,
+        token_compression_ratio,
+        cache_hit: false,
+        timed_out: result.timed_out,
+        parse_errors: result.parse_errors,
+        skipped_minified: result.skipped_minified,
+        truncated: result.truncated,
+        identical_to: None,
+        raw_included: false,
+    };
+
+    if options.is_none() {
+        if let Some((file_size, modified_unix_nanos)) = fingerprint {
+            skeleton_cache_insert(
+                path,
+                SkeletonCacheEntry {
+                    file_size,
+                    modified_unix_nanos,
+                    result: skeleton_result.clone(),
+                },
+            );
+        }
+    }
+
+    if let Ok(mut m) = perf.metrics.lock() {
+        m.skeleton_file = Some(SkeletonFileMetrics {
+            duration_ms: start.elapsed().as_secs_f64() * 1000.0,
+            cache_hit,
+        });
+        m.skeleton_cache_size = SKELETON_CACHE.lock().map(|c| c.len()).unwrap_or(0);
+    }
+
+    Ok(skeleton_result)
+}
+
+/// Walks `results` in `paths` order, replacing each result whose skeleton is
+/// byte-for-byte identical to an earlier one with a short marker pointing
+/// back at that earlier file's path. Cuts tokens substantially in monorepos
+/// full of near-identical barrel files (`export * from './x'` re-export
+/// modules, boilerplate config files, etc).
+fn dedup_identical_skeletons_in_place(paths: &[String], results: &mut [Result<SkeletonResult, String>]) {
+    let mut seen: HashMap<String, String> = HashMap::new();
+    for (path, result) in paths.iter().zip(results.iter_mut()) {
+        let Ok(skeleton_result) = result else { continue };
+        match seen.get(&skeleton_result.skeleton) {
+            Some(original_path) => {
+                skeleton_result.identical_to = Some(original_path.clone());
+                skeleton_result.skeleton = format!("// identical to {original_path}");
+            }
+            None => {
+                seen.insert(skeleton_result.skeleton.clone(), path.clone());
+            }
+        }
+    }
+}
+
+/// Walks `results` in order, summing the tokenizer count of each `Ok`
+/// entry's skeleton. Once that running total would exceed `budget`, every
+/// remaining entry (the one that tipped it over is kept) is overwritten with
+/// `Err("budget exhausted")`. Returns whether the budget was hit at all.
+fn apply_token_budget(results: &mut [Result<SkeletonResult, String>], budget: usize) -> bool {
+    let mut total_tokens = 0usize;
+    let mut exhausted = false;
+    for result in results.iter_mut() {
+        if exhausted {
+            *result = Err("budget exhausted".to_string());
+            continue;
+        }
+        if let Ok(skeleton_result) = result {
+            total_tokens += TOKENIZER.encode_with_special_tokens(&skeleton_result.skeleton).len();
+            if total_tokens > budget {
+                exhausted = true;
+            }
+        }
+    }
+    exhausted
+}
+
+/// Batch skeletonize multiple files at once for efficiency
+#[tauri::command]
+async fn skeletonize_files(
+    app: tauri::AppHandle,
+    paths: Vec<String>,
+    perf: State<'_, PerfMetricsState>,
+    overrides: State<'_, LanguageOverrides>,
+    config: Option<SkeletonConfig>,
+    dedup_identical_skeletons: Option<bool>,
+    // Paths to include verbatim, bypassing skeletonization entirely - for
+    // files (a prompt template, a critical schema) that must reach the
+    // prompt unmodified regardless of language support, size, or
+    // `full_below_lines`. Distinct from that threshold because it's an
+    // explicit per-file override rather than a size-based heuristic.
+    raw_include: Option<Vec<String>>,
+    // Caps the sum of tokenizer counts across `results`. Once a completed
+    // file's tokens push the running total past this, every remaining path
+    // is short-circuited to `Err("budget exhausted")` instead of being
+    // skeletonized, so a caller packing a prompt can't accidentally overrun
+    // the model's context window.
+    token_budget: Option<usize>,
+    operation_id: Option<String>,
+    cancellation: State<'_, CancellationRegistry>,
+) -> Result<SkeletonBatchCommandResult, String> {
+    let start = Instant::now();
+    let files_processed = paths.len();
+    let hit_counter = AtomicUsize::new(0);
+    let override_map = overrides.overrides.lock().map_err(|_| "Lock error")?.clone();
+    let options = skeleton_options_from_params(None, None, None, config);
+    let dedup_paths = dedup_identical_skeletons.unwrap_or(false).then(|| paths.clone());
+    let raw_include: HashSet<String> = raw_include.unwrap_or_default().into_iter().collect();
+    let token = operation_id.as_deref().map(|id| cancellation.register(id));
+
+    let process_one = |p: &str| -> Result<SkeletonResult, String> {
+        if raw_include.contains(p) {
+            let content = std::fs::read_to_string(p).map_err(|e| e.to_string())?;
+            let line_count = content.lines().count();
+            return Ok(SkeletonResult {
+                skeleton: content,
+                language: None,
+                original_lines: line_count,
+                skeleton_lines: line_count,
+                compression_ratio: 0.0,
+                token_compression_ratio: 0.0,
+                cache_hit: false,
+                timed_out: false,
+                parse_errors: false,
+                skipped_minified: false,
+                truncated: false,
+                identical_to: None,
+                raw_included: true,
+            });
+        }
+
+        let fingerprint = file_fingerprint(Path::new(p));
+        // A custom timeout changes the output shape, so cached entries
+        // (built with the default timeout) can't be reused for it.
+        if options.is_none() {
+            if let Some((file_size, modified_unix_nanos)) = fingerprint {
+                let cached = SKELETON_CACHE
+                    .lock()
+                    .ok()
+                    .and_then(|cache| cache.get(p).cloned());
+
+                if let Some(entry) = cached {
+                    if entry.file_size == file_size && entry.modified_unix_nanos == modified_unix_nanos {
+                        hit_counter.fetch_add(1, Ordering::Relaxed);
+                        return Ok(SkeletonResult { cache_hit: true, ..entry.result });
+                    }
+                }
+            }
+        }
+
+        let content = std::fs::read_to_string(p).map_err(|e| e.to_string())?;
+        let extension = Path::new(p)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("");
+        let result = skeleton::skeletonize_with_overrides_and_options(&content, extension, Some(p), &override_map, options);
+
+        let original_chars = content.len() as f32;
+        let skeleton_chars = result.skeleton.len() as f32;
+        let compression_ratio = if original_chars > 0.0 {
+            1.0 - (skeleton_chars / original_chars)
+        } else {
+            0.0
+        };
+        let token_compression_ratio = token_compression_ratio(&content, &result.skeleton);
+
+        if result.parse_errors {
+            let _ = app.emit("scan-warning", format!("{}: parse errors, skeleton may be incomplete", p));
+        }
+
+        let skeleton_result = SkeletonResult {
+            skeleton: result.skeleton,
+            language: result.language.map(|l| format!("{:?}", l)),
+            original_lines: result.original_lines,
+            skeleton_lines: result.skeleton_lines,
+            compression_ratio,
+            token_compression_ratio,
+            cache_hit: false,
+            timed_out: result.timed_out,
+            parse_errors: result.parse_errors,
+            skipped_minified: result.skipped_minified,
+            truncated: result.truncated,
+            identical_to: None,
+            raw_included: false,
+        };
+
+        if options.is_none() {
+            if let Some((file_size, modified_unix_nanos)) = fingerprint {
+                skeleton_cache_insert(
+                    p.to_string(),
+                    SkeletonCacheEntry {
+                        file_size,
+                        modified_unix_nanos,
+                        result: skeleton_result.clone(),
+                    },
+                );
+            }
+        }
+
+        Ok(skeleton_result)
+    };
+
+    // Pairs each survivor with its path so a cancellation mid-batch can drop
+    // not-yet-started files without losing track of which path each
+    // already-computed result belongs to (needed for the dedup and budget
+    // passes below). `collect` on a parallel iterator preserves the original
+    // `paths` order regardless of which thread finished a given file first,
+    // so the budget pass below sees files in the same order the caller gave.
+    let processed: Vec<(String, Result<SkeletonResult, String>)> = paths.into_par_iter().filter_map(|p| {
+        if token.as_ref().is_some_and(|t| t.load(Ordering::SeqCst)) {
+            return None;
+        }
+        let outcome = process_one(&p);
+        Some((p, outcome))
+    }).collect();
+
+    let cancelled = processed.len() < files_processed;
+    let (kept_paths, mut results): (Vec<String>, Vec<Result<SkeletonResult, String>>) =
+        processed.into_iter().unzip();
+
+    if let Some(dedup_paths) = dedup_paths {
+        let kept: HashSet<&String> = kept_paths.iter().collect();
+        let dedup_paths: Vec<String> = dedup_paths.into_iter().filter(|p| kept.contains(p)).collect();
+        dedup_identical_skeletons_in_place(&dedup_paths, &mut results);
+    }
+
+    let budget_exhausted = token_budget.is_some_and(|budget| apply_token_budget(&mut results, budget));
+
+    let cache_hits = hit_counter.load(Ordering::Relaxed);
+    let actually_processed = results.len();
+    let total_token_estimate: usize = results
+        .iter()
+        .filter_map(|r| r.as_ref().ok())
+        .map(|r| TOKENIZER.encode_with_special_tokens(&r.skeleton).len())
+        .sum();
+    if let Ok(mut m) = perf.metrics.lock() {
+        m.skeleton_batch = Some(SkeletonBatchMetrics {
+            duration_ms: start.elapsed().as_secs_f64() * 1000.0,
+            files_processed: actually_processed,
+            cache_hits,
+            cache_misses: actually_processed - cache_hits,
+        });
+        m.skeleton_cache_size = SKELETON_CACHE.lock().map(|c| c.len()).unwrap_or(0);
+    }
+
+    if let Some(id) = operation_id.as_deref() {
+        cancellation.unregister(id);
+    }
+
+    Ok(SkeletonBatchCommandResult { results, cancelled, budget_exhausted, total_token_estimate })
+}
+
+/// Walk `path` with the same ignore rules as `scan_project`, then skeletonize
+/// every eligible (supported-language) file found, in parallel. Spares the
+/// frontend from having to call `scan_project` and then `skeletonize_files`
+/// with hundreds of individual paths for a whole-project pass.
+#[tauri::command]
+async fn skeletonize_project(
+    app: tauri::AppHandle,
+    path: String,
+    perf: State<'_, PerfMetricsState>,
+    overrides: State<'_, LanguageOverrides>,
+    max_file_size: Option<u64>,
+    ignored_dirs: Option<IgnoredDirOverrides>,
+    max_call_edge_names: Option<usize>,
+    max_call_edge_nodes: Option<usize>,
+    full_below_lines: Option<usize>,
+    config: Option<SkeletonConfig>,
+) -> Result<Vec<(String, SkeletonResult)>, String> {
+    let start = Instant::now();
+    let options = skeleton_options_from_params(max_call_edge_names, max_call_edge_nodes, full_below_lines, config);
+    let override_map = overrides.overrides.lock().map_err(|_| "Lock error")?.clone();
+
+    let (entries, warnings) = scan_project_entries(
+        Path::new(&path),
+        max_file_size.unwrap_or(DEFAULT_MAX_SCAN_FILE_SIZE),
+        false,
+        None,
+        None,
+        None,
+        None,
+        None,
+        Arc::new(effective_ignored_dirs(ignored_dirs.as_ref())),
+        false,
+    )?;
+
+    if warnings.limit_hit {
+        let _ = app.emit("scan-warning", "max_files limit reached");
+    }
+
+    let files_processed = entries
+        .iter()
+        .filter(|e| !e.is_dir)
+        .filter(|e| {
+            let extension = Path::new(&e.relative_path)
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .unwrap_or("");
+            skeleton::is_supported_file(extension, Some(&e.relative_path), &override_map)
+        })
+        .count();
+
+    let results: Vec<(String, SkeletonResult)> = entries
+        .into_par_iter()
+        .filter(|e| !e.is_dir)
+        .filter_map(|entry| {
+            let extension = Path::new(&entry.relative_path)
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .unwrap_or("")
+                .to_string();
+            if !skeleton::is_supported_file(&extension, Some(&entry.relative_path), &override_map) {
+                return None;
+            }
+
+            let content = std::fs::read_to_string(&entry.path).ok()?;
+            let result = skeleton::skeletonize_with_overrides_and_options(
+                &content,
+                &extension,
+                Some(&entry.relative_path),
+                &override_map,
+                options,
+            );
+
+            let original_chars = content.len() as f32;
+            let skeleton_chars = result.skeleton.len() as f32;
+            let compression_ratio = if original_chars > 0.0 {
+                1.0 - (skeleton_chars / original_chars)
+            } else {
+                0.0
+            };
+            let token_compression_ratio = token_compression_ratio(&content, &result.skeleton);
+
+            if result.parse_errors {
+                let _ = app.emit("scan-warning", format!("{}: parse errors, skeleton may be incomplete", entry.relative_path));
+            }
+
+            let skeleton_result = SkeletonResult {
+                skeleton: result.skeleton,
+                language: result.language.map(|l| format!("{:?}", l)),
+                original_lines: result.original_lines,
+                skeleton_lines: result.skeleton_lines,
+                compression_ratio,
+                token_compression_ratio,
+                cache_hit: false,
+                timed_out: result.timed_out,
+                parse_errors: result.parse_errors,
+                skipped_minified: result.skipped_minified,
+                truncated: result.truncated,
+                identical_to: None,
+                raw_included: false,
+            };
+
+            Some((entry.relative_path, skeleton_result))
+        })
+        .collect();
+
+    if let Ok(mut m) = perf.metrics.lock() {
+        m.skeleton_batch = Some(SkeletonBatchMetrics {
+            duration_ms: start.elapsed().as_secs_f64() * 1000.0,
+            files_processed,
+            cache_hits: 0,
+            cache_misses: files_processed,
+        });
+        m.skeleton_cache_size = SKELETON_CACHE.lock().map(|c| c.len()).unwrap_or(0);
+    }
+
+    Ok(results)
+}
+
+/// Count tokens for given text using cl100k_base encoding (GPT-3.5/4 tokenizer)
+#[tauri::command]
+fn count_tokens(text: String) -> Result<usize, String> {
+    Ok(TOKENIZER.encode_with_special_tokens(&text).len())
+}
+
+/// Count tokens for multiple file paths, reading content from disk
+#[tauri::command]
+async fn count_tokens_for_files(paths: Vec<String>, perf: State<'_, PerfMetricsState>) -> Result<usize, String> {
+    let start = Instant::now();
+    let files_processed = paths.len();
+
+    let results: Vec<(usize, Option<(String, TokenCacheEntry)>)> = paths
+        .par_iter()
+        .map(|path| {
+            let (file_size, modified_unix_nanos) = match file_fingerprint(Path::new(path)) {
+                Some(fingerprint) => fingerprint,
+                None => return (0, None),
+            };
+
+            let cached = TOKEN_COUNT_CACHE
+                .lock()
+                .ok()
+                .and_then(|cache| cache.get(path).copied());
+
+            if let Some(entry) = cached {
+                if entry.file_size == file_size && entry.modified_unix_nanos == modified_unix_nanos {
+                    return (entry.token_count, None);
+                }
+            }
+
+            let content = match std::fs::read_to_string(path) {
+                Ok(content) => content,
+                Err(_) => return (0, None),
+            };
+
+            let token_count = TOKENIZER.encode_with_special_tokens(&content).len();
+
+            (
+                token_count,
+                Some((
+                    path.clone(),
+                    TokenCacheEntry {
+                        file_size,
+                        modified_unix_nanos,
+                        token_count,
+                    },
+                )),
+            )
+        })
+        .collect();
+
+    let total = results
+        .iter()
+        .map(|(token_count, _)| *token_count)
+        .sum::<usize>();
+
+    let new_entries: Vec<(String, TokenCacheEntry)> =
+        results.into_iter().filter_map(|(_, entry)| entry).collect();
+
+    let cache_misses = new_entries.len();
+    let cache_hits = files_processed - cache_misses;
+
+    if !new_entries.is_empty() {
+        if let Ok(mut cache) = TOKEN_COUNT_CACHE.lock() {
+            cache.extend(new_entries);
+        }
+    }
+
+    if let Ok(mut m) = perf.metrics.lock() {
+        m.token_count = Some(TokenCountMetrics {
+            duration_ms: start.elapsed().as_secs_f64() * 1000.0,
+            files_processed,
+            cache_hits,
+            cache_misses,
+        });
+        m.token_cache_size = TOKEN_COUNT_CACHE.lock().map(|c| c.len()).unwrap_or(0);
+    }
+
+    Ok(total)
+}
+
+/// Context window sizes (in tokens) for the models `get_token_estimate`
+/// reports fit-or-not against. Intentionally a short, hardcoded list of
+/// commonly-targeted models rather than a configurable one - exact enough
+/// for the "will this fit" gut check the frontend needs, and simple to keep
+/// in sync by hand as model limits change.
+const MODEL_CONTEXT_LIMITS: &[(&str, usize)] = &[
+    ("gpt-3.5", 4096),
+    ("gpt-4", 8192),
+    ("gpt-4-turbo", 128_000),
+    ("claude-3", 200_000),
+];
+
+/// A fast, tokenizer-free size estimate for a piece of content, so the
+/// frontend can show "will this fit" feedback without paying for a full
+/// `count_tokens` encode on every keystroke.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TokenEstimate {
+    char_count: usize,
+    estimated_tokens: usize,
+    fits_in: HashMap<String, bool>,
+}
+
+/// `chars / 4` is the standard rule-of-thumb token estimate for English
+/// prose - good enough to gut-check against a context window without
+/// running the real tokenizer.
+fn estimate_tokens_for_chars(char_count: usize) -> TokenEstimate {
+    let estimated_tokens = char_count / 4;
+    let fits_in = MODEL_CONTEXT_LIMITS
+        .iter()
+        .map(|(model, limit)| (model.to_string(), estimated_tokens <= *limit))
+        .collect();
+    TokenEstimate { char_count, estimated_tokens, fits_in }
+}
+
+/// Fast character-count-based token estimate for `content`. See `count_tokens`
+/// for the exact (but slower) tokenizer-based count.
+#[tauri::command]
+fn get_token_estimate(content: String) -> TokenEstimate {
+    estimate_tokens_for_chars(content.chars().count())
+}
+
+/// Same estimate as `get_token_estimate`, but summed across every readable
+/// file in `paths`. Files that can't be read (missing, binary, oversized)
+/// are silently skipped, same as `count_tokens_for_files`.
+#[tauri::command]
+async fn get_files_token_estimate(paths: Vec<String>) -> TokenEstimate {
+    let char_count: usize = paths
+        .par_iter()
+        .map(|path| {
+            read_whole_file_with_limit(Path::new(path), None)
+                .map(|content| content.chars().count())
+                .unwrap_or(0)
+        })
+        .sum();
+    estimate_tokens_for_chars(char_count)
+}
+
+/// A saved prompt template: a name plus its raw `{{placeholder}}` content.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PromptTemplate {
+    name: String,
+    content: String,
+}
+
+/// Where templates are persisted: `templates.json` in the app's config dir,
+/// created on first save.
+fn templates_file_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let dir = app.path().app_config_dir().map_err(|e| e.to_string())?;
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir.join("templates.json"))
+}
+
+fn load_templates(app: &tauri::AppHandle) -> Result<HashMap<String, String>, String> {
+    let path = templates_file_path(app)?;
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+    let content = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&content).map_err(|e| e.to_string())
+}
+
+fn save_templates_to_disk(app: &tauri::AppHandle, templates: &HashMap<String, String>) -> Result<(), String> {
+    let path = templates_file_path(app)?;
+    let json = serde_json::to_string_pretty(templates).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| e.to_string())
+}
+
+/// Where a project's selection rules are persisted: one JSON file per
+/// project (keyed by a hash of its root path, since paths aren't safe file
+/// names) under a `selection_rules` subdirectory of the app's config dir.
+fn selection_rules_file_path(app: &tauri::AppHandle, root_path: &str) -> Result<PathBuf, String> {
+    let dir = app.path().app_config_dir().map_err(|e| e.to_string())?.join("selection_rules");
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let key = blake3::hash(root_path.as_bytes()).to_hex().to_string();
+    Ok(dir.join(format!("{key}.json")))
+}
+
+/// Loads a project's persisted selection rules, falling back to an empty
+/// rule list if none were ever saved.
+fn load_selection_rules_from_disk(
+    app: &tauri::AppHandle,
+    root_path: &str,
+) -> Result<selection_rules::SelectionRules, String> {
+    let path = selection_rules_file_path(app, root_path)?;
+    if !path.exists() {
+        return Ok(selection_rules::SelectionRules::default());
+    }
+    let content = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&content).map_err(|e| e.to_string())
+}
+
+fn save_selection_rules_to_disk(
+    app: &tauri::AppHandle,
+    root_path: &str,
+    rules: &selection_rules::SelectionRules,
+) -> Result<(), String> {
+    let path = selection_rules_file_path(app, root_path)?;
+    let json = serde_json::to_string_pretty(rules).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| e.to_string())
+}
+
+/// Returns `root_path`'s persisted selection rules, so the frontend can
+/// show and edit them.
+#[tauri::command]
+fn get_selection_rules(app: tauri::AppHandle, root_path: String) -> Result<selection_rules::SelectionRules, String> {
+    load_selection_rules_from_disk(&app, &root_path)
+}
+
+/// Replaces `root_path`'s selection rules and persists them to disk.
+#[tauri::command]
+fn set_selection_rules(
+    app: tauri::AppHandle,
+    root_path: String,
+    rules: selection_rules::SelectionRules,
+) -> Result<(), String> {
+    save_selection_rules_to_disk(&app, &root_path, &rules)
+}
+
+/// Computes each entry's pack mode from `root_path`'s persisted selection
+/// rules, for a frontend that wants to preview or apply rule-driven modes
+/// without going through `generate_prompt`.
+#[tauri::command]
+fn compute_selection_modes(
+    app: tauri::AppHandle,
+    root_path: String,
+    entries: Vec<FileEntry>,
+) -> Result<HashMap<String, selection_rules::SelectionMode>, String> {
+    let rules = load_selection_rules_from_disk(&app, &root_path)?;
+    Ok(selection_rules::apply_selection_rules(Path::new(&root_path), &entries, &rules))
+}
+
+/// A pack's file-hash fingerprint as of `create_pack_snapshot`, so a later
+/// `generate_delta_prompt` call can tell which of those paths changed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PackSnapshot {
+    file_hashes: HashMap<String, String>,
+}
+
+/// `root_path` is not safe to use as a file name, so snapshots are keyed by
+/// a hash of it, same as [`selection_rules_file_path`]. One snapshot is kept
+/// per project: a new `create_pack_snapshot` call for the same project
+/// overwrites the old one rather than accumulating history.
+fn pack_snapshot_id(root_path: &str) -> String {
+    blake3::hash(root_path.as_bytes()).to_hex().to_string()
+}
+
+fn pack_snapshot_file_path(app: &tauri::AppHandle, snapshot_id: &str) -> Result<PathBuf, String> {
+    let dir = app.path().app_config_dir().map_err(|e| e.to_string())?.join("pack_snapshots");
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir.join(format!("{snapshot_id}.json")))
+}
+
+fn load_pack_snapshot_from_disk(app: &tauri::AppHandle, snapshot_id: &str) -> Result<PackSnapshot, String> {
+    let path = pack_snapshot_file_path(app, snapshot_id)?;
+    let content = std::fs::read_to_string(&path)
+        .map_err(|_| format!("No pack snapshot found for id {snapshot_id}; call create_pack_snapshot first"))?;
+    serde_json::from_str(&content).map_err(|e| e.to_string())
+}
+
+fn save_pack_snapshot_to_disk(app: &tauri::AppHandle, snapshot_id: &str, snapshot: &PackSnapshot) -> Result<(), String> {
+    let path = pack_snapshot_file_path(app, snapshot_id)?;
+    let json = serde_json::to_string_pretty(snapshot).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| e.to_string())
+}
+
+/// Records the content hash of every readable path in `paths`, so a later
+/// `generate_delta_prompt` call for the same `root_path` can tell which of
+/// them (plus any newly selected paths) changed since now. Persists to disk
+/// keyed by `root_path` so the snapshot survives an app restart. Returns the
+/// snapshot id to pass to `generate_delta_prompt`.
+#[tauri::command]
+async fn create_pack_snapshot(paths: Vec<String>, root_path: String, app: tauri::AppHandle) -> Result<String, String> {
+    let file_hashes: HashMap<String, String> = compute_file_hashes(paths).await.into_iter().collect();
+    let snapshot_id = pack_snapshot_id(&root_path);
+    save_pack_snapshot_to_disk(&app, &snapshot_id, &PackSnapshot { file_hashes })?;
+    Ok(snapshot_id)
+}
+
+/// `generate_delta_prompt`'s return value: the rendered prompt plus how many
+/// of `paths` were treated as changed vs. left out as unchanged.
+#[derive(Debug, Serialize, Deserialize)]
+struct GenerateDeltaPromptResult {
+    prompt: String,
+    changed_count: usize,
+    unchanged_count: usize,
+}
+
+/// Splits `paths` into those whose current content hash differs from (or is
+/// absent from) `snapshot_hashes`, and those that match it. Pure so it's
+/// testable without a real `AppHandle`.
+fn partition_changed_paths<'a>(
+    paths: &'a [String],
+    current_hashes: &HashMap<String, String>,
+    snapshot_hashes: &HashMap<String, String>,
+) -> (Vec<&'a String>, Vec<&'a String>) {
+    let mut changed = Vec::new();
+    let mut unchanged = Vec::new();
+    for path in paths {
+        let is_unchanged = current_hashes.get(path).is_some_and(|hash| snapshot_hashes.get(path) == Some(hash));
+        if is_unchanged {
+            unchanged.push(path);
+        } else {
+            changed.push(path);
+        }
+    }
+    (changed, unchanged)
+}
+
+/// Like `generate_prompt`, but re-hashes `paths` against the snapshot taken
+/// by `create_pack_snapshot(snapshot_id)` and only includes files whose hash
+/// changed (full content or skeleton, per the same `use_rules`/`raw_include`
+/// rules `generate_prompt` uses) or that the snapshot never saw at all (new
+/// selections). Everything else is listed by name under `{{unchanged_files}}`
+/// so the model knows those files still exist without resending them.
+#[tauri::command]
+async fn generate_delta_prompt(
+    snapshot_id: String,
+    paths: Vec<String>,
+    root_path: String,
+    template: String,
+    variables: Option<HashMap<String, String>>,
+    use_rules: Option<bool>,
+    raw_include: Option<Vec<String>>,
+    app: tauri::AppHandle,
+) -> Result<GenerateDeltaPromptResult, String> {
+    let snapshot = load_pack_snapshot_from_disk(&app, &snapshot_id)?;
+    let raw_include: HashSet<String> = raw_include.unwrap_or_default().into_iter().collect();
+    let current_hashes: HashMap<String, String> = compute_file_hashes(paths.clone()).await.into_iter().collect();
+
+    let selection_modes = if use_rules.unwrap_or(false) {
+        let root = Path::new(&root_path);
+        let rules = load_selection_rules_from_disk(&app, &root_path)?;
+        let (scanned, _warnings) = scan_project_entries(
+            root,
+            DEFAULT_MAX_SCAN_FILE_SIZE,
+            false,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Arc::new(effective_ignored_dirs(None)),
+            false,
+        )?;
+        Some(selection_rules::apply_selection_rules(root, &scanned, &rules))
+    } else {
+        None
+    };
+
+    let (changed_paths, unchanged_paths) = partition_changed_paths(&paths, &current_hashes, &snapshot.file_hashes);
+    let unchanged_names: Vec<String> = unchanged_paths.iter().map(|p| to_tree_relative(p, &root_path)).collect();
+
+    let mut files = String::new();
+    let mut skeletons = String::new();
+
+    for path in changed_paths.iter().copied() {
+        let relative = to_tree_relative(path, &root_path);
+        let mode = if raw_include.contains(path) {
+            Some(selection_rules::SelectionMode::Full)
+        } else {
+            selection_modes.as_ref().and_then(|modes| modes.get(path)).copied()
+        };
+        if mode == Some(selection_rules::SelectionMode::Exclude) {
+            continue;
+        }
+        let Ok(content) = std::fs::read_to_string(path) else { continue };
+        if mode != Some(selection_rules::SelectionMode::Skeleton) {
+            files.push_str(&format!("// {relative}\n{content}\n\n"));
+        }
+        if mode != Some(selection_rules::SelectionMode::Full) {
+            let extension = Path::new(path).extension().and_then(|e| e.to_str()).unwrap_or("");
+            let skeleton = skeleton::skeletonize_with_path(&content, extension, Some(path)).skeleton;
+            skeletons.push_str(&format!("// {relative}\n{skeleton}\n\n"));
+        }
+    }
+
+    let unchanged_files = if unchanged_names.is_empty() { "(none)".to_string() } else { unchanged_names.join("\n") };
+    let token_count = TOKENIZER.encode_with_special_tokens(&files).len();
+
+    let mut vars = variables.unwrap_or_default();
+    vars.insert("file_tree".to_string(), build_tree(paths.clone(), root_path.clone()));
+    vars.insert("files".to_string(), files);
+    vars.insert("skeletons".to_string(), skeletons);
+    vars.insert("unchanged_files".to_string(), unchanged_files);
+    vars.insert("token_count".to_string(), token_count.to_string());
+    vars.insert("date".to_string(), today_date_string());
+
+    let prompt = render_prompt_template(&template, &vars)?;
+
+    Ok(GenerateDeltaPromptResult { prompt, changed_count: changed_paths.len(), unchanged_count: unchanged_paths.len() })
+}
+
+/// Save (or overwrite) a named prompt template.
+#[tauri::command]
+fn save_template(name: String, content: String, app: tauri::AppHandle) -> Result<(), String> {
+    let mut templates = load_templates(&app)?;
+    templates.insert(name, content);
+    save_templates_to_disk(&app, &templates)
+}
+
+/// List saved prompt templates, sorted by name.
+#[tauri::command]
+fn list_templates(app: tauri::AppHandle) -> Result<Vec<PromptTemplate>, String> {
+    let templates = load_templates(&app)?;
+    let mut list: Vec<PromptTemplate> = templates
+        .into_iter()
+        .map(|(name, content)| PromptTemplate { name, content })
+        .collect();
+    list.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(list)
+}
+
+/// Delete a saved prompt template. A no-op (not an error) if it doesn't exist.
+#[tauri::command]
+fn delete_template(name: String, app: tauri::AppHandle) -> Result<(), String> {
+    let mut templates = load_templates(&app)?;
+    templates.remove(&name);
+    save_templates_to_disk(&app, &templates)
+}
+
+/// Renders a prompt template by substituting `{{name}}` placeholders with
+/// values from `vars`. Fails loudly, naming the placeholder, rather than
+/// leaving an unknown one in the output unexpanded.
+fn render_prompt_template(template: &str, vars: &HashMap<String, String>) -> Result<String, String> {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let after_open = &rest[start + 2..];
+        let Some(end) = after_open.find("}}") else {
+            return Err("Unclosed template placeholder: missing a closing '}}'".to_string());
+        };
+        let name = after_open[..end].trim();
+        let value = vars
+            .get(name)
+            .ok_or_else(|| format!("Unknown template placeholder: {{{{{name}}}}}"))?;
+        output.push_str(value);
+        rest = &after_open[end + 2..];
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
+/// Converts a days-since-Unix-epoch count to a `(year, month, day)` civil
+/// date, via Howard Hinnant's public-domain `civil_from_days` algorithm —
+/// avoids pulling in a date/time crate for the single `{{date}}` placeholder.
+fn civil_from_days(z: i64) -> (i64, u32, u32) {
+    let z = z + 719468;
+    let era = if z >= 0 { z } else { z - 146096 } / 146097;
+    let doe = (z - era * 146097) as u64;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let y = yoe as i64 + era * 400;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = if m <= 2 { y + 1 } else { y };
+    (year, m as u32, d)
+}
+
+/// Today's date as `YYYY-MM-DD`, for the `{{date}}` template placeholder.
+fn today_date_string() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let (year, month, day) = civil_from_days((secs / 86400) as i64);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Per-language line/file totals reported by `summarize_project`, sorted
+/// with the largest language first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LanguageStat {
+    language: String,
+    file_count: usize,
+    line_count: usize,
+}
+
+/// Short, heuristic overview of a project, for prepending to a generated
+/// prompt so the model has orientation before it sees the file tree.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct ProjectSummary {
+    top_languages: Vec<LanguageStat>,
+    frameworks: Vec<String>,
+    entry_points: Vec<String>,
+    source_file_count: usize,
+    test_file_count: usize,
+}
+
+/// How many languages `summarize_project` reports before truncating the
+/// (line-count-sorted) rest.
+const TOP_LANGUAGE_COUNT: usize = 5;
+
+/// Filenames recognized as a project's entry point, checked against a
+/// scanned file's basename.
+const KNOWN_ENTRY_POINT_NAMES: &[&str] = &[
+    "main.rs", "main.go", "main.py", "manage.py", "__main__.py",
+    "index.tsx", "index.ts", "index.js", "index.jsx", "app.py", "server.js",
+];
+
+/// Framework/library signatures recognized inside a manifest file's raw
+/// text, checked case-insensitively. Matching the raw text (rather than
+/// parsing each manifest's format) keeps a malformed manifest from failing
+/// detection outright — it just yields no matches.
+const KNOWN_FRAMEWORK_SIGNATURES: &[(&str, &str)] = &[
+    ("\"react\"", "React"),
+    ("\"vue\"", "Vue"),
+    ("\"svelte\"", "Svelte"),
+    ("\"next\"", "Next.js"),
+    ("\"nuxt\"", "Nuxt"),
+    ("\"express\"", "Express"),
+    ("\"@angular/core\"", "Angular"),
+    ("django", "Django"),
+    ("flask", "Flask"),
+    ("fastapi", "FastAPI"),
+    ("torch", "PyTorch"),
+    ("tensorflow", "TensorFlow"),
+    ("tokio", "Tokio"),
+    ("actix-web", "Actix Web"),
+    ("axum", "Axum"),
+    ("rocket", "Rocket"),
+    ("gin-gonic/gin", "Gin"),
+    ("labstack/echo", "Echo"),
+    ("gofiber/fiber", "Fiber"),
+];
+
+/// Aggregates line counts (already computed during the scan) by detected
+/// language, largest first.
+fn top_languages_by_line_count(entries: &[FileEntry]) -> Vec<LanguageStat> {
+    let mut totals: HashMap<String, (usize, usize)> = HashMap::new();
+    for entry in entries {
+        if entry.is_dir {
+            continue;
+        }
+        let extension = Path::new(&entry.relative_path).extension().and_then(|e| e.to_str()).unwrap_or("");
+        let Some(language) = skeleton::SupportedLanguage::from_extension(extension) else {
+            continue;
+        };
+        let stat = totals.entry(format!("{:?}", language)).or_insert((0, 0));
+        stat.0 += 1;
+        stat.1 += entry.line_count.unwrap_or(0);
+    }
+
+    let mut stats: Vec<LanguageStat> = totals
+        .into_iter()
+        .map(|(language, (file_count, line_count))| LanguageStat { language, file_count, line_count })
+        .collect();
+    stats.sort_by(|a, b| b.line_count.cmp(&a.line_count));
+    stats.truncate(TOP_LANGUAGE_COUNT);
+    stats
+}
+
+/// Whether a scanned file's relative path looks like a test, by directory
+/// (`tests/`, `__tests__/`) or filename convention (`test_*.py`, `*.spec.ts`).
+fn is_test_file(relative_path: &str) -> bool {
+    let lower = relative_path.to_lowercase();
+    let file_name = Path::new(&lower).file_name().and_then(|f| f.to_str()).unwrap_or("");
+    lower.contains("/test/") || lower.contains("/tests/") || lower.contains("/__tests__/")
+        || file_name.starts_with("test_")
+        || file_name.ends_with("_test.py")
+        || file_name.ends_with(".test.ts")
+        || file_name.ends_with(".test.tsx")
+        || file_name.ends_with(".test.js")
+        || file_name.ends_with(".spec.ts")
+        || file_name.ends_with(".spec.js")
+}
+
+/// Scanned files (of a supported language) whose basename matches a
+/// well-known entry-point filename, sorted for stable output.
+fn detect_entry_points(entries: &[FileEntry]) -> Vec<String> {
+    let mut found: Vec<String> = entries
+        .iter()
+        .filter(|e| !e.is_dir)
+        .filter(|e| {
+            Path::new(&e.relative_path)
+                .file_name()
+                .and_then(|f| f.to_str())
+                .is_some_and(|name| KNOWN_ENTRY_POINT_NAMES.contains(&name))
+        })
+        .map(|e| e.relative_path.clone())
+        .collect();
+    found.sort();
+    found
+}
+
+/// Framework names whose signature appears in a single manifest's contents.
+fn detect_frameworks_in_manifest(content: &str) -> Vec<&'static str> {
+    let lower = content.to_lowercase();
+    KNOWN_FRAMEWORK_SIGNATURES
+        .iter()
+        .filter(|(signature, _)| lower.contains(&signature.to_lowercase()))
+        .map(|(_, name)| *name)
+        .collect()
+}
+
+/// Reads the well-known manifest files at the project root (missing or
+/// unreadable ones are silently skipped) and returns the union of
+/// frameworks detected across them.
+fn detect_project_frameworks(root: &Path) -> Vec<String> {
+    const MANIFESTS: &[&str] = &["package.json", "Cargo.toml", "pyproject.toml", "go.mod"];
+    let mut found: Vec<&'static str> = Vec::new();
+    for manifest in MANIFESTS {
+        if let Ok(content) = std::fs::read_to_string(root.join(manifest)) {
+            for name in detect_frameworks_in_manifest(&content) {
+                if !found.contains(&name) {
+                    found.push(name);
+                }
+            }
+        }
+    }
+    found.into_iter().map(String::from).collect()
+}
+
+/// Builds a `ProjectSummary` from an already-scanned file list, so the
+/// expensive walk only ever happens once per call.
+fn build_project_summary(root: &Path, entries: &[FileEntry]) -> ProjectSummary {
+    let mut source_file_count = 0;
+    let mut test_file_count = 0;
+    for entry in entries {
+        if entry.is_dir {
+            continue;
+        }
+        let extension = Path::new(&entry.relative_path).extension().and_then(|e| e.to_str()).unwrap_or("");
+        if skeleton::SupportedLanguage::from_extension(extension).is_none() {
+            continue;
+        }
+        if is_test_file(&entry.relative_path) {
+            test_file_count += 1;
+        } else {
+            source_file_count += 1;
+        }
+    }
+
+    ProjectSummary {
+        top_languages: top_languages_by_line_count(entries),
+        frameworks: detect_project_frameworks(root),
+        entry_points: detect_entry_points(entries),
+        source_file_count,
+        test_file_count,
+    }
+}
+
+/// Renders a `ProjectSummary` as a short natural-language-ish header block,
+/// for prepending to a generated prompt so the model has orientation before
+/// it sees the file tree.
+fn render_project_summary_block(summary: &ProjectSummary) -> String {
+    let mut block = String::from("Project summary:\n");
+
+    if summary.top_languages.is_empty() {
+        block.push_str("- Languages: (none detected)\n");
+    } else {
+        let langs: Vec<String> = summary
+            .top_languages
+            .iter()
+            .map(|l| format!("{} ({} lines, {} files)", l.language, l.line_count, l.file_count))
+            .collect();
+        block.push_str(&format!("- Languages: {}\n", langs.join(", ")));
+    }
+
+    if summary.frameworks.is_empty() {
+        block.push_str("- Frameworks: (none detected)\n");
+    } else {
+        block.push_str(&format!("- Frameworks: {}\n", summary.frameworks.join(", ")));
+    }
+
+    if summary.entry_points.is_empty() {
+        block.push_str("- Entry points: (none detected)\n");
+    } else {
+        block.push_str(&format!("- Entry points: {}\n", summary.entry_points.join(", ")));
+    }
+
+    block.push_str(&format!(
+        "- Files: {} source, {} tests\n",
+        summary.source_file_count, summary.test_file_count
+    ));
+
+    block
+}
+
+/// Walks `path` (same ignore rules as `scan_project`) and produces a short
+/// heuristic overview: top languages by line count, frameworks detected
+/// from manifest files, entry points, and a test-vs-source file count.
+#[tauri::command]
+async fn summarize_project(path: String) -> Result<ProjectSummary, String> {
+    let root = Path::new(&path);
+    let (entries, _warnings) = scan_project_entries(
+        root,
+        DEFAULT_MAX_SCAN_FILE_SIZE,
+        false,
+        None,
+        None,
+        None,
+        None,
+        None,
+        Arc::new(effective_ignored_dirs(None)),
+        true,
+    )?;
+    Ok(build_project_summary(root, &entries))
+}
+
+/// A single 1-indexed inclusive line span, as supplied by a `RangeSelection`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct LineRange {
+    start: usize,
+    end: usize,
+}
+
+/// One file's line-range selection for `generate_prompt`: instead of the
+/// whole file or a skeleton, only `ranges` is included, each wrapped in a
+/// "lines N-M of TOTAL" marker. The `mode: "Range"` field is only present so
+/// a single JS-side selection-entry type can discriminate Full/Skeleton/
+/// Exclude/Range without a separate shape per mode; it's not read here.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RangeSelection {
+    path: String,
+    ranges: Vec<LineRange>,
+}
+
+/// Clamps each of `ranges` to `[1, total_lines]` (1-indexed, inclusive),
+/// drops any that end up empty once clamped, sorts by start line, then
+/// merges overlapping or touching ranges (`1-10` and `5-20` become `1-20`)
+/// so the same line never appears twice in the rendered output.
+fn merge_line_ranges(ranges: &[LineRange], total_lines: usize) -> Vec<LineRange> {
+    if total_lines == 0 {
+        return Vec::new();
+    }
+
+    let mut clamped: Vec<LineRange> = ranges
+        .iter()
+        .filter_map(|r| {
+            let start = r.start.max(1).min(total_lines);
+            let end = r.end.max(1).min(total_lines);
+            (start <= end).then_some(LineRange { start, end })
+        })
+        .collect();
+    clamped.sort_by_key(|r| r.start);
+
+    let mut merged: Vec<LineRange> = Vec::new();
+    for range in clamped.drain(..) {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end + 1 => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+/// Renders `ranges` (already merged/clamped by `merge_line_ranges`) from
+/// `content`, each preceded by a `// ... (lines N-M of TOTAL) ...` marker so
+/// the model can tell what was included and how much of the file it's
+/// missing.
+fn render_line_ranges(content: &str, ranges: &[LineRange], total_lines: usize) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut output = String::new();
+    for range in ranges {
+        output.push_str(&format!("// ... (lines {}-{} of {total_lines}) ...\n", range.start, range.end));
+        for line in &lines[range.start - 1..range.end] {
+            output.push_str(line);
+            output.push('\n');
+        }
+    }
+    output
+}
+
+/// `generate_prompt`'s return value. `prompt` is rendered from whatever
+/// files/skeletons were gathered before `cancelled` went true; on the
+/// non-cancelled path `cancelled` is simply `false` and `prompt` is the
+/// complete result, same as before this wrapper existed.
+#[derive(Debug, Serialize, Deserialize)]
+struct GeneratePromptResult {
+    prompt: String,
+    cancelled: bool,
+}
+
+/// Assembles a prompt from a template, filling in the built-in placeholders
+/// (`{{file_tree}}`, `{{files}}`, `{{skeletons}}`, `{{token_count}}`,
+/// `{{date}}`) from `paths`/`root_path`, merged with caller-supplied
+/// `variables`. Built-in placeholders take precedence over a user variable
+/// of the same name. When `include_project_summary` is set, a
+/// `summarize_project`-style block is prepended to the rendered result.
+/// When `use_rules` is set, each path's mode (full content, skeleton, or
+/// excluded) is looked up from that project's persisted
+/// [`selection_rules::SelectionRules`] instead of sending every path as
+/// both full content and a skeleton. When `operation_id` is given and gets
+/// cancelled mid-assembly, the files/skeletons gathered so far are still
+/// rendered into the template and returned with `cancelled: true`, rather
+/// than throwing away the part of the prompt already built. `range_selections`
+/// entries take priority over everything else: a path listed there has only
+/// its (merged, clamped) line ranges included, each wrapped in a "lines N-M
+/// of TOTAL" marker, rather than full content or a skeleton - for pulling in
+/// a single function from an otherwise huge file.
+#[tauri::command]
+async fn generate_prompt(
+    paths: Vec<String>,
+    root_path: String,
+    template: String,
+    variables: Option<HashMap<String, String>>,
+    entries: Option<Vec<FileEntry>>,
+    include_project_summary: Option<bool>,
+    use_rules: Option<bool>,
+    // Paths to render with their full, unmodified content no matter what
+    // `use_rules` or `full_below_lines` would otherwise pick - for files
+    // (a prompt template, a critical schema) that must reach the model
+    // verbatim regardless of size or language.
+    raw_include: Option<Vec<String>>,
+    range_selections: Option<Vec<RangeSelection>>,
+    operation_id: Option<String>,
+    app: tauri::AppHandle,
+    cancellation: State<'_, CancellationRegistry>,
+) -> Result<GeneratePromptResult, String> {
+    let raw_include: HashSet<String> = raw_include.unwrap_or_default().into_iter().collect();
+    let range_selections: HashMap<String, Vec<LineRange>> = range_selections
+        .unwrap_or_default()
+        .into_iter()
+        .map(|entry| (entry.path, entry.ranges))
+        .collect();
+    let token = operation_id.as_deref().map(|id| cancellation.register(id));
+    // Computed before `entries` is consumed by the file-tree match below.
+    let project_summary = if include_project_summary.unwrap_or(false) {
+        let root = Path::new(&root_path);
+        let summary = match &entries {
+            Some(entries) => build_project_summary(root, entries),
+            None => {
+                let (scanned, _warnings) = scan_project_entries(
+                    root,
+                    DEFAULT_MAX_SCAN_FILE_SIZE,
+                    false,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    Arc::new(effective_ignored_dirs(None)),
+                    true,
+                )?;
+                build_project_summary(root, &scanned)
+            }
+        };
+        Some(render_project_summary_block(&summary))
+    } else {
+        None
+    };
+
+    // Also computed before `entries` is consumed below.
+    let selection_modes = if use_rules.unwrap_or(false) {
+        let root = Path::new(&root_path);
+        let rules = load_selection_rules_from_disk(&app, &root_path)?;
+        let rule_entries = match &entries {
+            Some(entries) => entries.clone(),
+            None => {
+                let (scanned, _warnings) = scan_project_entries(
+                    root,
+                    DEFAULT_MAX_SCAN_FILE_SIZE,
+                    false,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    Arc::new(effective_ignored_dirs(None)),
+                    false,
+                )?;
+                scanned
+            }
+        };
+        Some(selection_rules::apply_selection_rules(root, &rule_entries, &rules))
+    } else {
+        None
+    };
+
+    // When the caller has scan data on hand, render an annotated tree (line
+    // counts, skeleton markers) from it instead of re-deriving a bare tree
+    // from `paths` alone.
+    let file_tree = match entries {
+        Some(entries) => render_file_tree(entries, root_path.clone(), paths.clone(), None),
+        None => build_tree(paths.clone(), root_path.clone()),
+    };
+
+    let mut files = String::new();
+    let mut skeletons = String::new();
+    let mut cancelled = false;
+    for path in &paths {
+        if token.as_ref().is_some_and(|t| t.load(Ordering::SeqCst)) {
+            cancelled = true;
+            break;
+        }
+
+        if let Some(ranges) = range_selections.get(path) {
+            let Ok(content) = std::fs::read_to_string(path) else {
+                continue;
+            };
+            let relative = to_tree_relative(path, &root_path);
+            let total_lines = content.lines().count();
+            let merged = merge_line_ranges(ranges, total_lines);
+            let rendered = render_line_ranges(&content, &merged, total_lines);
+            files.push_str(&format!("// {relative}\n{rendered}\n"));
+            continue;
+        }
+
+        let mode = if raw_include.contains(path) {
+            Some(selection_rules::SelectionMode::Full)
+        } else {
+            selection_modes.as_ref().and_then(|modes| modes.get(path)).copied()
+        };
+        if mode == Some(selection_rules::SelectionMode::Exclude) {
+            continue;
+        }
+        let Ok(content) = std::fs::read_to_string(path) else {
+            continue;
+        };
+        let relative = to_tree_relative(path, &root_path);
+
+        if mode != Some(selection_rules::SelectionMode::Skeleton) {
+            files.push_str(&format!("// {relative}\n{content}\n\n"));
+        }
+        if mode != Some(selection_rules::SelectionMode::Full) {
+            let extension = Path::new(path).extension().and_then(|e| e.to_str()).unwrap_or("");
+            let skeleton = skeleton::skeletonize_with_path(&content, extension, Some(path)).skeleton;
+            skeletons.push_str(&format!("// {relative}\n{skeleton}\n\n"));
+        }
+    }
+
+    let token_count = TOKENIZER.encode_with_special_tokens(&files).len();
+
+    let mut vars = variables.unwrap_or_default();
+    vars.insert("file_tree".to_string(), file_tree);
+    vars.insert("files".to_string(), files);
+    vars.insert("skeletons".to_string(), skeletons);
+    vars.insert("token_count".to_string(), token_count.to_string());
+    vars.insert("date".to_string(), today_date_string());
+
+    let rendered = render_prompt_template(&template, &vars)?;
+    let prompt = match project_summary {
+        Some(block) => format!("{}\n\n{}", block, rendered),
+        None => rendered,
+    };
+
+    if let Some(id) = operation_id.as_deref() {
+        cancellation.unregister(id);
+    }
+
+    Ok(GeneratePromptResult { prompt, cancelled })
+}
+
+/// Above this size, `copy_to_clipboard` skips the write and reports
+/// `TooLarge` instead: a webview clipboard write of tens of megabytes can
+/// visibly freeze the UI, so past this point exporting to a file is the
+/// better move.
+const CLIPBOARD_WARN_THRESHOLD_BYTES: usize = 5 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ClipboardCopyStatus {
+    Copied,
+    TooLarge,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ClipboardCopyResult {
+    status: ClipboardCopyStatus,
+    byte_len: usize,
+}
+
+/// Whether a clipboard write of `byte_len` bytes should proceed, or be
+/// reported as `TooLarge` instead, given `threshold`.
+fn clipboard_copy_status(byte_len: usize, threshold: usize) -> ClipboardCopyStatus {
+    if byte_len > threshold {
+        ClipboardCopyStatus::TooLarge
+    } else {
+        ClipboardCopyStatus::Copied
+    }
+}
+
+/// Writes `content` to the system clipboard through the clipboard-manager
+/// plugin's Rust API, so a large generated prompt never has to round-trip
+/// through JS to be copied. The write happens on a blocking-pool thread, not
+/// the async runtime's own worker, so it can't stall other in-flight
+/// commands; a "clipboard-copy-complete" event fires once it settles either
+/// way. If `content` exceeds `threshold_bytes` (default
+/// `CLIPBOARD_WARN_THRESHOLD_BYTES`), the write is skipped entirely and
+/// `ClipboardCopyStatus::TooLarge` is returned, so the caller can suggest
+/// exporting to a file instead.
+#[tauri::command]
+async fn copy_to_clipboard(
+    content: String,
+    threshold_bytes: Option<usize>,
+    app: tauri::AppHandle,
+) -> Result<ClipboardCopyResult, String> {
+    let threshold = threshold_bytes.unwrap_or(CLIPBOARD_WARN_THRESHOLD_BYTES);
+    let byte_len = content.len();
+
+    if clipboard_copy_status(byte_len, threshold) == ClipboardCopyStatus::TooLarge {
+        let result = ClipboardCopyResult { status: ClipboardCopyStatus::TooLarge, byte_len };
+        let _ = app.emit("clipboard-copy-complete", &result);
+        return Ok(result);
+    }
+
+    let app_for_write = app.clone();
+    tauri::async_runtime::spawn_blocking(move || app_for_write.clipboard().write_text(content))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+
+    let result = ClipboardCopyResult { status: ClipboardCopyStatus::Copied, byte_len };
+    let _ = app.emit("clipboard-copy-complete", &result);
+    Ok(result)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct DiffLine {
+    #[serde(rename = "type")]
+    line_type: String,
+    line: String,
+    old_line_num: Option<usize>,
+    new_line_num: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct FileDiff {
+    path: String,
+    relative_path: String,
+    previous: String,
+    current: String,
+    diff: Vec<DiffLine>,
+}
+
+/// Take a snapshot of current file contents for diff comparison
+#[tauri::command]
+async fn take_snapshot(paths: Vec<String>, state: State<'_, SnapshotState>) -> Result<usize, String> {
+    let mut snapshot = state.snapshot.lock().map_err(|_| "Lock error")?;
+    snapshot.clear();
+    
+    for path in &paths {
+        if let Ok(content) = std::fs::read_to_string(path) {
+            snapshot.insert(path.clone(), content);
+        }
+    }
+    
+    Ok(snapshot.len())
+}
+
+/// Get diffs between snapshot and current file contents
+#[tauri::command]
+async fn get_diffs(paths: Vec<String>, root_path: String, state: State<'_, SnapshotState>) -> Result<Vec<FileDiff>, String> {
+    let snapshot = state.snapshot.lock().map_err(|_| "Lock error")?;
+    let root = Path::new(&root_path);
+    let mut diffs = Vec::new();
+    
+    for path in paths {
+        let Some(prev_content) = snapshot.get(&path) else { continue };
+        let Ok(curr_content) = std::fs::read_to_string(&path) else { continue };
+        
+        if prev_content == &curr_content { continue; }
+        
+        let text_diff = TextDiff::from_lines(prev_content, &curr_content);
+        let mut diff_lines = Vec::new();
+        let mut old_line = 1usize;
+        let mut new_line = 1usize;
+        
+        for change in text_diff.iter_all_changes() {
+            let line = change.value().trim_end_matches('\n').to_string();
+            match change.tag() {
+                ChangeTag::Equal => {
+                    diff_lines.push(DiffLine { line_type: "unchanged".into(), line, old_line_num: Some(old_line), new_line_num: Some(new_line) });
+                    old_line += 1;
+                    new_line += 1;
+                }
+                ChangeTag::Delete => {
+                    diff_lines.push(DiffLine { line_type: "removed".into(), line, old_line_num: Some(old_line), new_line_num: None });
+                    old_line += 1;
+                }
+                ChangeTag::Insert => {
+                    diff_lines.push(DiffLine { line_type: "added".into(), line, old_line_num: None, new_line_num: Some(new_line) });
+                    new_line += 1;
+                }
+            }
+        }
+        
+        let relative_path = Path::new(&path).strip_prefix(root)
+            .map(|p| p.to_string_lossy().replace('\\', "/"))
+            .unwrap_or_else(|_| path.clone());
+        
+        diffs.push(FileDiff {
+            path: path.clone(),
+            relative_path,
+            previous: prev_content.clone(),
+            current: curr_content,
+            diff: diff_lines,
+        });
+    }
+    
+    Ok(diffs)
+}
+
+/// Structural diff between the skeletons of two files (typically two
+/// versions of the same file), so a caller can see which definitions were
+/// added or removed without wading through the raw skeleton text.
+#[derive(Debug, Serialize, Deserialize)]
+struct SkeletonDiff {
+    added_lines: Vec<String>,
+    removed_lines: Vec<String>,
+    unchanged_lines: usize,
+}
+
+/// Runs a line-level diff between two skeleton texts, splitting the result
+/// into lines only `b` has (added), lines only `a` has (removed), and a
+/// count of lines common to both.
+fn diff_skeleton_lines(skeleton_a: &str, skeleton_b: &str) -> SkeletonDiff {
+    let mut added_lines = Vec::new();
+    let mut removed_lines = Vec::new();
+    let mut unchanged_lines = 0;
+
+    for change in TextDiff::from_lines(skeleton_a, skeleton_b).iter_all_changes() {
+        let line = change.value().trim_end_matches('\n').to_string();
+        match change.tag() {
+            ChangeTag::Equal => unchanged_lines += 1,
+            ChangeTag::Delete => removed_lines.push(line),
+            ChangeTag::Insert => added_lines.push(line),
+        }
+    }
+
+    SkeletonDiff { added_lines, removed_lines, unchanged_lines }
+}
+
+/// Compare the skeletons of two files (e.g. an old and new version of the
+/// same source file) so a caller can see structurally what was added or
+/// removed, rather than diffing the raw file contents.
+#[tauri::command]
+fn skeletonize_diff(path_a: String, path_b: String) -> Result<SkeletonDiff, String> {
+    let content_a = std::fs::read_to_string(&path_a).map_err(|e| e.to_string())?;
+    let content_b = std::fs::read_to_string(&path_b).map_err(|e| e.to_string())?;
+
+    let ext_a = Path::new(&path_a).extension().and_then(|e| e.to_str()).unwrap_or("");
+    let ext_b = Path::new(&path_b).extension().and_then(|e| e.to_str()).unwrap_or("");
+
+    let skeleton_a = skeleton::skeletonize_with_path(&content_a, ext_a, Some(&path_a)).skeleton;
+    let skeleton_b = skeleton::skeletonize_with_path(&content_b, ext_b, Some(&path_b)).skeleton;
+
+    Ok(diff_skeleton_lines(&skeleton_a, &skeleton_b))
+}
+
+#[tauri::command]
+fn get_perf_metrics(perf: State<'_, PerfMetricsState>) -> PerfMetrics {
+    let mut metrics = perf.metrics.lock().map(|m| m.clone()).unwrap_or_default();
+    metrics.token_cache_size = TOKEN_COUNT_CACHE.lock().map(|c| c.len()).unwrap_or(0);
+    metrics.skeleton_cache_size = SKELETON_CACHE.lock().map(|c| c.len()).unwrap_or(0);
+    metrics
+}
+
+/// Clear the snapshot
+#[tauri::command]
+async fn clear_snapshot(state: State<'_, SnapshotState>) -> Result<(), String> {
+    let mut snapshot = state.snapshot.lock().map_err(|_| "Lock error")?;
+    snapshot.clear();
+    Ok(())
+}
+
+/// Register a custom extension-to-language mapping (e.g. `"blade"` ->
+/// `"TypeScript"`) so non-standard extensions like `.blade.php` can still
+/// be skeletonized. `language` matches a `SupportedLanguage` variant name
+/// case-insensitively.
+#[tauri::command]
+fn set_language_override(
+    extension: String,
+    language: String,
+    state: State<'_, LanguageOverrides>,
+) -> Result<(), String> {
+    let lang = skeleton::SupportedLanguage::from_name(&language)
+        .ok_or_else(|| format!("Unknown language: {}", language))?;
+    let mut overrides = state.overrides.lock().map_err(|_| "Lock error")?;
+    overrides.insert(extension.to_lowercase(), lang);
+    Ok(())
+}
+
+/// Remove a previously registered extension-to-language override.
+#[tauri::command]
+fn remove_language_override(extension: String, state: State<'_, LanguageOverrides>) -> Result<(), String> {
+    let mut overrides = state.overrides.lock().map_err(|_| "Lock error")?;
+    overrides.remove(&extension.to_lowercase());
+    Ok(())
+}
+
+/// Clear the skeleton cache, e.g. when a user suspects a stale result.
+/// Returns the number of entries that were evicted.
+#[tauri::command]
+fn clear_skeleton_cache() -> Result<usize, String> {
+    let mut cache = SKELETON_CACHE.lock().map_err(|_| "Lock error")?;
+    let mut order = SKELETON_CACHE_ORDER.lock().map_err(|_| "Lock error")?;
+    let count = cache.len();
+    cache.clear();
+    order.clear();
+    Ok(count)
+}
+
+#[cfg_attr(mobile, tauri::mobile_entry_point)]
+
+pub fn run() {
+    // Force tokenizer initialization at startup (downloads vocab on first run)
+    let _ = &*TOKENIZER;
+
+    // Warm the tree-sitter parser pool on the main thread and on every rayon
+    // worker thread, so the first file `skeletonize_files` hands a given
+    // thread doesn't pay `Language` setup cost that a later file on the same
+    // thread would otherwise have shared.
+    skeleton::preload_parsers();
+    rayon::broadcast(|_| skeleton::preload_parsers());
+
+    tauri::Builder::default()
+
+        .plugin(tauri_plugin_fs::init())
+
+        .plugin(tauri_plugin_dialog::init())
+
+        .plugin(tauri_plugin_opener::init())
+
+        .plugin(tauri_plugin_clipboard_manager::init())
+
+        .setup(|app| {
+
+            app.manage(WatcherState {
+                watcher: Mutex::new(None),
+                debounce_ms: Mutex::new(DEFAULT_WATCH_DEBOUNCE_MS),
+                roots: Mutex::new(Vec::new()),
+            });
+            app.manage(SnapshotState { snapshot: Mutex::new(HashMap::new()) });
+            app.manage(PerfMetricsState { metrics: Mutex::new(PerfMetrics::default()) });
+            app.manage(LanguageOverrides { overrides: Mutex::new(HashMap::new()) });
+            app.manage(SkeletonEstimateState { cancelled: AtomicBool::new(false) });
+            app.manage(CancellationRegistry::default());
+
+            if let Ok(persisted) = load_ignore_config_from_disk(app.handle()) {
+                if let Ok(mut config) = IGNORE_CONFIG.lock() {
+                    *config = persisted;
+                }
+            }
+
+            Ok(())
+
+        })
+
+        .invoke_handler(tauri::generate_handler![greet, scan_project, scan_project_streaming, scan_stats, count_lines_for, compute_dir_stats, list_changed_files, build_import_graph, build_tree, render_file_tree, read_file_content, compute_file_hashes, find_duplicate_content, scan_for_secrets, estimate_skeletons, cancel_skeleton_estimates, watch_project, get_watch_config, get_watched_roots, unwatch_project, unwatch_path, stop_watching, skeletonize_file, skeletonize_files, skeletonize_project, skeletonize_diff, count_tokens, count_tokens_for_files, get_token_estimate, get_files_token_estimate, generate_prompt, create_pack_snapshot, generate_delta_prompt, cancel_operation, summarize_project, copy_to_clipboard, save_template, list_templates, delete_template, take_snapshot, get_diffs, clear_snapshot, clear_skeleton_cache, set_language_override, remove_language_override, get_perf_metrics, get_ignore_config, set_ignore_config, get_selection_rules, set_selection_rules, compute_selection_modes])
+
+        .run(tauri::generate_context!())
+
+        .expect("error while running tauri application");
+
+}
+
+#[cfg(test)]
+mod lib_tests {
+    use super::*;
+    use std::path::{Path, PathBuf};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    struct TestDir {
+        path: PathBuf,
+    }
+
+    impl TestDir {
+        fn new(prefix: &str) -> Self {
+            let mut path = std::env::temp_dir();
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_nanos();
+            path.push(format!("{}_{}_{}", prefix, std::process::id(), now));
+            std::fs::create_dir_all(&path).unwrap();
+            Self { path }
+        }
+
+        fn path(&self) -> &Path {
             &self.path
         }
     }
 
-    impl Drop for TestDir {
-        fn drop(&mut self) {
-            let _ = std::fs::remove_dir_all(&self.path);
+    impl Drop for TestDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.path);
+        }
+    }
+
+    #[test]
+    fn normalize_relative_path_replaces_backslashes() {
+        let path = Path::new("foo\\bar\\baz.txt");
+        assert_eq!(normalize_relative_path(path), "foo/bar/baz.txt");
+    }
+
+    #[test]
+    fn scan_project_entries_collects_dirs_and_paths() {
+        let temp = TestDir::new("prompt_pack_lite_scan");
+        let root = temp.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src").join("main.rs"), "fn main() {}\n").unwrap();
+
+        let (entries, _warnings) = scan_project_entries(root, DEFAULT_MAX_SCAN_FILE_SIZE, false, None, None, None, None, None, Arc::new(effective_ignored_dirs(None)), true).expect("scan project");
+        let main_rs = entries
+            .iter()
+            .find(|entry| entry.relative_path == "src/main.rs")
+            .expect("main.rs entry");
+        assert_eq!(main_rs.line_count, Some(1));
+    }
+
+    #[test]
+    fn scan_project_entries_skips_line_count_over_size_limit() {
+        let temp = TestDir::new("prompt_pack_lite_scan_limit");
+        let root = temp.path();
+        std::fs::write(root.join("big.rs"), "fn main() {}\n").unwrap();
+
+        let (entries, _warnings) = scan_project_entries(root, 0, false, None, None, None, None, None, Arc::new(effective_ignored_dirs(None)), true).expect("scan project");
+        let big_rs = entries
+            .iter()
+            .find(|entry| entry.relative_path == "big.rs")
+            .expect("big.rs entry");
+        assert_eq!(big_rs.line_count, None);
+    }
+
+    #[test]
+    fn scan_project_entries_skips_line_count_for_csv() {
+        let temp = TestDir::new("prompt_pack_lite_scan_csv");
+        let root = temp.path();
+        std::fs::write(root.join("data.csv"), "a,b\n1,2\n").unwrap();
+
+        let (entries, _warnings) = scan_project_entries(root, DEFAULT_MAX_SCAN_FILE_SIZE, false, None, None, None, None, None, Arc::new(effective_ignored_dirs(None)), true).expect("scan project");
+        let csv = entries
+            .iter()
+            .find(|entry| entry.relative_path == "data.csv")
+            .expect("data.csv entry");
+        assert_eq!(csv.line_count, None);
+    }
+
+    #[test]
+    fn scan_project_entries_annotates_git_status_when_requested() {
+        let temp = TestDir::new("prompt_pack_lite_git");
+        let root = temp.path();
+
+        let run_git = |args: &[&str]| {
+            std::process::Command::new("git")
+                .arg("-C")
+                .arg(root)
+                .args(args)
+                .output()
+                .expect("run git")
+        };
+
+        run_git(&["init", "-q"]);
+        run_git(&["config", "user.email", "test@example.com"]);
+        run_git(&["config", "user.name", "Test"]);
+        std::fs::write(root.join("committed.rs"), "fn a() {}\n").unwrap();
+        run_git(&["add", "committed.rs"]);
+        run_git(&["commit", "-q", "-m", "initial"]);
+        std::fs::write(root.join("committed.rs"), "fn a() { /* changed */ }\n").unwrap();
+        std::fs::write(root.join("untracked.rs"), "fn b() {}\n").unwrap();
+
+        let (entries, _warnings) = scan_project_entries(root, DEFAULT_MAX_SCAN_FILE_SIZE, true, None, None, None, None, None, Arc::new(effective_ignored_dirs(None)), true).expect("scan project");
+        let committed = entries
+            .iter()
+            .find(|entry| entry.relative_path == "committed.rs")
+            .expect("committed.rs entry");
+        let untracked = entries
+            .iter()
+            .find(|entry| entry.relative_path == "untracked.rs")
+            .expect("untracked.rs entry");
+
+        assert_eq!(committed.git_status, Some(GitFileStatus::Modified));
+        assert!(committed.last_commit_epoch.is_some());
+        assert_eq!(untracked.git_status, Some(GitFileStatus::Untracked));
+    }
+
+    #[test]
+    fn scan_project_entries_leaves_git_fields_none_when_flag_off() {
+        let temp = TestDir::new("prompt_pack_lite_git_off");
+        let root = temp.path();
+        std::fs::write(root.join("a.rs"), "fn a() {}\n").unwrap();
+
+        let (entries, _warnings) = scan_project_entries(root, DEFAULT_MAX_SCAN_FILE_SIZE, false, None, None, None, None, None, Arc::new(effective_ignored_dirs(None)), true).expect("scan project");
+        let a = entries.iter().find(|entry| entry.relative_path == "a.rs").expect("a.rs entry");
+        assert_eq!(a.git_status, None);
+        assert_eq!(a.last_commit_epoch, None);
+    }
+
+    #[test]
+    fn autodetect_base_ref_prefers_main_then_master() {
+        let temp = TestDir::new("prompt_pack_lite_base_ref");
+        let root = temp.path();
+
+        let run_git = |args: &[&str]| {
+            std::process::Command::new("git")
+                .arg("-C")
+                .arg(root)
+                .args(args)
+                .output()
+                .expect("run git")
+        };
+
+        run_git(&["init", "-q", "-b", "master"]);
+        run_git(&["config", "user.email", "test@example.com"]);
+        run_git(&["config", "user.name", "Test"]);
+        std::fs::write(root.join("a.txt"), "a\n").unwrap();
+        run_git(&["add", "a.txt"]);
+        run_git(&["commit", "-q", "-m", "initial"]);
+
+        assert_eq!(autodetect_base_ref(root), "master");
+
+        run_git(&["branch", "main"]);
+        assert_eq!(autodetect_base_ref(root), "main");
+    }
+
+    #[test]
+    fn scan_project_entries_respects_max_depth() {
+        let temp = TestDir::new("prompt_pack_lite_depth");
+        let root = temp.path();
+        std::fs::write(root.join("root.txt"), "a\n").unwrap();
+        std::fs::create_dir_all(root.join("sub").join("nested")).unwrap();
+        std::fs::write(root.join("sub").join("nested").join("deep.txt"), "b\n").unwrap();
+
+        let (entries, _warnings) = scan_project_entries(root, DEFAULT_MAX_SCAN_FILE_SIZE, false, Some(1), None, None, None, None, Arc::new(effective_ignored_dirs(None)), true).expect("scan project");
+        let paths: HashSet<&str> = entries.iter().map(|e| e.relative_path.as_str()).collect();
+
+        assert!(paths.contains("root.txt"));
+        assert!(paths.contains("sub"));
+        assert!(!paths.iter().any(|p| p.starts_with("sub/")));
+    }
+
+    #[test]
+    fn scan_project_entries_respects_max_files() {
+        let temp = TestDir::new("prompt_pack_lite_max_files");
+        let root = temp.path();
+        for i in 0..20 {
+            std::fs::write(root.join(format!("file_{i}.txt")), "x\n").unwrap();
+        }
+
+        let (entries, warnings) =
+            scan_project_entries(root, DEFAULT_MAX_SCAN_FILE_SIZE, false, None, Some(5), None, None, None, Arc::new(effective_ignored_dirs(None)), true).expect("scan project");
+        let file_count = entries.iter().filter(|e| !e.is_dir).count();
+
+        assert_eq!(file_count, 5);
+        assert!(warnings.limit_hit);
+    }
+
+    #[test]
+    fn scan_project_entries_excludes_files_below_min_size() {
+        let temp = TestDir::new("prompt_pack_lite_min_size");
+        let root = temp.path();
+        std::fs::write(root.join("small.txt"), vec![b'x'; 500]).unwrap();
+        std::fs::write(root.join("big.txt"), vec![b'x'; 2000]).unwrap();
+
+        let (entries, _warnings) =
+            scan_project_entries(root, DEFAULT_MAX_SCAN_FILE_SIZE, false, None, None, Some(1000), None, None, Arc::new(effective_ignored_dirs(None)), true)
+                .expect("scan project");
+        let names: Vec<&str> = entries
+            .iter()
+            .filter(|e| !e.is_dir)
+            .map(|e| e.relative_path.as_str())
+            .collect();
+
+        assert!(!names.contains(&"small.txt"));
+        assert!(names.contains(&"big.txt"));
+    }
+
+    #[test]
+    fn scan_project_entries_size_filter_absent_keeps_all_files() {
+        let temp = TestDir::new("prompt_pack_lite_no_size_filter");
+        let root = temp.path();
+        std::fs::write(root.join("small.txt"), vec![b'x'; 500]).unwrap();
+
+        let (entries, _warnings) =
+            scan_project_entries(root, DEFAULT_MAX_SCAN_FILE_SIZE, false, None, None, None, None, None, Arc::new(effective_ignored_dirs(None)), true)
+                .expect("scan project");
+        let file_count = entries.iter().filter(|e| !e.is_dir).count();
+
+        assert_eq!(file_count, 1);
+    }
+
+    #[test]
+    fn scan_project_entries_never_filters_out_directories_by_size() {
+        // A directory's own on-disk size (e.g. one filesystem block) is
+        // typically far larger than a 1-byte max_size; the size filter
+        // must only ever apply to files, never to the directory entries
+        // themselves.
+        let temp = TestDir::new("prompt_pack_lite_size_filter_keeps_dirs");
+        let root = temp.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src").join("tiny.txt"), "x").unwrap();
+
+        let (entries, _warnings) =
+            scan_project_entries(root, DEFAULT_MAX_SCAN_FILE_SIZE, false, None, None, None, Some(1), None, Arc::new(effective_ignored_dirs(None)), true)
+                .expect("scan project");
+
+        assert!(entries.iter().any(|e| e.is_dir && e.relative_path == "src"));
+    }
+
+    #[test]
+    fn scan_project_entries_rejects_min_size_greater_than_max_size() {
+        let temp = TestDir::new("prompt_pack_lite_invalid_size_range");
+        let root = temp.path();
+
+        let result = scan_project_entries(root, DEFAULT_MAX_SCAN_FILE_SIZE, false, None, None, Some(2000), Some(1000), None, Arc::new(effective_ignored_dirs(None)), true);
+        assert!(result.is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn scan_project_entries_flags_symlinks_and_skips_following_dirs_by_default() {
+        let temp = TestDir::new("prompt_pack_lite_symlinks_default");
+        let root = temp.path();
+        std::fs::create_dir_all(root.join("real")).unwrap();
+        std::fs::write(root.join("real").join("file.txt"), "content\n").unwrap();
+        std::os::unix::fs::symlink(root.join("real"), root.join("link_to_real")).unwrap();
+        std::os::unix::fs::symlink(root.join("real").join("file.txt"), root.join("link_to_file.txt")).unwrap();
+
+        let (entries, _warnings) =
+            scan_project_entries(root, DEFAULT_MAX_SCAN_FILE_SIZE, false, None, None, None, None, None, Arc::new(effective_ignored_dirs(None)), true)
+                .expect("scan project");
+
+        let link_dir = entries.iter().find(|e| e.relative_path == "link_to_real").expect("link_to_real entry");
+        assert!(link_dir.is_symlink);
+        assert!(link_dir.is_dir);
+
+        let link_file = entries.iter().find(|e| e.relative_path == "link_to_file.txt").expect("link_to_file.txt entry");
+        assert!(link_file.is_symlink);
+        assert!(!link_file.is_dir);
+
+        // Not followed by default: nothing from inside the symlinked directory shows up.
+        assert!(!entries.iter().any(|e| e.relative_path == "link_to_real/file.txt"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn scan_project_entries_follows_symlinked_dirs_when_enabled() {
+        let temp = TestDir::new("prompt_pack_lite_symlinks_follow");
+        let root = temp.path();
+        std::fs::create_dir_all(root.join("real")).unwrap();
+        std::fs::write(root.join("real").join("file.txt"), "content\n").unwrap();
+        std::os::unix::fs::symlink(root.join("real"), root.join("link_to_real")).unwrap();
+
+        let (entries, _warnings) = scan_project_entries(
+            root,
+            DEFAULT_MAX_SCAN_FILE_SIZE,
+            false,
+            None,
+            None,
+            None,
+            None,
+            Some(true),
+            Arc::new(effective_ignored_dirs(None)),
+            true,
+        )
+        .expect("scan project");
+
+        assert!(entries.iter().any(|e| e.relative_path == "link_to_real/file.txt"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn scan_project_entries_breaks_symlink_cycles_when_following() {
+        let temp = TestDir::new("prompt_pack_lite_symlink_cycle");
+        let root = temp.path();
+        std::fs::create_dir_all(root.join("a")).unwrap();
+        // A symlink back to the project root creates a cycle when followed.
+        std::os::unix::fs::symlink(root, root.join("a").join("loop")).unwrap();
+
+        let result = scan_project_entries(
+            root,
+            DEFAULT_MAX_SCAN_FILE_SIZE,
+            false,
+            None,
+            None,
+            None,
+            None,
+            Some(true),
+            Arc::new(effective_ignored_dirs(None)),
+            true,
+        );
+
+        // The walk must terminate (no timeout/hang) and succeed.
+        let (_entries, warnings) = result.expect("scan project");
+        assert!(warnings.symlink_cycles > 0);
+    }
+
+    #[test]
+    fn root_labels_uses_final_path_component() {
+        let roots = vec!["/home/dev/app".to_string(), "/home/dev/lib".to_string()];
+        assert_eq!(root_labels(&roots), vec!["app".to_string(), "lib".to_string()]);
+    }
+
+    #[test]
+    fn root_labels_disambiguates_collisions() {
+        let roots = vec!["/home/dev/app".to_string(), "/opt/other/app".to_string()];
+        assert_eq!(root_labels(&roots), vec!["app".to_string(), "app-2".to_string()]);
+    }
+
+    #[test]
+    fn scan_project_entries_multi_root_prefixes_relative_path_with_label() {
+        let temp_a = TestDir::new("prompt_pack_lite_multiroot_a");
+        let temp_b = TestDir::new("prompt_pack_lite_multiroot_b");
+        std::fs::write(temp_a.path().join("shared.rs"), "// a\n").unwrap();
+        std::fs::write(temp_b.path().join("shared.rs"), "// b\n").unwrap();
+
+        let roots = vec![
+            temp_a.path().to_string_lossy().to_string(),
+            temp_b.path().to_string_lossy().to_string(),
+        ];
+        let labels = root_labels(&roots);
+        assert_eq!(labels.len(), 2);
+        assert_ne!(labels[0], labels[1]);
+
+        let mut prefixed = Vec::new();
+        for (root_str, label) in roots.iter().zip(labels.iter()) {
+            let (mut entries, _warnings) = scan_project_entries(
+                Path::new(root_str),
+                DEFAULT_MAX_SCAN_FILE_SIZE,
+                false,
+                None,
+                None,
+                None,
+                None,
+                None,
+                Arc::new(effective_ignored_dirs(None)),
+                true,
+            )
+            .expect("scan project");
+            for entry in &mut entries {
+                entry.relative_path = format!("{}//{}", label, entry.relative_path);
+            }
+            prefixed.append(&mut entries);
+        }
+
+        assert!(prefixed.iter().any(|e| e.relative_path == format!("{}//shared.rs", labels[0])));
+        assert!(prefixed.iter().any(|e| e.relative_path == format!("{}//shared.rs", labels[1])));
+    }
+
+    #[test]
+    fn root_for_changed_path_picks_the_longest_matching_root() {
+        let roots = vec!["/home/dev/app".to_string(), "/home/dev/app/vendor".to_string()];
+        let changed = Path::new("/home/dev/app/vendor/lib.rs");
+        assert_eq!(
+            root_for_changed_path(changed, &roots),
+            Some("/home/dev/app/vendor".to_string())
+        );
+    }
+
+    #[test]
+    fn root_for_changed_path_returns_none_outside_all_roots() {
+        let roots = vec!["/home/dev/app".to_string()];
+        let changed = Path::new("/home/dev/other/lib.rs");
+        assert_eq!(root_for_changed_path(changed, &roots), None);
+    }
+
+    #[test]
+    fn render_prompt_template_substitutes_builtin_and_user_placeholders() {
+        let mut vars = HashMap::new();
+        vars.insert("file_tree".to_string(), "src/main.rs".to_string());
+        vars.insert("focus".to_string(), "the auth flow".to_string());
+
+        let rendered = render_prompt_template(
+            "Review {{focus}}:\n{{file_tree}}",
+            &vars,
+        )
+        .expect("known placeholders should render");
+        assert_eq!(rendered, "Review the auth flow:\nsrc/main.rs");
+    }
+
+    #[test]
+    fn render_prompt_template_errors_on_unknown_placeholder() {
+        let vars = HashMap::new();
+        let err = render_prompt_template("{{nonexistent}}", &vars).expect_err("should fail");
+        assert!(err.contains("nonexistent"), "error was: {err}");
+    }
+
+    #[test]
+    fn render_prompt_template_passes_through_text_without_placeholders() {
+        let vars = HashMap::new();
+        let rendered = render_prompt_template("plain text, no placeholders", &vars).unwrap();
+        assert_eq!(rendered, "plain text, no placeholders");
+    }
+
+    #[test]
+    fn partition_changed_paths_splits_on_hash_match() {
+        let paths = vec!["a.rs".to_string(), "b.rs".to_string(), "c.rs".to_string()];
+        let mut current = HashMap::new();
+        current.insert("a.rs".to_string(), "hash_a".to_string());
+        current.insert("b.rs".to_string(), "hash_b_new".to_string());
+        current.insert("c.rs".to_string(), "hash_c".to_string());
+        let mut snapshot = HashMap::new();
+        snapshot.insert("a.rs".to_string(), "hash_a".to_string());
+        snapshot.insert("b.rs".to_string(), "hash_b_old".to_string());
+        // "c.rs" intentionally absent from the snapshot, as if newly selected.
+
+        let (changed, unchanged) = partition_changed_paths(&paths, &current, &snapshot);
+
+        assert_eq!(changed, vec!["b.rs", "c.rs"]);
+        assert_eq!(unchanged, vec!["a.rs"]);
+    }
+
+    #[test]
+    fn pack_snapshot_id_is_stable_and_distinguishes_projects() {
+        assert_eq!(pack_snapshot_id("/home/dev/app"), pack_snapshot_id("/home/dev/app"));
+        assert_ne!(pack_snapshot_id("/home/dev/app"), pack_snapshot_id("/home/dev/other"));
+    }
+
+    #[test]
+    fn merge_line_ranges_clamps_out_of_bounds_and_drops_empties() {
+        let ranges = vec![
+            LineRange { start: 0, end: 5 },
+            LineRange { start: 40, end: 50 },
+            LineRange { start: 20, end: 10 },
+        ];
+        let merged = merge_line_ranges(&ranges, 30);
+        assert_eq!(merged, vec![LineRange { start: 1, end: 5 }, LineRange { start: 30, end: 30 }]);
+    }
+
+    #[test]
+    fn merge_line_ranges_merges_overlapping_and_touching_ranges() {
+        let ranges = vec![
+            LineRange { start: 1, end: 10 },
+            LineRange { start: 5, end: 20 },
+            LineRange { start: 21, end: 25 },
+            LineRange { start: 100, end: 120 },
+        ];
+        let merged = merge_line_ranges(&ranges, 200);
+        assert_eq!(
+            merged,
+            vec![LineRange { start: 1, end: 25 }, LineRange { start: 100, end: 120 }]
+        );
+    }
+
+    #[test]
+    fn merge_line_ranges_returns_empty_for_empty_file() {
+        let ranges = vec![LineRange { start: 1, end: 10 }];
+        assert!(merge_line_ranges(&ranges, 0).is_empty());
+    }
+
+    #[test]
+    fn render_line_ranges_extracts_1_indexed_inclusive_lines_with_markers() {
+        let content = (1..=10).map(|n| format!("line{n}")).collect::<Vec<_>>().join("\n");
+        let ranges = vec![LineRange { start: 2, end: 3 }, LineRange { start: 8, end: 9 }];
+        let rendered = render_line_ranges(&content, &ranges, 10);
+        assert_eq!(
+            rendered,
+            "// ... (lines 2-3 of 10) ...\nline2\nline3\n// ... (lines 8-9 of 10) ...\nline8\nline9\n"
+        );
+    }
+
+    #[test]
+    fn civil_from_days_matches_known_unix_epoch_dates() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        // 2024-02-29 (leap day) is 19782 days after the epoch.
+        assert_eq!(civil_from_days(19782), (2024, 2, 29));
+    }
+
+    /// Temporarily installs a different `IGNORE_CONFIG`, restoring the
+    /// previous value on drop (even on panic/assertion failure) so tests
+    /// mutating the shared static can run alongside others safely.
+    struct IgnoreConfigGuard(IgnoreConfig);
+
+    impl IgnoreConfigGuard {
+        fn install(new: IgnoreConfig) -> Self {
+            let previous = IGNORE_CONFIG.lock().unwrap().clone();
+            *IGNORE_CONFIG.lock().unwrap() = new;
+            Self(previous)
+        }
+    }
+
+    impl Drop for IgnoreConfigGuard {
+        fn drop(&mut self) {
+            *IGNORE_CONFIG.lock().unwrap() = self.0.clone();
+        }
+    }
+
+    #[test]
+    fn validate_ignore_config_rejects_blank_and_root_entries() {
+        let mut config = IgnoreConfig::default();
+        config.dirs.push("".to_string());
+        assert!(validate_ignore_config(&config).is_err());
+
+        let mut config = IgnoreConfig::default();
+        config.file_names.push("/".to_string());
+        assert!(validate_ignore_config(&config).is_err());
+
+        let mut config = IgnoreConfig::default();
+        config.file_suffixes.push("   ".to_string());
+        assert!(validate_ignore_config(&config).is_err());
+
+        assert!(validate_ignore_config(&IgnoreConfig::default()).is_ok());
+    }
+
+    #[test]
+    fn ignore_config_changes_are_picked_up_without_reselecting_project() {
+        let _guard = IgnoreConfigGuard::install(IgnoreConfig {
+            dirs: vec!["custom_ignored_dir".to_string()],
+            file_names: vec![],
+            file_suffixes: vec![],
+        });
+
+        let dirs = effective_ignored_dirs(None);
+        assert!(dirs.contains("custom_ignored_dir"));
+        // The old built-in default is gone now that the config was replaced
+        // wholesale, confirming `effective_ignored_dirs` reads the live
+        // static rather than the compile-time constant.
+        assert!(!dirs.contains("node_modules"));
+    }
+
+    #[test]
+    fn is_ignored_by_config_matches_dir_components_and_file_suffixes() {
+        let _guard = IgnoreConfigGuard::install(IgnoreConfig::default());
+        assert!(is_ignored_by_config(Path::new("/project/node_modules/pkg/index.js")));
+        assert!(is_ignored_by_config(Path::new("/project/src/logo.png")));
+        assert!(!is_ignored_by_config(Path::new("/project/src/main.rs")));
+    }
+
+    #[test]
+    fn effective_ignored_dirs_applies_remove_then_add() {
+        let overrides = IgnoredDirOverrides {
+            add: vec!["__generated__".to_string()],
+            remove: vec!["vendor".to_string()],
+        };
+        let dirs = effective_ignored_dirs(Some(&overrides));
+        assert!(!dirs.contains("vendor"));
+        assert!(dirs.contains("__generated__"));
+        // Untouched defaults still apply.
+        assert!(dirs.contains("node_modules"));
+    }
+
+    #[test]
+    fn scan_project_entries_honors_ignored_dir_overrides() {
+        let temp = TestDir::new("prompt_pack_lite_ignored_dir_overrides");
+        let root = temp.path();
+        std::fs::create_dir_all(root.join("vendor")).unwrap();
+        std::fs::write(root.join("vendor").join("lib.rs"), "// vendored\n").unwrap();
+        std::fs::create_dir_all(root.join("__generated__")).unwrap();
+        std::fs::write(root.join("__generated__").join("gen.rs"), "// gen\n").unwrap();
+
+        let overrides = IgnoredDirOverrides {
+            add: vec!["__generated__".to_string()],
+            remove: vec!["vendor".to_string()],
+        };
+        let (entries, _warnings) = scan_project_entries(
+            root,
+            DEFAULT_MAX_SCAN_FILE_SIZE,
+            false,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Arc::new(effective_ignored_dirs(Some(&overrides))),
+            true,
+        )
+        .expect("scan project");
+
+        assert!(entries.iter().any(|e| e.relative_path == "vendor/lib.rs"));
+        assert!(!entries.iter().any(|e| e.relative_path.starts_with("__generated__")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn scan_project_entries_counts_broken_symlinks_as_a_warning() {
+        let temp = TestDir::new("prompt_pack_lite_broken_symlink");
+        let root = temp.path();
+        std::os::unix::fs::symlink(root.join("does_not_exist"), root.join("dangling")).unwrap();
+
+        let (entries, warnings) =
+            scan_project_entries(root, DEFAULT_MAX_SCAN_FILE_SIZE, false, None, None, None, None, None, Arc::new(effective_ignored_dirs(None)), true)
+                .expect("scan project");
+
+        assert!(!entries.iter().any(|e| e.relative_path == "dangling"));
+        assert_eq!(warnings.broken_symlinks, 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn scan_project_entries_dedupes_case_insensitive_aliases() {
+        let temp = TestDir::new("prompt_pack_lite_case_alias");
+        let root = temp.path();
+        std::fs::write(root.join("README.md"), "hello\n").unwrap();
+        // A second name that only differs by case but points at the exact
+        // same inode, mimicking what shows up via a case-insensitive
+        // filesystem alias.
+        std::fs::hard_link(root.join("README.md"), root.join("Readme.md")).unwrap();
+
+        let (entries, _warnings) =
+            scan_project_entries(root, DEFAULT_MAX_SCAN_FILE_SIZE, false, None, None, None, None, None, Arc::new(effective_ignored_dirs(None)), true)
+                .expect("scan project");
+
+        let matches: Vec<_> = entries
+            .iter()
+            .filter(|e| e.relative_path.eq_ignore_ascii_case("README.md"))
+            .collect();
+        assert_eq!(matches.len(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn scan_project_entries_reports_raw_bytes_for_non_utf8_names() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let temp = TestDir::new("prompt_pack_lite_non_utf8_name");
+        let root = temp.path();
+        let raw_name = OsStr::from_bytes(b"bad-\xffname.txt");
+        std::fs::write(root.join(raw_name), "content\n").unwrap();
+
+        let (entries, _warnings) =
+            scan_project_entries(root, DEFAULT_MAX_SCAN_FILE_SIZE, false, None, None, None, None, None, Arc::new(effective_ignored_dirs(None)), true)
+                .expect("scan project");
+
+        let entry = entries.iter().find(|e| !e.is_dir).expect("non-utf8 entry");
+        let bytes = entry.path_bytes.as_ref().expect("path_bytes populated for lossy name");
+        assert!(bytes.ends_with(b"bad-\xffname.txt"));
+
+        let resolved = resolve_actual_path(&entry.path, entry.path_bytes.as_ref());
+        assert!(resolved.exists());
+    }
+
+    #[test]
+    fn looks_binary_detects_nul_byte() {
+        assert!(looks_binary(b"hello\0world"));
+        assert!(!looks_binary(b"hello world"));
+    }
+
+    #[test]
+    fn read_file_byte_range_pages_through_content() {
+        let temp = TestDir::new("prompt_pack_lite_read_range");
+        let path = temp.path().join("data.txt");
+        std::fs::write(&path, "0123456789").unwrap();
+
+        let chunk = read_file_byte_range(&path, 3, Some(4)).expect("byte range read");
+        assert_eq!(chunk, "3456");
+    }
+
+    #[test]
+    fn read_file_line_range_selects_requested_lines() {
+        let temp = TestDir::new("prompt_pack_lite_read_lines");
+        let path = temp.path().join("data.txt");
+        std::fs::write(&path, "one\ntwo\nthree\nfour\n").unwrap();
+
+        let chunk = read_file_line_range(&path, 2, 3).expect("line range read");
+        assert_eq!(chunk, "two\nthree");
+    }
+
+    #[test]
+    fn read_whole_file_rejects_binary_content() {
+        let temp = TestDir::new("prompt_pack_lite_read_binary");
+        let path = temp.path().join("data.bin");
+        std::fs::write(&path, [0u8, 1, 2, 3]).unwrap();
+
+        match read_whole_file(&path) {
+            Err(ReadFileError::Binary { .. }) => {}
+            other => panic!("expected Binary error, got {:?}", other),
         }
     }
 
     #[test]
-    fn normalize_relative_path_replaces_backslashes() {
-        let path = Path::new("foo\\bar\\baz.txt");
-        assert_eq!(normalize_relative_path(path), "foo/bar/baz.txt");
+    fn read_whole_file_strips_utf8_bom() {
+        let temp = TestDir::new("prompt_pack_lite_read_bom");
+        let with_bom = temp.path().join("with_bom.txt");
+        let without_bom = temp.path().join("without_bom.txt");
+        let mut bytes = UTF8_BOM.to_vec();
+        bytes.extend_from_slice(b"hello world\n");
+        std::fs::write(&with_bom, &bytes).unwrap();
+        std::fs::write(&without_bom, "hello world\n").unwrap();
+
+        let content_with_bom = read_whole_file(&with_bom).expect("read with bom");
+        let content_without_bom = read_whole_file(&without_bom).expect("read without bom");
+        assert_eq!(content_with_bom, content_without_bom);
+        assert!(!content_with_bom.starts_with('\u{feff}'));
     }
 
     #[test]
-    fn scan_project_entries_collects_dirs_and_paths() {
-        let temp = TestDir::new("prompt_pack_lite_scan");
+    fn read_whole_file_transcodes_utf16le_with_bom() {
+        let temp = TestDir::new("prompt_pack_lite_read_utf16");
+        let path = temp.path().join("utf16.txt");
+        let mut bytes = UTF16_LE_BOM.to_vec();
+        bytes.extend_from_slice(b"h\0e\0l\0l\0o\0");
+        std::fs::write(&path, &bytes).unwrap();
+
+        let content = read_whole_file(&path).expect("transcode utf-16le with bom");
+        assert_eq!(content, "hello");
+    }
+
+    #[test]
+    fn read_whole_file_transcodes_utf16be_with_bom() {
+        let temp = TestDir::new("prompt_pack_lite_read_utf16be");
+        let path = temp.path().join("utf16be.txt");
+        let mut bytes = UTF16_BE_BOM.to_vec();
+        bytes.extend_from_slice(b"\0h\0e\0l\0l\0o");
+        std::fs::write(&path, &bytes).unwrap();
+
+        let content = read_whole_file(&path).expect("transcode utf-16be with bom");
+        assert_eq!(content, "hello");
+    }
+
+    #[test]
+    fn read_whole_file_transcodes_utf16le_without_bom_via_heuristic() {
+        let temp = TestDir::new("prompt_pack_lite_read_utf16_no_bom");
+        let path = temp.path().join("utf16_no_bom.txt");
+        let bytes = b"h\0e\0l\0l\0o\0 \0w\0o\0r\0l\0d\0".to_vec();
+        std::fs::write(&path, &bytes).unwrap();
+
+        let content = read_whole_file(&path).expect("transcode utf-16le without bom");
+        assert_eq!(content, "hello world");
+    }
+
+    #[test]
+    fn scan_detected_encoding_flags_utf16_and_leaves_utf8_unset() {
+        let temp = TestDir::new("prompt_pack_lite_scan_encoding");
+        let root = temp.path();
+        let mut bytes = UTF16_LE_BOM.to_vec();
+        bytes.extend_from_slice(b"h\0i\0");
+        std::fs::write(root.join("windows.txt"), &bytes).unwrap();
+        std::fs::write(root.join("normal.txt"), "hi\n").unwrap();
+
+        let (entries, _warnings) =
+            scan_project_entries(root, DEFAULT_MAX_SCAN_FILE_SIZE, false, None, None, None, None, None, Arc::new(effective_ignored_dirs(None)), true)
+                .expect("scan project");
+
+        let windows = entries.iter().find(|e| e.relative_path == "windows.txt").expect("windows.txt entry");
+        let normal = entries.iter().find(|e| e.relative_path == "normal.txt").expect("normal.txt entry");
+        assert_eq!(windows.detected_encoding.as_deref(), Some("UTF-16LE"));
+        assert_eq!(normal.detected_encoding, None);
+    }
+
+    #[test]
+    fn count_lines_ignores_leading_bom() {
+        let temp = TestDir::new("prompt_pack_lite_count_lines_bom");
+        let with_bom = temp.path().join("with_bom.txt");
+        let without_bom = temp.path().join("without_bom.txt");
+        let mut bytes = UTF8_BOM.to_vec();
+        bytes.extend_from_slice(b"one\ntwo\nthree\n");
+        std::fs::write(&with_bom, &bytes).unwrap();
+        std::fs::write(&without_bom, "one\ntwo\nthree\n").unwrap();
+
+        assert_eq!(count_lines(&with_bom), count_lines(&without_bom));
+    }
+
+    #[test]
+    fn compute_content_hash_is_stable_and_size_bounded() {
+        let temp = TestDir::new("prompt_pack_lite_hash");
+        let path_a = temp.path().join("a.txt");
+        let path_b = temp.path().join("b.txt");
+        std::fs::write(&path_a, "hello world").unwrap();
+        std::fs::write(&path_b, "goodbye world").unwrap();
+
+        let hash_a1 = compute_content_hash(&path_a, 11).expect("hash a first read");
+        let hash_a2 = compute_content_hash(&path_a, 11).expect("hash a second read");
+        let hash_b = compute_content_hash(&path_b, 13).expect("hash b");
+
+        assert_eq!(hash_a1, hash_a2);
+        assert_ne!(hash_a1, hash_b);
+        assert!(compute_content_hash(&path_a, MAX_HASH_FILE_SIZE + 1).is_none());
+    }
+
+    #[test]
+    fn looks_generated_detects_generated_header() {
+        assert!(looks_generated(b"// @generated by protoc-gen-rust\nfn foo() {}\n"));
+        assert!(looks_generated(b"# GENERATED FILE, DO NOT EDIT\nx = 1\n"));
+        assert!(!looks_generated(b"fn normal_code() {\n    let x = 1;\n}\n"));
+    }
+
+    #[test]
+    fn looks_generated_detects_minified_line_length() {
+        let minified = format!("var x=1;{}", "a".repeat(2000));
+        assert!(looks_generated(minified.as_bytes()));
+        assert!(!looks_generated(b"a short line\nanother short line\n"));
+    }
+
+    #[test]
+    fn is_generated_file_samples_only_the_first_few_kb() {
+        let temp = TestDir::new("prompt_pack_lite_is_generated");
+        let path = temp.path().join("bundle.js");
+        let mut content = "// @generated\n".to_string();
+        content.push_str(&"x".repeat(GENERATED_FILE_SAMPLE_SIZE * 4));
+        std::fs::write(&path, &content).unwrap();
+
+        assert!(is_generated_file(&path));
+    }
+
+    #[test]
+    fn scan_stats_entries_aggregates_by_extension() {
+        let temp = TestDir::new("prompt_pack_lite_scan_stats");
         let root = temp.path();
         std::fs::create_dir_all(root.join("src")).unwrap();
         std::fs::write(root.join("src").join("main.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(root.join("src").join("lib.rs"), "pub fn lib() {}\n").unwrap();
+        std::fs::write(root.join("README.md"), "hello\n").unwrap();
+
+        let stats = scan_stats_entries(root).expect("scan stats");
+        assert_eq!(stats.total_files, 3);
+        assert_eq!(stats.total_dirs, 1);
+        let (rs_count, _rs_bytes) = stats.by_extension.get("rs").copied().expect("rs extension");
+        assert_eq!(rs_count, 2);
+    }
+
+    #[test]
+    fn compute_dir_stats_rolls_up_bottom_up() {
+        let temp = TestDir::new("prompt_pack_lite_dir_stats");
+        let root = temp.path();
+        std::fs::create_dir_all(root.join("src").join("nested")).unwrap();
+        std::fs::write(root.join("src").join("main.rs"), "fn main() {}\nfn helper() {}\n").unwrap();
+        std::fs::write(root.join("src").join("nested").join("util.rs"), "fn util() {}\n").unwrap();
+        std::fs::write(root.join("README.md"), "hello\n").unwrap();
+
+        let (entries, _warnings) =
+            scan_project_entries(root, DEFAULT_MAX_SCAN_FILE_SIZE, false, None, None, None, None, None, Arc::new(effective_ignored_dirs(None)), true).expect("scan project");
+        let stats = compute_dir_stats_from_entries(&entries);
+
+        let root_stats = stats.get("").expect("root stats");
+        assert_eq!(root_stats.files, 3);
+        assert_eq!(root_stats.lines, 4);
+
+        let src_stats = stats.get("src").expect("src stats");
+        assert_eq!(src_stats.files, 2);
+        assert_eq!(src_stats.lines, 3);
+
+        let nested_stats = stats.get("src/nested").expect("nested stats");
+        assert_eq!(nested_stats.files, 1);
+        assert_eq!(nested_stats.lines, 1);
+
+        let root_rs = root_stats.by_extension.get("rs").expect("rs extension at root");
+        assert_eq!(root_rs.files, 2);
+    }
+
+    #[test]
+    fn compute_dir_stats_counts_bytes_only_when_line_count_skipped() {
+        let temp = TestDir::new("prompt_pack_lite_dir_stats_large");
+        let root = temp.path();
+        std::fs::write(root.join("big.rs"), "fn main() {}\n").unwrap();
+
+        let (mut entries, _warnings) =
+            scan_project_entries(root, DEFAULT_MAX_SCAN_FILE_SIZE, false, None, None, None, None, None, Arc::new(effective_ignored_dirs(None)), true).expect("scan project");
+        for entry in entries.iter_mut() {
+            if !entry.is_dir {
+                entry.line_count = None;
+            }
+        }
+
+        let stats = compute_dir_stats_from_entries(&entries);
+        let root_stats = stats.get("").expect("root stats");
+        assert_eq!(root_stats.files, 1);
+        assert_eq!(root_stats.lines, 0);
+        assert!(root_stats.bytes > 0);
+    }
+
+    #[test]
+    fn render_tree_sorts_dirs_before_files_alphabetically() {
+        let paths = vec![
+            "b.rs".to_string(),
+            "src/main.rs".to_string(),
+            "a.rs".to_string(),
+            "src/lib.rs".to_string(),
+        ];
+        let tree = render_tree(&paths);
+        let src_line = tree.find("src").unwrap();
+        let a_line = tree.find("a.rs").unwrap();
+        assert!(src_line < a_line, "directories should be listed before files");
+        assert!(tree.find("lib.rs").unwrap() < tree.find("main.rs").unwrap());
+    }
+
+    #[test]
+    fn render_tree_truncates_huge_directories() {
+        let paths: Vec<String> = (0..(MAX_TREE_ENTRIES_PER_DIR + 5))
+            .map(|i| format!("file_{i}.txt"))
+            .collect();
+        let tree = render_tree(&paths);
+        assert!(tree.contains("... and 5 more"));
+    }
+
+    #[test]
+    fn build_tree_strips_absolute_root_prefix() {
+        let tree = build_tree(
+            vec!["/project/src/main.rs".to_string()],
+            "/project".to_string(),
+        );
+        assert!(tree.contains("src"));
+        assert!(tree.contains("main.rs"));
+        assert!(!tree.contains("/project"));
+    }
+
+    #[test]
+    fn clipboard_copy_status_warns_above_threshold() {
+        assert_eq!(clipboard_copy_status(100, 200), ClipboardCopyStatus::Copied);
+        assert_eq!(clipboard_copy_status(200, 200), ClipboardCopyStatus::Copied);
+        assert_eq!(clipboard_copy_status(201, 200), ClipboardCopyStatus::TooLarge);
+    }
+
+    fn dummy_file_entry(path: &str, line_count: Option<usize>) -> FileEntry {
+        FileEntry {
+            path: path.to_string(),
+            relative_path: path.to_string(),
+            is_dir: false,
+            is_symlink: false,
+            size: 0,
+            line_count,
+            content_hash: None,
+            git_status: None,
+            last_commit_epoch: None,
+            is_generated: false,
+            path_bytes: None,
+            detected_encoding: None,
+        }
+    }
+
+    #[test]
+    fn render_file_tree_annotates_line_counts() {
+        let entries = vec![dummy_file_entry("/project/src/main.rs", Some(42))];
+        let tree = render_file_tree(entries, "/project".to_string(), vec![], None);
+        assert!(tree.contains("main.rs (42 lines)"), "tree was:\n{tree}");
+    }
+
+    #[test]
+    fn render_file_tree_marks_skeleton_paths() {
+        let entries = vec![dummy_file_entry("/project/src/lib.rs", None)];
+        let options = FileTreeOptions {
+            skeleton_paths: vec!["/project/src/lib.rs".to_string()],
+            ..FileTreeOptions::default()
+        };
+        let tree = render_file_tree(entries, "/project".to_string(), vec![], Some(options));
+        assert!(tree.contains("lib.rs [skeleton]"), "tree was:\n{tree}");
+    }
+
+    #[test]
+    fn render_file_tree_prunes_directories_without_selected_files() {
+        let entries = vec![
+            dummy_file_entry("/project/src/main.rs", None),
+            dummy_file_entry("/project/docs/readme.md", None),
+        ];
+        let tree = render_file_tree(
+            entries,
+            "/project".to_string(),
+            vec!["/project/src/main.rs".to_string()],
+            None,
+        );
+        assert!(tree.contains("main.rs"), "tree was:\n{tree}");
+        assert!(!tree.contains("docs"), "tree was:\n{tree}");
+        assert!(!tree.contains("readme.md"), "tree was:\n{tree}");
+    }
+
+    #[test]
+    fn render_file_tree_collapses_unselected_siblings() {
+        let entries = vec![
+            dummy_file_entry("/project/src/main.rs", None),
+            dummy_file_entry("/project/src/a.rs", None),
+            dummy_file_entry("/project/src/b.rs", None),
+        ];
+        let options = FileTreeOptions {
+            collapse_unselected_siblings: true,
+            ..FileTreeOptions::default()
+        };
+        let tree = render_file_tree(
+            entries,
+            "/project".to_string(),
+            vec!["/project/src/main.rs".to_string()],
+            Some(options),
+        );
+        assert!(tree.contains("main.rs"), "tree was:\n{tree}");
+        assert!(tree.contains("… (2 more files)"), "tree was:\n{tree}");
+        assert!(!tree.contains("a.rs"), "tree was:\n{tree}");
+    }
+
+    fn dummy_skeleton_entry() -> SkeletonCacheEntry {
+        SkeletonCacheEntry {
+            file_size: 0,
+            modified_unix_nanos: 0,
+            result: SkeletonResult {
+                skeleton: "fn main() {}".to_string(),
+                language: Some("Rust".to_string()),
+                original_lines: 1,
+                skeleton_lines: 1,
+                compression_ratio: 0.0,
+                token_compression_ratio: 0.0,
+                cache_hit: false,
+                timed_out: false,
+                parse_errors: false,
+                skipped_minified: false,
+                truncated: false,
+                identical_to: None,
+                raw_included: false,
+            },
+        }
+    }
+
+    fn dummy_skeleton_result(skeleton: &str) -> SkeletonResult {
+        SkeletonResult { skeleton: skeleton.to_string(), ..dummy_skeleton_entry().result }
+    }
+
+    #[test]
+    fn dedup_identical_skeletons_in_place_marks_repeats() {
+        let paths = vec!["a.ts".to_string(), "b.ts".to_string(), "c.ts".to_string(), "d.ts".to_string()];
+        let mut results: Vec<Result<SkeletonResult, String>> = vec![
+            Ok(dummy_skeleton_result("export * from './x'")),
+            Ok(dummy_skeleton_result("export * from './y'")),
+            Ok(dummy_skeleton_result("export * from './x'")),
+            Err("read error".to_string()),
+        ];
+
+        dedup_identical_skeletons_in_place(&paths, &mut results);
+
+        assert_eq!(results[0].as_ref().unwrap().skeleton, "export * from './x'");
+        assert_eq!(results[0].as_ref().unwrap().identical_to, None);
+        assert_eq!(results[1].as_ref().unwrap().skeleton, "export * from './y'");
+        assert_eq!(results[2].as_ref().unwrap().skeleton, "// identical to a.ts");
+        assert_eq!(results[2].as_ref().unwrap().identical_to, Some("a.ts".to_string()));
+        assert!(results[3].is_err());
+    }
+
+    #[test]
+    fn apply_token_budget_marks_files_past_the_budget() {
+        let skeletons = [
+            "fn a() {}".repeat(20),
+            "fn b() {}".repeat(20),
+            "fn c() {}".repeat(20),
+        ];
+        let first_file_tokens = TOKENIZER.encode_with_special_tokens(&skeletons[0]).len();
+        let mut results: Vec<Result<SkeletonResult, String>> =
+            skeletons.iter().map(|s| Ok(dummy_skeleton_result(s))).collect();
+
+        let exhausted = apply_token_budget(&mut results, first_file_tokens);
+
+        assert!(exhausted);
+        assert!(results[0].is_ok());
+        assert_eq!(results[1].as_ref().unwrap_err(), "budget exhausted");
+        assert_eq!(results[2].as_ref().unwrap_err(), "budget exhausted");
+    }
+
+    #[test]
+    fn apply_token_budget_leaves_results_untouched_when_under_budget() {
+        let mut results: Vec<Result<SkeletonResult, String>> =
+            vec![Ok(dummy_skeleton_result("fn a() {}")), Ok(dummy_skeleton_result("fn b() {}"))];
+
+        let exhausted = apply_token_budget(&mut results, 10_000);
+
+        assert!(!exhausted);
+        assert!(results[0].is_ok());
+        assert!(results[1].is_ok());
+    }
+
+    #[test]
+    fn skeleton_cache_insert_evicts_oldest_beyond_entry_cap() {
+        // Isolate from other tests touching the shared cache.
+        {
+            let mut cache = SKELETON_CACHE.lock().unwrap();
+            let mut order = SKELETON_CACHE_ORDER.lock().unwrap();
+            cache.clear();
+            order.clear();
+        }
 
-        let entries = scan_project_entries(root).expect("scan project");
-        assert!(entries.iter().any(|entry| entry.relative_path == "src/main.rs"));
+        for i in 0..(SKELETON_CACHE_MAX_ENTRIES + 10) {
+            skeleton_cache_insert(format!("file_{i}.rs"), dummy_skeleton_entry());
+        }
+
+        let cache = SKELETON_CACHE.lock().unwrap();
+        assert!(cache.len() <= SKELETON_CACHE_MAX_ENTRIES);
+        assert!(!cache.contains_key("file_0.rs"));
+        assert!(cache.contains_key(&format!("file_{}.rs", SKELETON_CACHE_MAX_ENTRIES + 9)));
+    }
+
+    #[test]
+    fn watcher_state_defaults_to_default_debounce() {
+        let state = WatcherState {
+            watcher: Mutex::new(None),
+            debounce_ms: Mutex::new(DEFAULT_WATCH_DEBOUNCE_MS),
+            roots: Mutex::new(Vec::new()),
+        };
+        assert_eq!(*state.debounce_ms.lock().unwrap(), 500);
+    }
+
+    #[test]
+    fn root_gone_error_detects_path_not_found() {
+        let err = notify::Error::new(notify::ErrorKind::PathNotFound)
+            .add_path(PathBuf::from("/tmp/does-not-exist"));
+        assert!(is_root_gone_error(&err));
+    }
+
+    #[test]
+    fn root_gone_error_detects_wrapped_io_not_found() {
+        let io_err = std::io::Error::new(std::io::ErrorKind::NotFound, "no such file or directory");
+        let err = notify::Error::io(io_err);
+        assert!(is_root_gone_error(&err));
+    }
+
+    #[test]
+    fn root_gone_error_ignores_unrelated_errors() {
+        let err = notify::Error::new(notify::ErrorKind::WatchNotFound);
+        assert!(!is_root_gone_error(&err));
+
+        let io_err = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "denied");
+        assert!(!is_root_gone_error(&notify::Error::io(io_err)));
+    }
+
+    #[test]
+    fn skeleton_diff_reports_added_function_signature() {
+        let before = "fn one() {}\n";
+        let after = "fn one() {}\n\nfn two() {}\n";
+
+        let skeleton_before = skeleton::skeletonize_with_path(before, "rs", None).skeleton;
+        let skeleton_after = skeleton::skeletonize_with_path(after, "rs", None).skeleton;
+
+        let diff = diff_skeleton_lines(&skeleton_before, &skeleton_after);
+        assert!(
+            diff.added_lines.iter().any(|l| l.contains("fn two()")),
+            "added_lines was: {:?}",
+            diff.added_lines
+        );
+        assert!(diff.removed_lines.is_empty());
+    }
+
+    #[test]
+    fn debounce_coalesces_events_within_window() {
+        let debounce = Duration::from_millis(2000);
+        let mut last_emit = Instant::now();
+        let mut emitted = 1; // the first change always fires
+
+        std::thread::sleep(Duration::from_millis(300));
+        if debounce_elapsed(last_emit, debounce) {
+            emitted += 1;
+            last_emit = Instant::now();
+        }
+        let _ = last_emit;
+
+        assert_eq!(emitted, 1);
+    }
+
+    #[test]
+    fn debounce_allows_events_outside_window() {
+        let debounce = Duration::from_millis(100);
+        let mut last_emit = Instant::now();
+        let mut emitted = 1; // the first change always fires
+
+        std::thread::sleep(Duration::from_millis(300));
+        if debounce_elapsed(last_emit, debounce) {
+            emitted += 1;
+            last_emit = Instant::now();
+        }
+        let _ = last_emit;
+
+        assert_eq!(emitted, 2);
+    }
+
+    #[test]
+    fn creating_a_file_in_a_watched_dir_emits_a_created_change() {
+        use std::sync::mpsc;
+
+        let temp = TestDir::new("prompt_pack_lite_watch");
+        let watched_path = temp.path().canonicalize().unwrap();
+
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(move |res: Result<Event, notify::Error>| {
+            if let Ok(event) = res {
+                let _ = tx.send(event);
+            }
+        })
+        .expect("create watcher");
+        watcher
+            .watch(&watched_path, RecursiveMode::Recursive)
+            .expect("watch temp dir");
+
+        let file_path = watched_path.join("new_file.txt");
+        std::fs::write(&file_path, "hello").unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut found = None;
+        while Instant::now() < deadline {
+            if let Ok(event) = rx.recv_timeout(Duration::from_millis(200)) {
+                if let Some(FileChangeKind::Created) = should_emit(&event) {
+                    if event.paths.iter().any(|p| p == &file_path) {
+                        found = Some(FileChange {
+                            path: file_path.to_string_lossy().to_string(),
+                            kind: FileChangeKind::Created,
+                            root: None,
+                        });
+                        break;
+                    }
+                }
+            }
+        }
+
+        let change = found.expect("expected a Created event for the new file");
+        assert_eq!(change.kind, FileChangeKind::Created);
+        assert!(Path::new(&change.path).is_absolute());
+        assert_eq!(Path::new(&change.path), file_path);
+    }
+
+    #[test]
+    fn watching_two_roots_reports_changes_from_either() {
+        use std::sync::mpsc;
+
+        let temp_a = TestDir::new("prompt_pack_lite_watch_a");
+        let temp_b = TestDir::new("prompt_pack_lite_watch_b");
+        let root_a = temp_a.path().canonicalize().unwrap();
+        let root_b = temp_b.path().canonicalize().unwrap();
+
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(move |res: Result<Event, notify::Error>| {
+            if let Ok(event) = res {
+                let _ = tx.send(event);
+            }
+        })
+        .expect("create watcher");
+        // One shared watcher instance covering both roots, mirroring how
+        // `watch_project` loops over `paths` against a single `RecommendedWatcher`.
+        for root in [&root_a, &root_b] {
+            watcher.watch(root, RecursiveMode::Recursive).expect("watch root");
+        }
+
+        let file_a = root_a.join("a.txt");
+        let file_b = root_b.join("b.txt");
+        std::fs::write(&file_a, "a").unwrap();
+        std::fs::write(&file_b, "b").unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut saw_a = false;
+        let mut saw_b = false;
+        while Instant::now() < deadline && !(saw_a && saw_b) {
+            if let Ok(event) = rx.recv_timeout(Duration::from_millis(200)) {
+                if matches!(should_emit(&event), Some(FileChangeKind::Created)) {
+                    saw_a |= event.paths.iter().any(|p| p == &file_a);
+                    saw_b |= event.paths.iter().any(|p| p == &file_b);
+                }
+            }
+        }
+
+        assert!(saw_a, "expected a Created event from the first watched root");
+        assert!(saw_b, "expected a Created event from the second watched root");
+    }
+
+    #[test]
+    fn unwatch_project_stops_all_events() {
+        use std::sync::mpsc;
+
+        let temp = TestDir::new("prompt_pack_lite_unwatch");
+        let watched_path = temp.path().canonicalize().unwrap();
+
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(move |res: Result<Event, notify::Error>| {
+            if let Ok(event) = res {
+                let _ = tx.send(event);
+            }
+        })
+        .expect("create watcher");
+        watcher
+            .watch(&watched_path, RecursiveMode::Recursive)
+            .expect("watch temp dir");
+
+        let state = WatcherState {
+            watcher: Mutex::new(Some(watcher)),
+            debounce_ms: Mutex::new(DEFAULT_WATCH_DEBOUNCE_MS),
+            roots: Mutex::new(vec![watched_path.to_string_lossy().to_string()]),
+        };
+
+        take_watcher(&state).expect("unwatch_project should succeed");
+        assert!(state.watcher.lock().unwrap().is_none());
+        assert!(state.roots.lock().unwrap().is_empty());
+
+        std::fs::write(watched_path.join("after_unwatch.txt"), "hello").unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(1);
+        let mut saw_event = false;
+        while Instant::now() < deadline {
+            if rx.recv_timeout(Duration::from_millis(100)).is_ok() {
+                saw_event = true;
+                break;
+            }
+        }
+
+        assert!(!saw_event, "expected no events once the watcher was dropped");
+    }
+
+    #[test]
+    fn unwatch_path_stops_events_for_that_root_only() {
+        use std::sync::mpsc;
+
+        let temp_a = TestDir::new("prompt_pack_lite_unwatch_a");
+        let temp_b = TestDir::new("prompt_pack_lite_unwatch_b");
+        let root_a = temp_a.path().canonicalize().unwrap();
+        let root_b = temp_b.path().canonicalize().unwrap();
+
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(move |res: Result<Event, notify::Error>| {
+            if let Ok(event) = res {
+                let _ = tx.send(event);
+            }
+        })
+        .expect("create watcher");
+        for root in [&root_a, &root_b] {
+            watcher.watch(root, RecursiveMode::Recursive).expect("watch root");
+        }
+
+        let state = WatcherState {
+            watcher: Mutex::new(Some(watcher)),
+            debounce_ms: Mutex::new(DEFAULT_WATCH_DEBOUNCE_MS),
+            roots: Mutex::new(vec![
+                root_a.to_string_lossy().to_string(),
+                root_b.to_string_lossy().to_string(),
+            ]),
+        };
+
+        drop_watch_root(&state, &root_a.to_string_lossy()).expect("unwatch_path should succeed");
+        assert_eq!(*state.roots.lock().unwrap(), vec![root_b.to_string_lossy().to_string()]);
+
+        std::fs::write(root_a.join("a.txt"), "a").unwrap();
+        std::fs::write(root_b.join("b.txt"), "b").unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut saw_a = false;
+        let mut saw_b = false;
+        while Instant::now() < deadline && !saw_b {
+            if let Ok(event) = rx.recv_timeout(Duration::from_millis(200)) {
+                if matches!(should_emit(&event), Some(FileChangeKind::Created)) {
+                    saw_a |= event.paths.iter().any(|p| p == &root_a.join("a.txt"));
+                    saw_b |= event.paths.iter().any(|p| p == &root_b.join("b.txt"));
+                }
+            }
+        }
+
+        assert!(!saw_a, "expected no Created event from the unwatched root");
+        assert!(saw_b, "expected a Created event from the still-watched root");
+    }
+
+    #[test]
+    fn top_languages_by_line_count_sorts_largest_first() {
+        let entries = vec![
+            dummy_file_entry("/project/a.py", Some(10)),
+            dummy_file_entry("/project/b.rs", Some(100)),
+            dummy_file_entry("/project/c.rs", Some(50)),
+            dummy_file_entry("/project/README.md", Some(1000)),
+        ];
+        let stats = top_languages_by_line_count(&entries);
+        assert_eq!(stats[0].language, "Rust");
+        assert_eq!(stats[0].line_count, 150);
+        assert_eq!(stats[0].file_count, 2);
+        assert_eq!(stats[1].language, "Python");
+    }
+
+    #[test]
+    fn is_test_file_matches_dirs_and_filename_conventions() {
+        assert!(is_test_file("src/tests/foo.rs"));
+        assert!(is_test_file("src/__tests__/foo.test.tsx"));
+        assert!(is_test_file("pkg/test_utils.py"));
+        assert!(is_test_file("pkg/utils_test.py"));
+        assert!(is_test_file("src/foo.spec.ts"));
+        assert!(!is_test_file("src/main.rs"));
+    }
+
+    #[test]
+    fn detect_entry_points_finds_known_filenames_only() {
+        let entries = vec![
+            dummy_file_entry("/project/src/main.rs", None),
+            dummy_file_entry("/project/src/lib.rs", None),
+            dummy_file_entry("/project/frontend/index.tsx", None),
+        ];
+        let points = detect_entry_points(&entries);
+        assert_eq!(points, vec!["/project/frontend/index.tsx", "/project/src/main.rs"]);
+    }
+
+    #[test]
+    fn detect_frameworks_in_manifest_matches_known_signatures() {
+        let package_json = r#"{"dependencies": {"react": "^18.0.0", "express": "^4.0.0"}}"#;
+        let mut found = detect_frameworks_in_manifest(package_json);
+        found.sort();
+        assert_eq!(found, vec!["Express", "React"]);
+
+        assert_eq!(detect_frameworks_in_manifest("no frameworks here"), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn detect_project_frameworks_skips_missing_manifests_without_failing() {
+        let dir = std::env::temp_dir().join(format!("summarize-fw-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("Cargo.toml"), "[dependencies]\naxum = \"0.7\"\n").unwrap();
+
+        let frameworks = detect_project_frameworks(&dir);
+        assert_eq!(frameworks, vec!["Axum"]);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn build_project_summary_counts_source_and_test_files() {
+        let entries = vec![
+            dummy_file_entry("/project/src/main.rs", Some(20)),
+            dummy_file_entry("/project/tests/it_works.rs", Some(15)),
+            dummy_file_entry("/project/README.md", Some(5)),
+        ];
+        let summary = build_project_summary(Path::new("/project"), &entries);
+        assert_eq!(summary.source_file_count, 1);
+        assert_eq!(summary.test_file_count, 1);
+        assert_eq!(summary.entry_points, vec!["/project/src/main.rs"]);
+    }
+
+    #[test]
+    fn render_project_summary_block_reports_none_detected_when_empty() {
+        let summary = ProjectSummary::default();
+        let block = render_project_summary_block(&summary);
+        assert!(block.contains("Languages: (none detected)"));
+        assert!(block.contains("Frameworks: (none detected)"));
+        assert!(block.contains("Entry points: (none detected)"));
+        assert!(block.contains("Files: 0 source, 0 tests"));
+    }
+
+    #[test]
+    fn estimate_tokens_for_chars_uses_chars_over_four() {
+        let estimate = estimate_tokens_for_chars(1000);
+        assert_eq!(estimate.char_count, 1000);
+        assert_eq!(estimate.estimated_tokens, 250);
+        assert!(estimate.fits_in["gpt-3.5"]);
+        assert!(estimate.fits_in["claude-3"]);
+    }
+
+    #[test]
+    fn estimate_tokens_for_chars_empty_is_zero() {
+        let estimate = estimate_tokens_for_chars(0);
+        assert_eq!(estimate.estimated_tokens, 0);
+        assert!(estimate.fits_in.values().all(|&fits| fits));
+    }
+
+    #[test]
+    fn estimate_tokens_for_chars_flags_models_too_small() {
+        // 4096 gpt-3.5-sized tokens' worth of chars overflows gpt-3.5 but not gpt-4.
+        let estimate = estimate_tokens_for_chars(4096 * 4 + 4);
+        assert!(!estimate.fits_in["gpt-3.5"]);
+        assert!(estimate.fits_in["gpt-4"]);
     }
 }
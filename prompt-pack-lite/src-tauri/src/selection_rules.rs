@@ -0,0 +1,182 @@
+//! Per-project rules for defaulting a file's pack mode (full content,
+//! skeleton, or excluded entirely) from its path, instead of the user
+//! choosing a mode by hand for every scan.
+//!
+//! Rules are an ordered list of glob patterns, each mapping to a
+//! [`SelectionMode`]. They're evaluated in order and the last match wins,
+//! `.gitignore`-style; a pattern prefixed with `!` negates instead of
+//! assigning a mode, clearing whatever an earlier rule set so the file
+//! falls back to [`SelectionMode::Full`].
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use globset::{Glob, GlobSetBuilder};
+use serde::{Deserialize, Serialize};
+
+use crate::{to_tree_relative, FileEntry};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SelectionMode {
+    Full,
+    Skeleton,
+    Exclude,
+}
+
+/// One rule: a glob `pattern` (optionally `!`-prefixed to negate) and the
+/// `mode` it assigns when it's the last match for a path.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SelectionRule {
+    pub pattern: String,
+    pub mode: SelectionMode,
+}
+
+/// A project's ordered rule list, persisted alongside its selections.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SelectionRules {
+    pub rules: Vec<SelectionRule>,
+}
+
+/// Computes the [`SelectionMode`] for every non-directory entry in
+/// `entries`, keyed by `FileEntry::path`, from `rules` evaluated against
+/// each entry's path relative to `root`. Entries matched by no rule (or
+/// only by a negated one) default to `SelectionMode::Full`. Patterns that
+/// fail to parse as globs are skipped rather than failing the whole scan.
+pub fn apply_selection_rules(
+    root: &Path,
+    entries: &[FileEntry],
+    rules: &SelectionRules,
+) -> HashMap<String, SelectionMode> {
+    let mut builder = GlobSetBuilder::new();
+    let mut specs = Vec::with_capacity(rules.rules.len());
+    for rule in &rules.rules {
+        let (pattern, negate) = match rule.pattern.strip_prefix('!') {
+            Some(rest) => (rest, true),
+            None => (rule.pattern.as_str(), false),
+        };
+        let Ok(glob) = Glob::new(pattern) else {
+            continue;
+        };
+        builder.add(glob);
+        specs.push((negate, rule.mode));
+    }
+
+    let root_str = root.to_string_lossy();
+    let Ok(globset) = builder.build() else {
+        return entries
+            .iter()
+            .filter(|entry| !entry.is_dir)
+            .map(|entry| (entry.path.clone(), SelectionMode::Full))
+            .collect();
+    };
+
+    entries
+        .iter()
+        .filter(|entry| !entry.is_dir)
+        .map(|entry| {
+            let relative = to_tree_relative(&entry.path, &root_str);
+            let mut mode = SelectionMode::Full;
+            for index in globset.matches(&relative) {
+                let (negate, rule_mode) = specs[index];
+                mode = if negate { SelectionMode::Full } else { rule_mode };
+            }
+            (entry.path.clone(), mode)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str) -> FileEntry {
+        FileEntry {
+            path: path.to_string(),
+            relative_path: path.to_string(),
+            is_dir: false,
+            is_symlink: false,
+            size: 0,
+            line_count: None,
+            content_hash: None,
+            git_status: None,
+            last_commit_epoch: None,
+            is_generated: false,
+            path_bytes: None,
+            detected_encoding: None,
+        }
+    }
+
+    fn rule(pattern: &str, mode: SelectionMode) -> SelectionRule {
+        SelectionRule { pattern: pattern.to_string(), mode }
+    }
+
+    #[test]
+    fn unmatched_files_default_to_full() {
+        let entries = vec![file("/proj/src/main.rs")];
+        let rules = SelectionRules { rules: vec![] };
+        let computed = apply_selection_rules(Path::new("/proj"), &entries, &rules);
+        assert_eq!(computed["/proj/src/main.rs"], SelectionMode::Full);
+    }
+
+    #[test]
+    fn matching_pattern_assigns_its_mode() {
+        let entries = vec![
+            file("/proj/src/foo.test.ts"),
+            file("/proj/src/foo.ts"),
+            file("/proj/migrations/001_init.sql"),
+        ];
+        let rules = SelectionRules {
+            rules: vec![
+                rule("src/**/*.test.ts", SelectionMode::Skeleton),
+                rule("migrations/**/*.sql", SelectionMode::Exclude),
+            ],
+        };
+        let computed = apply_selection_rules(Path::new("/proj"), &entries, &rules);
+        assert_eq!(computed["/proj/src/foo.test.ts"], SelectionMode::Skeleton);
+        assert_eq!(computed["/proj/src/foo.ts"], SelectionMode::Full);
+        assert_eq!(computed["/proj/migrations/001_init.sql"], SelectionMode::Exclude);
+    }
+
+    #[test]
+    fn later_rules_take_precedence_over_earlier_ones() {
+        let entries = vec![file("/proj/src/foo.test.ts")];
+        let rules = SelectionRules {
+            rules: vec![
+                rule("src/**/*.ts", SelectionMode::Skeleton),
+                rule("src/**/*.test.ts", SelectionMode::Exclude),
+            ],
+        };
+        let computed = apply_selection_rules(Path::new("/proj"), &entries, &rules);
+        assert_eq!(computed["/proj/src/foo.test.ts"], SelectionMode::Exclude);
+    }
+
+    #[test]
+    fn negated_pattern_clears_an_earlier_match() {
+        let entries = vec![
+            file("/proj/migrations/001_init.sql"),
+            file("/proj/migrations/seed.sql"),
+        ];
+        let rules = SelectionRules {
+            rules: vec![
+                rule("migrations/**/*.sql", SelectionMode::Exclude),
+                rule("!migrations/seed.sql", SelectionMode::Full),
+            ],
+        };
+        let computed = apply_selection_rules(Path::new("/proj"), &entries, &rules);
+        assert_eq!(computed["/proj/migrations/001_init.sql"], SelectionMode::Exclude);
+        assert_eq!(computed["/proj/migrations/seed.sql"], SelectionMode::Full);
+    }
+
+    #[test]
+    fn directories_are_not_included_in_the_result() {
+        let entries = vec![
+            FileEntry { is_dir: true, ..file("/proj/src") },
+            file("/proj/src/main.rs"),
+        ];
+        let rules = SelectionRules { rules: vec![] };
+        let computed = apply_selection_rules(Path::new("/proj"), &entries, &rules);
+        assert_eq!(computed.len(), 1);
+        assert!(!computed.contains_key("/proj/src"));
+    }
+}
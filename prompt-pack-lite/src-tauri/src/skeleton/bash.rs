@@ -0,0 +1,251 @@
+//! Bash/shell-specific skeleton extraction using tree-sitter AST.
+//!
+//! Handles `.sh`/`.bash`/`.zsh` files with focus on:
+//! - The shebang line
+//! - Top-level variable assignments
+//! - `source`/`.` includes
+//! - Function definitions, kept as `name()` with bodies dropped and a
+//!   `# Calls:` line listing the external commands the body invokes
+
+use tree_sitter::Node;
+
+use super::common::{
+    compact_text_prefix, get_node_text, truncate_line, is_shebang_or_encoding_comment, CallEdgeList, SkeletonOptions,
+    MAX_CALL_EDGE_NAME_LEN, MAX_DEF_LINE_LEN, line_number_prefix,
+};
+
+// ============ Main Entry Point ============
+
+pub fn extract_skeleton(content: &str, root: Node, source: &[u8]) -> String {
+    extract_skeleton_with_options(content, root, source, &SkeletonOptions::default())
+}
+
+/// Extract skeleton from shell source code with caller-supplied call-edge limits.
+pub fn extract_skeleton_with_options(_content: &str, root: Node, source: &[u8], options: &SkeletonOptions) -> String {
+    let mut output = String::new();
+    let mut cursor = root.walk();
+    for child in root.children(&mut cursor) {
+        extract_bash_statement(&mut output, child, source, options);
+    }
+    output.trim_end().to_string()
+}
+
+fn extract_bash_statement(output: &mut String, node: Node, source: &[u8], options: &SkeletonOptions) {
+    match node.kind() {
+        "comment" => {
+            let text = get_node_text(node, source);
+            if node.start_position().row <= 1 && is_shebang_or_encoding_comment(text) {
+                output.push_str(text);
+                output.push('\n');
+            }
+        }
+        "variable_assignment" => {
+            output.push_str(&line_number_prefix(node, options));
+            output.push_str(&truncate_line(get_node_text(node, source), MAX_DEF_LINE_LEN));
+            output.push('\n');
+        }
+        "command" => {
+            if is_include_command(node, source) {
+                output.push_str(&line_number_prefix(node, options));
+                output.push_str(&truncate_line(get_node_text(node, source), MAX_DEF_LINE_LEN));
+                output.push('\n');
+            }
+        }
+        "function_definition" => emit_bash_function(output, node, source, options),
+        _ => {}
+    }
+}
+
+/// `source ./lib.sh` and `. ./lib.sh` are the two spellings of a shell
+/// include; both parse as a `command` node whose `command_name` child is the
+/// literal word `source` or `.`.
+fn is_include_command(node: Node, source: &[u8]) -> bool {
+    let Some(name_node) = bash_command_name(node) else {
+        return false;
+    };
+    matches!(get_node_text(name_node, source), "source" | ".")
+}
+
+fn bash_command_name(node: Node) -> Option<Node> {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.kind() == "command_name" {
+            return Some(child);
+        }
+    }
+    None
+}
+
+fn emit_bash_function(output: &mut String, node: Node, source: &[u8], options: &SkeletonOptions) {
+    let Some(name) = bash_function_name(node, source) else {
+        return;
+    };
+    output.push_str(&line_number_prefix(node, options));
+    output.push_str(&name);
+    output.push_str("()\n");
+
+    if let Some(body) = bash_function_body(node) {
+        emit_bash_call_edges(output, body, source, options);
+    }
+    output.push('\n');
+}
+
+/// `function greet { ... }`, `function greet() { ... }`, and `greet() { ... }`
+/// all place the function's name in the first `word` child.
+fn bash_function_name(node: Node, source: &[u8]) -> Option<String> {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.kind() == "word" {
+            return Some(get_node_text(child, source).to_string());
+        }
+    }
+    None
+}
+
+fn bash_function_body(node: Node) -> Option<Node> {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.kind() == "compound_statement" {
+            return Some(child);
+        }
+    }
+    None
+}
+
+fn emit_bash_call_edges(output: &mut String, body: Node, source: &[u8], options: &SkeletonOptions) {
+    let calls = collect_bash_calls(body, source, options);
+    if calls.entries.is_empty() {
+        return;
+    }
+    output.push_str("# Calls: ");
+    output.push_str(&calls.entries.join(", "));
+    if calls.truncated {
+        output.push_str(", ...");
+    }
+    output.push('\n');
+}
+
+/// Collect external command invocations from a function body
+fn collect_bash_calls(node: Node, source: &[u8], options: &SkeletonOptions) -> CallEdgeList {
+    let mut list = CallEdgeList::new();
+    collect_bash_calls_rec(node, source, &mut list, options);
+    list
+}
+
+fn collect_bash_calls_rec(node: Node, source: &[u8], list: &mut CallEdgeList, options: &SkeletonOptions) {
+    if list.truncated {
+        return;
+    }
+    list.visited += 1;
+    if list.visited > options.max_call_edge_nodes {
+        list.truncated = true;
+        return;
+    }
+
+    if let Some(name) = bash_call_name(node, source) {
+        if !list.entries.contains(&name) {
+            if list.entries.len() < options.max_call_edge_names {
+                list.entries.push(name);
+            } else {
+                list.truncated = true;
+                return;
+            }
+        }
+    }
+
+    if bash_is_scope_boundary(node.kind()) {
+        return;
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_bash_calls_rec(child, source, list, options);
+        if list.truncated {
+            break;
+        }
+    }
+}
+
+fn bash_call_name(node: Node, source: &[u8]) -> Option<String> {
+    if node.kind() != "command" {
+        return None;
+    }
+    let name_node = bash_command_name(node)?;
+    let (compact, _) = compact_text_prefix(get_node_text(name_node, source), MAX_CALL_EDGE_NAME_LEN);
+    let name = compact.trim();
+    if name.is_empty() {
+        None
+    } else {
+        Some(truncate_line(name, MAX_CALL_EDGE_NAME_LEN))
+    }
+}
+
+/// Nested function definitions get their own `# Calls:` line when they're
+/// emitted, so don't fold their bodies into the enclosing function's list.
+fn bash_is_scope_boundary(kind: &str) -> bool {
+    kind == "function_definition"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tree_sitter::Parser;
+
+    fn parse_bash(code: &str) -> String {
+        let mut parser = Parser::new();
+        parser.set_language(&tree_sitter_bash::LANGUAGE.into()).unwrap();
+        let tree = parser.parse(code, None).unwrap();
+        extract_skeleton(code, tree.root_node(), code.as_bytes())
+    }
+
+    #[test]
+    fn test_bash_skeleton_keeps_shebang_vars_and_includes() {
+        let code = "#!/bin/bash\nset -euo pipefail\n\nVAR=\"hello\"\n\nsource ./lib.sh\n. ./other.sh\n";
+        let skeleton = parse_bash(code);
+        assert!(skeleton.contains("#!/bin/bash"));
+        assert!(skeleton.contains("VAR=\"hello\""));
+        assert!(skeleton.contains("source ./lib.sh"));
+        assert!(skeleton.contains(". ./other.sh"));
+        assert!(!skeleton.contains("set -euo pipefail"));
+    }
+
+    #[test]
+    fn test_bash_skeleton_keeps_encoding_declaration() {
+        let code = "#!/bin/bash\n# -*- coding: utf-8 -*-\necho hi\n";
+        let skeleton = parse_bash(code);
+        assert!(skeleton.contains("#!/bin/bash"), "skeleton was:\n{skeleton}");
+        assert!(skeleton.contains("# -*- coding: utf-8 -*-"), "skeleton was:\n{skeleton}");
+    }
+
+    #[test]
+    fn test_bash_function_definition_drops_body_and_lists_calls() {
+        let code = "greet() {\n    echo \"hi $VAR\"\n    curl -s https://example.com\n}\n";
+        let skeleton = parse_bash(code);
+        assert!(skeleton.contains("greet()"));
+        assert!(skeleton.contains("# Calls: echo, curl"));
+        assert!(!skeleton.contains("https://example.com"));
+    }
+
+    #[test]
+    fn test_bash_function_keyword_syntax() {
+        let code = "function greet {\n    echo hi\n}\n";
+        let skeleton = parse_bash(code);
+        assert!(skeleton.contains("greet()"));
+        assert!(skeleton.contains("# Calls: echo"));
+    }
+
+    #[test]
+    fn test_bash_line_numbers_opt_in() {
+        let code = "VAR=1\n\ngreet() {\n    echo hi\n}\n";
+        let mut parser = Parser::new();
+        parser.set_language(&tree_sitter_bash::LANGUAGE.into()).unwrap();
+        let tree = parser.parse(code, None).unwrap();
+        let options = SkeletonOptions {
+            include_line_numbers: true,
+            ..SkeletonOptions::default()
+        };
+        let skeleton = extract_skeleton_with_options(code, tree.root_node(), code.as_bytes(), &options);
+        assert!(skeleton.contains("1: VAR=1"), "skeleton was:\n{skeleton}");
+        assert!(skeleton.contains("3: greet()"), "skeleton was:\n{skeleton}");
+    }
+}
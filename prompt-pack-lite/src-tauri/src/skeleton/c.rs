@@ -12,7 +12,7 @@ use tree_sitter::Node;
 use super::common::{
     get_node_text, truncate_line, collect_summary_phrases,
     CallEdgeList, MAX_DEF_LINE_LEN, MAX_CALL_EDGE_NAMES,
-    MAX_CALL_EDGE_NAME_LEN, MAX_CALL_EDGE_NODES,
+    MAX_CALL_EDGE_NAME_LEN, MAX_CALL_EDGE_NODES, SkeletonOptions, line_number_prefix,
 };
 
 const MAX_C_INCLUDE_LINES: usize = 12;
@@ -20,12 +20,18 @@ const MAX_C_INCLUDE_LINES: usize = 12;
 // ============ Main Entry Point ============
 
 pub fn extract_skeleton(_content: &str, root: Node, source: &[u8]) -> String {
+    extract_skeleton_with_options(_content, root, source, &SkeletonOptions::default())
+}
+
+/// Extract skeleton from C source code, additionally letting the caller turn
+/// on line-number anchoring via `options`.
+pub fn extract_skeleton_with_options(_content: &str, root: Node, source: &[u8], options: &SkeletonOptions) -> String {
     let mut output = String::new();
-    extract_c_skeleton(&mut output, root, source, 0);
+    extract_c_skeleton(&mut output, root, source, 0, options);
     output
 }
 
-fn extract_c_skeleton(output: &mut String, node: Node, source: &[u8], depth: usize) {
+fn extract_c_skeleton(output: &mut String, node: Node, source: &[u8], depth: usize, options: &SkeletonOptions) {
     let indent = "    ".repeat(depth);
     let kind = node.kind();
 
@@ -38,13 +44,14 @@ fn extract_c_skeleton(output: &mut String, node: Node, source: &[u8], depth: usi
                 if child.kind() == "preproc_include" {
                     continue;
                 }
-                extract_c_skeleton(output, child, source, 0);
+                extract_c_skeleton(output, child, source, 0, options);
             }
         }
 
         // Preprocessor directives - always keep
         "preproc_include" | "preproc_def" | "preproc_function_def" => {
             output.push_str(&indent);
+            output.push_str(&line_number_prefix(node, options));
             output.push_str(&truncate_line(get_node_text(node, source), MAX_DEF_LINE_LEN));
             output.push('\n');
         }
@@ -63,7 +70,7 @@ fn extract_c_skeleton(output: &mut String, node: Node, source: &[u8], depth: usi
             for child in node.children(&mut cursor) {
                 let ck = child.kind();
                 if ck != "identifier" && ck != "preproc_arg" && ck != "#endif" {
-                    extract_c_skeleton(output, child, source, depth);
+                    extract_c_skeleton(output, child, source, depth, options);
                 }
             }
             
@@ -78,17 +85,18 @@ fn extract_c_skeleton(output: &mut String, node: Node, source: &[u8], depth: usi
 
         // Function definitions
         "function_definition" => {
-            extract_function_skeleton(output, node, source, &indent);
+            extract_function_skeleton(output, node, source, &indent, options);
         }
 
         // Declarations (includes function prototypes, variable declarations, struct/enum/typedef)
         "declaration" => {
-            extract_declaration(output, node, source, &indent);
+            extract_declaration(output, node, source, &indent, options);
         }
 
         // Standalone struct/union/enum (rare, usually in declarations)
         "struct_specifier" | "union_specifier" | "enum_specifier" => {
             output.push_str(&indent);
+            output.push_str(&line_number_prefix(node, options));
             output.push_str(&summarize_composite_type(node, source));
             output.push('\n');
         }
@@ -107,7 +115,7 @@ fn extract_c_skeleton(output: &mut String, node: Node, source: &[u8], depth: usi
         _ => {
             let mut cursor = node.walk();
             for child in node.children(&mut cursor) {
-                extract_c_skeleton(output, child, source, depth);
+                extract_c_skeleton(output, child, source, depth, options);
             }
         }
     }
@@ -115,7 +123,7 @@ fn extract_c_skeleton(output: &mut String, node: Node, source: &[u8], depth: usi
 
 // ============ Function Extraction ============
 
-fn extract_function_skeleton(output: &mut String, node: Node, source: &[u8], indent: &str) {
+fn extract_function_skeleton(output: &mut String, node: Node, source: &[u8], indent: &str, options: &SkeletonOptions) {
     // Build signature from parts
     let mut sig_parts = Vec::new();
     
@@ -131,6 +139,7 @@ fn extract_function_skeleton(output: &mut String, node: Node, source: &[u8], ind
     
     let signature = sig_parts.join(" ");
     output.push_str(indent);
+    output.push_str(&line_number_prefix(node, options));
     output.push_str(&signature);
     output.push('\n');
     
@@ -230,7 +239,7 @@ fn emit_include_summary(output: &mut String, node: Node, source: &[u8]) {
 
 // ============ Declaration Extraction ============
 
-fn extract_declaration(output: &mut String, node: Node, source: &[u8], indent: &str) {
+fn extract_declaration(output: &mut String, node: Node, source: &[u8], indent: &str, options: &SkeletonOptions) {
     let text = get_node_text(node, source);
     
     // Check what kind of declaration this is
@@ -242,6 +251,7 @@ fn extract_declaration(output: &mut String, node: Node, source: &[u8], indent: &
             // Typedef with struct/union/enum
             "type_definition" | "storage_class_specifier" if text.starts_with("typedef") => {
                 output.push_str(indent);
+                output.push_str(&line_number_prefix(node, options));
                 output.push_str(&summarize_typedef(node, source));
                 output.push('\n');
                 return;
@@ -250,6 +260,7 @@ fn extract_declaration(output: &mut String, node: Node, source: &[u8], indent: &
             // Struct/union/enum declaration
             "struct_specifier" | "union_specifier" | "enum_specifier" => {
                 output.push_str(indent);
+                output.push_str(&line_number_prefix(node, options));
                 output.push_str(&summarize_composite_type(*child, source));
                 
                 // Check for variable name after struct
@@ -266,6 +277,7 @@ fn extract_declaration(output: &mut String, node: Node, source: &[u8], indent: &
             // Function pointer typedef
             "function_declarator" if text.contains("(*)") => {
                 output.push_str(indent);
+                output.push_str(&line_number_prefix(node, options));
                 output.push_str(&truncate_line(text, MAX_DEF_LINE_LEN));
                 output.push('\n');
                 return;
@@ -278,6 +290,7 @@ fn extract_declaration(output: &mut String, node: Node, source: &[u8], indent: &
     // Function prototype (declaration with function_declarator)
     if text.contains('(') && text.ends_with(';') && !text.contains('=') {
         output.push_str(indent);
+        output.push_str(&line_number_prefix(node, options));
         output.push_str(&truncate_line(text, MAX_DEF_LINE_LEN));
         output.push('\n');
     }
@@ -444,9 +457,25 @@ fn should_keep_comment(text: &str) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use tree_sitter::Parser;
 
     #[test]
     fn test_module_compiles() {
         let _ = extract_skeleton;
     }
+
+    #[test]
+    fn test_c_line_numbers_opt_in() {
+        let mut parser = Parser::new();
+        parser.set_language(&tree_sitter_c::LANGUAGE.into()).unwrap();
+        let code = "int one(void);\n\nint two(void);\n";
+        let tree = parser.parse(code, None).unwrap();
+        let options = SkeletonOptions {
+            include_line_numbers: true,
+            ..SkeletonOptions::default()
+        };
+        let skeleton = extract_skeleton_with_options(code, tree.root_node(), code.as_bytes(), &options);
+        assert!(skeleton.contains("1: int one(void);"), "skeleton was:\n{skeleton}");
+        assert!(skeleton.contains("3: int two(void);"), "skeleton was:\n{skeleton}");
+    }
 }
@@ -2,6 +2,8 @@
 //!
 //! Handles: JSON, CSS, and HTML files.
 
+use std::path::Path;
+
 use tree_sitter::Node;
 
 use crate::skeleton::common::{get_node_text, truncate_line, MAX_DEF_LINE_LEN};
@@ -20,23 +22,284 @@ const JSON_DEP_KEYS: &[&str] = &[
     "devDependencies",
     "peerDependencies",
     "optionalDependencies",
+    "engines",
+    "peerDependenciesMeta",
 ];
 const JSON_SCRIPT_KEY: &str = "scripts";
+const JSON_WORKSPACES_KEY: &str = "workspaces";
+const JSON_EXPORTS_KEY: &str = "exports";
 
 // ============ JSON Extraction ============
 
-/// Extract skeleton from JSON source code
-pub fn extract_json_skeleton(content: &str, root: Node, source: &[u8]) -> String {
+/// Replaces `//` line comments and `/* */` block comments in JSONC content
+/// (VS Code's `settings.json`/`tsconfig.json` dialect) with equal-length
+/// runs of spaces, so `tree-sitter-json` — which doesn't understand
+/// comments — can still parse the file. Byte length (and therefore every
+/// other node's position) is preserved; comment markers inside string
+/// literals are left alone.
+pub fn strip_jsonc_comments(content: &str) -> String {
+    let bytes = content.as_bytes();
+    let mut output = String::with_capacity(content.len());
+    let mut chars = content.char_indices();
+    let mut in_string = false;
+    let mut escape = false;
+
+    while let Some((i, ch)) = chars.next() {
+        if in_string {
+            output.push(ch);
+            if escape {
+                escape = false;
+            } else if ch == '\\' {
+                escape = true;
+            } else if ch == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+
+        if ch == '"' {
+            in_string = true;
+            output.push(ch);
+            continue;
+        }
+
+        if ch == '/' && bytes.get(i + 1) == Some(&b'/') {
+            chars.next(); // consume the second '/'
+            output.push_str("  ");
+            for (_, c) in chars.by_ref() {
+                if c == '\n' {
+                    output.push('\n');
+                    break;
+                }
+                output.push_str(&" ".repeat(c.len_utf8()));
+            }
+            continue;
+        }
+
+        if ch == '/' && bytes.get(i + 1) == Some(&b'*') {
+            chars.next(); // consume the '*'
+            output.push_str("  ");
+            let mut prev = '\0';
+            for (_, c) in chars.by_ref() {
+                if prev == '*' && c == '/' {
+                    output.push(' ');
+                    break;
+                }
+                if c == '\n' {
+                    output.push('\n');
+                } else {
+                    output.push_str(&" ".repeat(c.len_utf8()));
+                }
+                prev = c;
+            }
+            continue;
+        }
+
+        output.push(ch);
+    }
+
+    output
+}
+
+/// Extract skeleton from JSON source code. `file_path` (when available) is
+/// used to switch `tsconfig.json`-style files into a dedicated rendering
+/// mode instead of the generic key/value walk.
+pub fn extract_json_skeleton(content: &str, root: Node, source: &[u8], file_path: Option<&str>) -> String {
     // Handle large JSON files without full parsing
     if content.len() > MAX_JSON_LARGE_BYTES {
         return summarize_large_json(content);
     }
 
+    if is_tsconfig_file(file_path) {
+        return extract_tsconfig_skeleton(root, source);
+    }
+
     let mut output = String::new();
     extract_json_skeleton_rec(&mut output, root, source, 0);
     output.trim().to_string()
 }
 
+/// Whether `file_path`'s base name looks like a TypeScript project config
+/// (`tsconfig.json`, `tsconfig.build.json`, `tsconfig.base.json`, ...).
+fn is_tsconfig_file(file_path: Option<&str>) -> bool {
+    file_path
+        .and_then(|p| Path::new(p).file_name())
+        .and_then(|f| f.to_str())
+        .is_some_and(|name| name == "tsconfig.json" || (name.starts_with("tsconfig.") && name.ends_with(".json")))
+}
+
+/// Renders a `tsconfig.json` as its `extends` chain, `compilerOptions.paths`
+/// map, `include`/`exclude` pattern counts, and project `references` -
+/// the fields that matter for understanding module resolution across a
+/// TypeScript project, rather than the full (often noisy) compiler option
+/// list the generic JSON renderer would otherwise dump verbatim.
+fn extract_tsconfig_skeleton(root: Node, source: &[u8]) -> String {
+    let Some(object) = json_root_object(root) else {
+        return String::new();
+    };
+
+    let mut lines: Vec<String> = Vec::new();
+    let mut cursor = object.walk();
+    for child in object.children(&mut cursor) {
+        if child.kind() != "pair" {
+            continue;
+        }
+        let (key, value_node) = json_pair_key_value(child, source);
+        let (Some(key), Some(value)) = (key, value_node) else {
+            continue;
+        };
+
+        match key.as_str() {
+            "extends" => lines.push(format!("extends: {}", summarize_tsconfig_extends(value, source))),
+            "compilerOptions" => {
+                if let Some(paths_line) = tsconfig_compiler_options_paths_line(value, source) {
+                    lines.push(paths_line);
+                }
+            }
+            "include" => lines.push(format!("include: {}", tsconfig_pattern_count(value))),
+            "exclude" => lines.push(format!("exclude: {}", tsconfig_pattern_count(value))),
+            "references" => lines.push(format!("references: {}", summarize_tsconfig_references(value, source))),
+            _ => {}
+        }
+    }
+
+    lines.join("\n")
+}
+
+/// The top-level `object` node under a JSON `document` root (or `root`
+/// itself, for callers that already hand one over).
+fn json_root_object(root: Node) -> Option<Node> {
+    if root.kind() == "object" {
+        return Some(root);
+    }
+    let mut cursor = root.walk();
+    root.children(&mut cursor).find(|c| c.kind() == "object")
+}
+
+/// `extends` is either a single project path or (TS 5+ project references)
+/// an array of them.
+fn summarize_tsconfig_extends(value: Node, source: &[u8]) -> String {
+    match value.kind() {
+        "string" => json_string_value(value, source).unwrap_or_default(),
+        "array" => {
+            let mut items = Vec::new();
+            let mut cursor = value.walk();
+            for child in value.children(&mut cursor) {
+                if let Some(s) = json_string_value(child, source) {
+                    items.push(s);
+                }
+            }
+            format!("[{}]", items.join(", "))
+        }
+        _ => get_node_text(value, source).to_string(),
+    }
+}
+
+fn tsconfig_compiler_options_paths_line(compiler_options: Node, source: &[u8]) -> Option<String> {
+    if compiler_options.kind() != "object" {
+        return None;
+    }
+    let mut cursor = compiler_options.walk();
+    for child in compiler_options.children(&mut cursor) {
+        if child.kind() != "pair" {
+            continue;
+        }
+        let (key, value_node) = json_pair_key_value(child, source);
+        if key.as_deref() != Some("paths") {
+            continue;
+        }
+        let value = value_node?;
+        if value.kind() != "object" {
+            return None;
+        }
+        return Some(format!("compilerOptions.paths: {}", summarize_tsconfig_paths_object(value, source)));
+    }
+    None
+}
+
+/// Lists `paths` aliases as `alias -> [targets]`, up to `MAX_JSON_DEP_ENTRIES`.
+/// Matches the `name -> target` shape `summarize_json_exports_object` uses
+/// for `exports`, since both map a module specifier to file locations.
+fn summarize_tsconfig_paths_object(node: Node, source: &[u8]) -> String {
+    let mut entries: Vec<String> = Vec::new();
+    let mut count = 0;
+    let mut cursor = node.walk();
+
+    for child in node.children(&mut cursor) {
+        if child.kind() != "pair" {
+            continue;
+        }
+        count += 1;
+        if entries.len() >= MAX_JSON_DEP_ENTRIES {
+            continue;
+        }
+        let (key, value_node) = json_pair_key_value(child, source);
+        let Some(name) = key else {
+            continue;
+        };
+        let targets = match value_node {
+            Some(v) if v.kind() == "array" => summarize_json_array(v, source),
+            Some(v) => v.kind().to_string(),
+            None => String::new(),
+        };
+        entries.push(truncate_line(&format!("{} -> {}", name, targets), MAX_JSON_ENTRY_LEN));
+    }
+
+    if entries.is_empty() {
+        return "{}".to_string();
+    }
+
+    let mut summary = entries.join(", ");
+    if count > entries.len() {
+        summary.push_str(&format!(", ... (+{})", count - entries.len()));
+    }
+    summary
+}
+
+fn tsconfig_pattern_count(value: Node) -> String {
+    if value.kind() == "array" {
+        let count = value.named_child_count();
+        format!("[{} pattern{}]", count, if count == 1 { "" } else { "s" })
+    } else {
+        format!("[{}]", value.kind())
+    }
+}
+
+/// Lists `references` project paths (each entry is `{ "path": "..." }`), up
+/// to `MAX_JSON_DEP_ENTRIES`, reusing `json_object_path_value` since it
+/// already knows how to pull a `path` field out of a reference object.
+fn summarize_tsconfig_references(value: Node, source: &[u8]) -> String {
+    if value.kind() != "array" {
+        return get_node_text(value, source).to_string();
+    }
+
+    let mut items: Vec<String> = Vec::new();
+    let mut count = 0;
+    let mut cursor = value.walk();
+    for child in value.children(&mut cursor) {
+        if !child.is_named() {
+            continue;
+        }
+        count += 1;
+        if items.len() >= MAX_JSON_DEP_ENTRIES {
+            continue;
+        }
+        if let Some(path) = json_object_path_value(child, source) {
+            items.push(path);
+        }
+    }
+
+    if items.is_empty() {
+        return "[]".to_string();
+    }
+
+    let mut summary = format!("[{}]", items.join(", "));
+    if count > items.len() {
+        summary.push_str(&format!(", ... (+{})", count - items.len()));
+    }
+    summary
+}
+
 fn extract_json_skeleton_rec(output: &mut String, node: Node, source: &[u8], depth: usize) {
     let indent = "  ".repeat(depth);
 
@@ -75,6 +338,14 @@ fn extract_json_skeleton_rec(output: &mut String, node: Node, source: &[u8], dep
                     let summary = summarize_json_scripts_object(value, source);
                     format!("{}: {}", key, summary)
                 }
+                Some(value) if key == JSON_WORKSPACES_KEY && value.kind() == "array" => {
+                    let summary = summarize_json_workspaces_array(value, source);
+                    format!("{}: {}", key, summary)
+                }
+                Some(value) if key == JSON_EXPORTS_KEY && value.kind() == "object" => {
+                    let summary = summarize_json_exports_object(value, source);
+                    format!("{}: {}", key, summary)
+                }
                 Some(value) if value.kind() == "string" => {
                     let val = json_string_value(value, source).unwrap_or_default();
                     format!("{}: {}", key, val)
@@ -216,6 +487,77 @@ fn summarize_json_scripts_object(node: Node, source: &[u8]) -> String {
     summary
 }
 
+/// Lists `workspaces` glob patterns compactly, up to `MAX_JSON_DEP_ENTRIES` -
+/// monorepos can have many, so this trades completeness for the same
+/// `... (+N)` overflow convention as `summarize_json_dependency_object`.
+fn summarize_json_workspaces_array(node: Node, source: &[u8]) -> String {
+    let mut entries: Vec<String> = Vec::new();
+    let mut count = 0;
+    let mut cursor = node.walk();
+
+    for child in node.children(&mut cursor) {
+        if !child.is_named() {
+            continue;
+        }
+        count += 1;
+        if entries.len() >= MAX_JSON_DEP_ENTRIES {
+            continue;
+        }
+        let item = json_primitive_value(child, source).unwrap_or_else(|| child.kind().to_string());
+        entries.push(item);
+    }
+
+    if entries.is_empty() {
+        return "[]".to_string();
+    }
+
+    let mut summary = format!("[{}]", entries.join(", "));
+    if count > entries.len() {
+        summary.push_str(&format!(", ... (+{})", count - entries.len()));
+    }
+    summary
+}
+
+/// Lists `exports` entry points as `name -> target`, up to
+/// `MAX_JSON_DEP_ENTRIES`. Conditional exports (`"." : { "import": ..., "require": ... }`)
+/// show as `object` rather than expanding, matching how
+/// `summarize_json_dependency_object` treats nested objects.
+fn summarize_json_exports_object(node: Node, source: &[u8]) -> String {
+    let mut entries: Vec<String> = Vec::new();
+    let mut count = 0;
+    let mut cursor = node.walk();
+
+    for child in node.children(&mut cursor) {
+        if child.kind() != "pair" {
+            continue;
+        }
+        count += 1;
+        if entries.len() >= MAX_JSON_DEP_ENTRIES {
+            continue;
+        }
+        let (key, value_node) = json_pair_key_value(child, source);
+        let Some(name) = key else {
+            continue;
+        };
+        let target = match value_node {
+            Some(v) if v.kind() == "string" => json_string_value(v, source).unwrap_or_default(),
+            Some(v) => v.kind().to_string(),
+            None => String::new(),
+        };
+        entries.push(truncate_line(&format!("{} -> {}", name, target), MAX_JSON_ENTRY_LEN));
+    }
+
+    if entries.is_empty() {
+        return "{}".to_string();
+    }
+
+    let mut summary = entries.join(", ");
+    if count > entries.len() {
+        summary.push_str(&format!(", ... (+{})", count - entries.len()));
+    }
+    summary
+}
+
 fn summarize_json_array(node: Node, source: &[u8]) -> String {
     let count = node.named_child_count();
     if count == 0 {
@@ -396,6 +738,11 @@ fn summarize_large_json(content: &str) -> String {
 
 // ============ CSS Extraction ============
 
+/// Custom property names shown in a `// Variables:` comment before truncating
+/// with `...` - CSS theming blocks can define dozens of these, so cap the
+/// noise the same way other insight lists in this module do.
+const MAX_CSS_VARIABLE_NAMES: usize = 12;
+
 /// Extract skeleton from CSS source code
 pub fn extract_css_skeleton(content: &str, root: Node, source: &[u8]) -> String {
     let _ = content; // Reserved for future use
@@ -412,6 +759,7 @@ fn extract_css_skeleton_rec(output: &mut String, node: Node, source: &[u8]) {
             "rule_set" => {
                 let mut selector = String::new();
                 let mut prop_count = 0;
+                let mut variables = Vec::new();
 
                 let mut rule_cursor = child.walk();
                 for part in child.children(&mut rule_cursor) {
@@ -424,6 +772,13 @@ fn extract_css_skeleton_rec(output: &mut String, node: Node, source: &[u8]) {
                             for item in part.children(&mut block_cursor) {
                                 if item.kind() == "declaration" {
                                     prop_count += 1;
+                                    if is_css_variable_scope_selector(&selector) {
+                                        if let Some(name) = css_declaration_property_name(item, source) {
+                                            if name.starts_with("--") {
+                                                variables.push(name);
+                                            }
+                                        }
+                                    }
                                 }
                             }
                         }
@@ -434,19 +789,81 @@ fn extract_css_skeleton_rec(output: &mut String, node: Node, source: &[u8]) {
                 let selector = truncate_line(&selector, MAX_DEF_LINE_LEN);
                 output.push_str(&selector);
                 output.push_str(&format!(" props={}\n", prop_count));
+                emit_css_variables_comment(output, &variables);
             }
             "media_statement" | "keyframes_statement" | "import_statement" => {
                 output.push_str(&truncate_line(get_node_text(child, source), MAX_DEF_LINE_LEN));
                 output.push('\n');
             }
+            "at_rule" => {
+                if let Some(name) = css_property_at_rule_name(child, source) {
+                    output.push_str("// Property: ");
+                    output.push_str(&name);
+                    output.push('\n');
+                }
+            }
             _ => {}
         }
     }
 }
 
+/// Whether `selector` is one of the conventional places CSS custom properties
+/// (theme variables) are declared: `:root`, `:root, :host`, or `html`. A
+/// selector list only needs one matching branch, e.g. `:root, :host` still
+/// counts even though `:host` alone wouldn't.
+fn is_css_variable_scope_selector(selector: &str) -> bool {
+    selector.split(',').map(str::trim).any(|part| part == ":root" || part == "html")
+}
+
+fn css_declaration_property_name(declaration: Node, source: &[u8]) -> Option<String> {
+    let mut cursor = declaration.walk();
+    for part in declaration.children(&mut cursor) {
+        if part.kind() == "property_name" {
+            return Some(get_node_text(part, source).to_string());
+        }
+    }
+    None
+}
+
+fn emit_css_variables_comment(output: &mut String, variables: &[String]) {
+    if variables.is_empty() {
+        return;
+    }
+    output.push_str("// Variables: ");
+    let shown = variables.iter().take(MAX_CSS_VARIABLE_NAMES).cloned().collect::<Vec<_>>();
+    output.push_str(&shown.join(", "));
+    if variables.len() > MAX_CSS_VARIABLE_NAMES {
+        output.push_str(", ...");
+    }
+    output.push('\n');
+}
+
+/// `@property --my-color { ... }` names its target custom property in the
+/// `keyword_query` node right after the `@property` keyword.
+fn css_property_at_rule_name(at_rule: Node, source: &[u8]) -> Option<String> {
+    let mut cursor = at_rule.walk();
+    let mut children = at_rule.children(&mut cursor);
+    let keyword = children.find(|c| c.kind() == "at_keyword")?;
+    if get_node_text(keyword, source) != "@property" {
+        return None;
+    }
+    let name_node = children.find(|c| c.kind() == "keyword_query")?;
+    Some(get_node_text(name_node, source).to_string())
+}
+
 // ============ HTML Extraction ============
 
 /// Extract skeleton from HTML source code
+/// Attributes worth surfacing inline on an element: `id`/`class` as the most
+/// useful anchors for locating an element, `src`/`href` so `<script src=...>`
+/// and `<link href=...>` references survive skeletonization.
+const HTML_ANCHOR_ATTRIBUTES: [&str; 4] = ["id", "class", "src", "href"];
+
+/// Recurse this many levels below the document root even for elements that
+/// aren't `html`/`head`/`body`, so component containers like `<div id="app">`
+/// show their immediate structure instead of collapsing straight to a count.
+const HTML_STRUCTURAL_RECURSE_DEPTH: usize = 3;
+
 pub fn extract_html_skeleton(content: &str, root: Node, source: &[u8]) -> String {
     let _ = content; // Reserved for future use
     let mut output = String::new();
@@ -486,6 +903,50 @@ fn html_tag_name(node: Node, source: &[u8]) -> (Option<String>, bool) {
     (tag_name, is_self_closing)
 }
 
+/// Find the `start_tag`/`self_closing_tag` child holding an element's
+/// attributes. `script_element`/`style_element` alias their opening tag to
+/// `start_tag` too, so this works uniformly across all three.
+fn html_tag_container(node: Node) -> Option<Node> {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if matches!(child.kind(), "start_tag" | "self_closing_tag") {
+            return Some(child);
+        }
+    }
+    None
+}
+
+/// Read attribute `name`'s value off a `start_tag`/`self_closing_tag` node,
+/// unwrapping a `quoted_attribute_value` down to its inner `attribute_value`.
+fn html_attribute_value(tag: Node, source: &[u8], name: &str) -> Option<String> {
+    let mut cursor = tag.walk();
+    for attribute in tag.children(&mut cursor).filter(|c| c.kind() == "attribute") {
+        let mut attr_cursor = attribute.walk();
+        let mut attr_name = None;
+        let mut attr_value = None;
+        for part in attribute.children(&mut attr_cursor) {
+            match part.kind() {
+                "attribute_name" => attr_name = Some(get_node_text(part, source)),
+                "attribute_value" => attr_value = Some(get_node_text(part, source).to_string()),
+                "quoted_attribute_value" => {
+                    let mut value_cursor = part.walk();
+                    for value in part.children(&mut value_cursor) {
+                        if value.kind() == "attribute_value" {
+                            attr_value = Some(get_node_text(value, source).to_string());
+                            break;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        if attr_name == Some(name) {
+            return attr_value;
+        }
+    }
+    None
+}
+
 fn extract_html_skeleton_rec(output: &mut String, node: Node, source: &[u8], depth: usize) {
     let indent = "  ".repeat(depth);
 
@@ -500,7 +961,7 @@ fn extract_html_skeleton_rec(output: &mut String, node: Node, source: &[u8], dep
             output.push_str(get_node_text(node, source));
             output.push('\n');
         }
-        "element" => {
+        "element" | "script_element" | "style_element" => {
             let mut cursor = node.walk();
             let (tag_name_opt, is_self_closing) = html_tag_name(node, source);
             let tag_name = tag_name_opt.unwrap_or_else(|| "element".to_string());
@@ -509,11 +970,11 @@ fn extract_html_skeleton_rec(output: &mut String, node: Node, source: &[u8], dep
 
             for child in node.children(&mut cursor) {
                 match child.kind() {
-                    "element" => {
+                    "element" | "script_element" | "style_element" => {
                         has_children = true;
                         child_elements += 1;
                     }
-                    "text" => {
+                    "text" | "raw_text" => {
                         let text = get_node_text(child, source).trim().to_string();
                         if !text.is_empty() {
                             has_children = true;
@@ -526,19 +987,31 @@ fn extract_html_skeleton_rec(output: &mut String, node: Node, source: &[u8], dep
             output.push_str(&indent);
             output.push('<');
             output.push_str(&tag_name);
+            if let Some(tag) = html_tag_container(node) {
+                for attr in HTML_ANCHOR_ATTRIBUTES {
+                    if let Some(value) = html_attribute_value(tag, source, attr) {
+                        output.push(' ');
+                        output.push_str(attr);
+                        output.push_str("=\"");
+                        output.push_str(&value);
+                        output.push('"');
+                    }
+                }
+            }
             if is_self_closing {
                 output.push_str(" />\n");
                 return;
             }
             output.push('>');
 
-            let should_recurse = matches!(tag_name.as_str(), "html" | "head" | "body");
+            let should_recurse = matches!(tag_name.as_str(), "html" | "head" | "body")
+                || depth < HTML_STRUCTURAL_RECURSE_DEPTH;
 
-            if should_recurse {
+            if should_recurse && child_elements > 0 {
                 output.push('\n');
                 let mut cursor = node.walk();
                 for child in node.children(&mut cursor) {
-                    if child.kind() == "element" {
+                    if matches!(child.kind(), "element" | "script_element" | "style_element") {
                         extract_html_skeleton_rec(output, child, source, depth + 1);
                     }
                 }
@@ -567,10 +1040,14 @@ mod tests {
     use tree_sitter::Parser;
 
     fn parse_json(code: &str) -> String {
+        parse_json_with_path(code, None)
+    }
+
+    fn parse_json_with_path(code: &str, file_path: impl Into<Option<&'static str>>) -> String {
         let mut parser = Parser::new();
         parser.set_language(&tree_sitter_json::LANGUAGE.into()).unwrap();
         let tree = parser.parse(code, None).unwrap();
-        extract_json_skeleton(code, tree.root_node(), code.as_bytes())
+        extract_json_skeleton(code, tree.root_node(), code.as_bytes(), file_path.into())
     }
 
     fn parse_css(code: &str) -> String {
@@ -598,6 +1075,39 @@ mod tests {
         assert!(skeleton.contains("version: 1.0.0"));
     }
 
+    #[test]
+    fn test_strip_jsonc_comments_preserves_byte_offsets() {
+        let code = "{\n  // a comment\n  \"a\": 1, /* inline */ \"b\": 2\n}";
+        let stripped = strip_jsonc_comments(code);
+        assert_eq!(stripped.len(), code.len());
+        assert!(!stripped.contains("comment"));
+        assert!(!stripped.contains("inline"));
+        assert!(stripped.contains("\"a\": 1"));
+        assert!(stripped.contains("\"b\": 2"));
+    }
+
+    #[test]
+    fn test_strip_jsonc_comments_ignores_markers_inside_strings() {
+        let code = r#"{ "url": "https://example.com", "note": "/* not a comment */" }"#;
+        let stripped = strip_jsonc_comments(code);
+        assert_eq!(stripped, code);
+    }
+
+    #[test]
+    fn test_json_skeleton_handles_jsonc_tsconfig() {
+        let code = r#"{
+    // Enable strict type checking
+    "compilerOptions": {
+        "target": "ES2020", // language target
+        "strict": true
+        /* module resolution */
+    }
+}"#;
+        let stripped = strip_jsonc_comments(code);
+        let skeleton = parse_json(&stripped);
+        assert!(skeleton.contains("compilerOptions: object"));
+    }
+
     #[test]
     fn test_json_dependencies() {
         let code = r#"{
@@ -611,6 +1121,87 @@ mod tests {
         assert!(skeleton.contains("react"));
     }
 
+    #[test]
+    fn test_json_package_workspaces_and_exports() {
+        let code = r#"{
+    "name": "my-monorepo",
+    "workspaces": ["packages/*", "apps/*"],
+    "exports": {
+        ".": "./dist/index.js",
+        "./feature": "./dist/feature.js"
+    },
+    "engines": {
+        "node": ">=18"
+    },
+    "peerDependenciesMeta": {
+        "react": {
+            "optional": true
+        }
+    }
+}"#;
+        let skeleton = parse_json(code);
+        assert!(skeleton.contains("workspaces: [\"packages/*\", \"apps/*\"]"), "skeleton was:\n{skeleton}");
+        assert!(skeleton.contains(". -> ./dist/index.js"), "skeleton was:\n{skeleton}");
+        assert!(skeleton.contains("./feature -> ./dist/feature.js"), "skeleton was:\n{skeleton}");
+        assert!(skeleton.contains("engines: node@>=18"), "skeleton was:\n{skeleton}");
+        assert!(skeleton.contains("peerDependenciesMeta: react@object"), "skeleton was:\n{skeleton}");
+    }
+
+    #[test]
+    fn test_json_workspaces_array_capped_with_overflow() {
+        let patterns: Vec<String> = (1..=14).map(|n| format!("\"p{n}\"")).collect();
+        let code = format!("{{\"workspaces\": [{}]}}", patterns.join(", "));
+        let skeleton = parse_json(&code);
+        assert!(skeleton.contains("... (+2)"), "skeleton was:\n{skeleton}");
+        assert!(skeleton.contains("\"p1\""), "skeleton was:\n{skeleton}");
+        assert!(!skeleton.contains("\"p14\""), "skeleton was:\n{skeleton}");
+    }
+
+    #[test]
+    fn test_tsconfig_extends_paths_and_references() {
+        let code = r#"{
+    "extends": ["./tsconfig.base.json", "./tsconfig.paths.json"],
+    "compilerOptions": {
+        "paths": {
+            "@app/*": ["src/app/*"],
+            "@lib/*": ["src/lib/*"],
+            "@utils": ["src/utils/index.ts"],
+            "@config": ["src/config.ts"]
+        }
+    },
+    "include": ["src/**/*.ts"],
+    "exclude": ["node_modules", "dist"],
+    "references": [
+        { "path": "../pkg-a" },
+        { "path": "../pkg-b" }
+    ]
+}"#;
+        let skeleton = parse_json_with_path(code, "tsconfig.json");
+        assert!(
+            skeleton.contains("extends: [./tsconfig.base.json, ./tsconfig.paths.json]"),
+            "skeleton was:\n{skeleton}"
+        );
+        assert!(skeleton.contains("@app/* -> [\"src/app/*\"]"), "skeleton was:\n{skeleton}");
+        assert!(skeleton.contains("@lib/* -> [\"src/lib/*\"]"), "skeleton was:\n{skeleton}");
+        assert!(skeleton.contains("@utils -> [\"src/utils/index.ts\"]"), "skeleton was:\n{skeleton}");
+        assert!(skeleton.contains("@config -> [\"src/config.ts\"]"), "skeleton was:\n{skeleton}");
+        assert!(skeleton.contains("include: [1 pattern]"), "skeleton was:\n{skeleton}");
+        assert!(skeleton.contains("exclude: [2 patterns]"), "skeleton was:\n{skeleton}");
+        assert!(skeleton.contains("references: [../pkg-a, ../pkg-b]"), "skeleton was:\n{skeleton}");
+    }
+
+    #[test]
+    fn test_tsconfig_mode_only_triggers_for_tsconfig_filename() {
+        let code = r#"{ "extends": "./base.json", "include": ["src"] }"#;
+        let generic = parse_json_with_path(code, "settings.json");
+        assert!(generic.contains("extends: ./base.json"), "skeleton was:\n{generic}");
+        assert!(!generic.contains("include: [1 pattern]"), "skeleton was:\n{generic}");
+
+        let tsconfig = parse_json_with_path(code, "tsconfig.json");
+        assert!(tsconfig.contains("extends: ./base.json"), "skeleton was:\n{tsconfig}");
+        assert!(tsconfig.contains("include: [1 pattern]"), "skeleton was:\n{tsconfig}");
+    }
+
     #[test]
     fn test_css_rules() {
         let code = r#"
@@ -625,6 +1216,63 @@ mod tests {
         assert!(skeleton.contains("props=3"));
     }
 
+    #[test]
+    fn test_css_root_variables_capped_at_twelve() {
+        let code = r#"
+:root {
+    --color-1: red;
+    --color-2: blue;
+    --color-3: green;
+    --color-4: yellow;
+    --color-5: purple;
+    --color-6: orange;
+    --color-7: pink;
+    --color-8: black;
+}
+"#;
+        let skeleton = parse_css(code);
+        assert!(skeleton.contains(":root"));
+        assert!(skeleton.contains("props=8"));
+        for n in 1..=8 {
+            assert!(
+                skeleton.contains(&format!("--color-{n}")),
+                "skeleton was:\n{skeleton}"
+            );
+        }
+        let variables_line = skeleton
+            .lines()
+            .find(|line| line.starts_with("// Variables:"))
+            .unwrap_or_else(|| panic!("no Variables line in:\n{skeleton}"));
+        assert_eq!(variables_line.matches("--color-").count(), 8);
+    }
+
+    #[test]
+    fn test_css_variables_scope_and_at_property() {
+        let code = r#"
+:root, :host {
+    --spacing-unit: 8px;
+}
+
+html {
+    --html-var: 1;
+}
+
+.container {
+    --not-a-theme-var: 1;
+    color: red;
+}
+
+@property --my-color {
+    syntax: '<color>';
+}
+"#;
+        let skeleton = parse_css(code);
+        assert!(skeleton.contains("--spacing-unit"), "skeleton was:\n{skeleton}");
+        assert!(skeleton.contains("--html-var"), "skeleton was:\n{skeleton}");
+        assert!(!skeleton.contains("--not-a-theme-var"), "skeleton was:\n{skeleton}");
+        assert!(skeleton.contains("// Property: --my-color"), "skeleton was:\n{skeleton}");
+    }
+
     #[test]
     fn test_html_structure() {
         let code = r#"<!DOCTYPE html>
@@ -641,4 +1289,34 @@ mod tests {
         assert!(skeleton.contains("<head>"));
         assert!(skeleton.contains("<body>"));
     }
+
+    #[test]
+    fn test_html_keeps_id_and_class_and_recurses_into_containers() {
+        let code = r#"<!DOCTYPE html>
+<html>
+<body>
+    <div id="app" class="root">
+        <span class="label">Hi</span>
+    </div>
+</body>
+</html>"#;
+        let skeleton = parse_html(code);
+        assert!(skeleton.contains(r#"<div id="app" class="root">"#));
+        assert!(skeleton.contains(r#"<span class="label">"#));
+    }
+
+    #[test]
+    fn test_html_surfaces_script_and_link_references() {
+        let code = r#"<!DOCTYPE html>
+<html>
+<head>
+    <link href="styles.css" />
+    <script src="app.js"></script>
+</head>
+<body></body>
+</html>"#;
+        let skeleton = parse_html(code);
+        assert!(skeleton.contains(r#"<link href="styles.css" />"#));
+        assert!(skeleton.contains(r#"<script src="app.js">"#));
+    }
 }
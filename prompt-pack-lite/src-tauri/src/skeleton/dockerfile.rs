@@ -0,0 +1,80 @@
+//! Dockerfile skeleton extraction.
+//!
+//! Dockerfiles have no tree-sitter grammar vendored in this crate, so unlike
+//! the other languages in this module this is plain line-based text
+//! processing rather than an AST walk - `mod.rs` special-cases `Dockerfile`
+//! before it ever reaches the tree-sitter dispatch. Structural instructions
+//! (`FROM`, `RUN`, `COPY`, `EXPOSE`, etc.) are kept verbatim with long `RUN`
+//! bodies truncated; everything else (blank lines, free-form comments) is
+//! dropped.
+
+use super::common::{truncate_line, MAX_DEF_LINE_LEN};
+
+/// Instructions worth keeping in the skeleton. Keywords are matched
+/// case-insensitively since Docker itself doesn't require uppercase, though
+/// virtually every real-world Dockerfile uses it.
+const DOCKERFILE_INSTRUCTIONS: [&str; 14] = [
+    "FROM", "RUN", "COPY", "ADD", "EXPOSE", "ENV", "ARG", "WORKDIR", "CMD", "ENTRYPOINT", "LABEL",
+    "USER", "VOLUME", "SHELL",
+];
+
+pub fn extract_skeleton(content: &str) -> String {
+    let mut output = String::new();
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if !is_dockerfile_instruction(trimmed) {
+            continue;
+        }
+        output.push_str(&truncate_line(trimmed, MAX_DEF_LINE_LEN));
+        output.push('\n');
+    }
+    output.trim_end().to_string()
+}
+
+/// Whether `trimmed` starts with one of `DOCKERFILE_INSTRUCTIONS` followed
+/// by whitespace, e.g. `run apt-get update` matches `RUN`.
+fn is_dockerfile_instruction(trimmed: &str) -> bool {
+    let Some(word) = trimmed.split_whitespace().next() else {
+        return false;
+    };
+    DOCKERFILE_INSTRUCTIONS.iter().any(|instruction| word.eq_ignore_ascii_case(instruction))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keeps_structural_instructions_and_truncates_run() {
+        let code = "\
+# base image
+FROM node:18-alpine
+
+RUN apt-get update && apt-get install -y curl && rm -rf /var/lib/apt/lists/*
+
+COPY package.json .
+COPY . .
+
+EXPOSE 3000
+CMD [\"node\", \"index.js\"]
+";
+        let skeleton = extract_skeleton(code);
+        assert!(skeleton.contains("FROM node:18-alpine"), "skeleton was:\n{skeleton}");
+        assert!(skeleton.contains("COPY package.json ."), "skeleton was:\n{skeleton}");
+        assert!(skeleton.contains("EXPOSE 3000"), "skeleton was:\n{skeleton}");
+        assert!(skeleton.contains("CMD"), "skeleton was:\n{skeleton}");
+        assert!(skeleton.contains("RUN apt-get update"), "skeleton was:\n{skeleton}");
+        assert!(!skeleton.contains("# base image"), "skeleton was:\n{skeleton}");
+    }
+
+    #[test]
+    fn test_truncates_long_run_body() {
+        let long_run = format!("RUN {}", "x".repeat(MAX_DEF_LINE_LEN + 50));
+        let skeleton = extract_skeleton(&long_run);
+        assert!(skeleton.len() < long_run.len(), "skeleton was:\n{skeleton}");
+        assert!(skeleton.contains("..."), "skeleton was:\n{skeleton}");
+    }
+}
@@ -11,8 +11,8 @@ use tree_sitter::Node;
 
 use crate::skeleton::common::{
     get_node_text, truncate_line, compact_text_prefix,
-    CallEdgeList, MAX_DEF_LINE_LEN, MAX_CALL_EDGE_NAMES,
-    MAX_CALL_EDGE_NAME_LEN, MAX_CALL_EDGE_NODES,
+    classify_comment, CommentType, CallEdgeList, SkeletonOptions, MAX_DEF_LINE_LEN,
+    MAX_CALL_EDGE_NAME_LEN, MAX_MEMBER_NAMES, line_number_prefix,
 };
 
 /// Minimum family size to trigger summarization
@@ -22,22 +22,27 @@ const MIN_FAMILY_SIZE: usize = 4;
 
 /// Extract skeleton from Go source code
 pub fn extract_skeleton(content: &str, root: Node, source: &[u8]) -> String {
+    extract_skeleton_with_options(content, root, source, &SkeletonOptions::default())
+}
+
+/// Extract skeleton from Go source code with caller-supplied call-edge limits.
+pub fn extract_skeleton_with_options(content: &str, root: Node, source: &[u8], options: &SkeletonOptions) -> String {
     let _ = content;
     let mut output = String::new();
-    extract_go_skeleton_with_families(&mut output, root, source);
+    extract_go_skeleton_with_families(&mut output, root, source, options);
     output
 }
 
 // ============ Core Extraction with Method Family Detection ============
 
-fn extract_go_skeleton_with_families(output: &mut String, root: Node, source: &[u8]) {
+fn extract_go_skeleton_with_families(output: &mut String, root: Node, source: &[u8], options: &SkeletonOptions) {
     // First pass: collect all methods grouped by receiver type
     let mut methods_by_receiver: HashMap<String, Vec<MethodInfo>> = HashMap::new();
     
     let mut cursor = root.walk();
     for child in root.children(&mut cursor) {
         if child.kind() == "method_declaration" {
-            if let Some(info) = extract_method_info(child, source) {
+            if let Some(info) = extract_method_info(child, source, options) {
                 methods_by_receiver
                     .entry(info.receiver.clone())
                     .or_default()
@@ -73,7 +78,7 @@ fn extract_go_skeleton_with_families(output: &mut String, root: Node, source: &[
             }
             if let Some(next) = children.get(j) {
                 if next.kind() == "method_declaration" {
-                    if let Some(info) = extract_method_info(*next, source) {
+                    if let Some(info) = extract_method_info(*next, source, options) {
                         if skip_variants.contains(&(info.receiver.clone(), info.name.clone())) {
                             // Skip all comments plus the variant method.
                             i = j + 1;
@@ -86,15 +91,15 @@ fn extract_go_skeleton_with_families(output: &mut String, root: Node, source: &[
         
         match child.kind() {
             "method_declaration" => {
-                if let Some(info) = extract_method_info(child, source) {
+                if let Some(info) = extract_method_info(child, source, options) {
                     if skip_variants.contains(&(info.receiver.clone(), info.name.clone())) {
                         i += 1;
                         continue;
                     }
                 }
-                emit_method_with_family_check(output, child, source, &families);
+                emit_method_with_family_check(output, child, source, &families, options);
             }
-            _ => extract_go_node(output, child, source, 0),
+            _ => extract_go_node(output, child, source, 0, options),
         }
         i += 1;
     }
@@ -107,7 +112,7 @@ struct MethodInfo {
     call_edges: String,
 }
 
-fn extract_method_info(node: Node, source: &[u8]) -> Option<MethodInfo> {
+fn extract_method_info(node: Node, source: &[u8], options: &SkeletonOptions) -> Option<MethodInfo> {
     let receiver_node = node.child_by_field_name("receiver")?;
     let receiver = normalize_receiver(receiver_node, source);
     let name = node
@@ -121,7 +126,7 @@ fn extract_method_info(node: Node, source: &[u8]) -> Option<MethodInfo> {
         truncate_line(text, MAX_DEF_LINE_LEN)
     };
     
-    let call_edges = collect_call_edges_string(node, source);
+    let call_edges = collect_call_edges_string(node, source, options);
     
     Some(MethodInfo {
         receiver,
@@ -148,10 +153,10 @@ fn normalize_receiver(receiver_node: Node, source: &[u8]) -> String {
     last.to_string()
 }
 
-fn collect_call_edges_string(node: Node, source: &[u8]) -> String {
+fn collect_call_edges_string(node: Node, source: &[u8], options: &SkeletonOptions) -> String {
     let body = node.child_by_field_name("body");
     let Some(body) = body else { return String::new() };
-    let calls = collect_go_calls(body, source);
+    let calls = collect_go_calls(body, source, options);
     if calls.entries.is_empty() {
         return String::new();
     }
@@ -229,9 +234,10 @@ fn emit_method_with_family_check(
     node: Node,
     source: &[u8],
     families: &[MethodFamily],
+    options: &SkeletonOptions,
 ) {
-    let Some(info) = extract_method_info(node, source) else {
-        extract_go_function_skeleton(output, node, source, "");
+    let Some(info) = extract_method_info(node, source, options) else {
+        extract_go_function_skeleton(output, node, source, "", options);
         return;
     };
     
@@ -240,6 +246,7 @@ fn emit_method_with_family_check(
         if info.receiver == family.receiver {
             // If this is the base method, emit it with family summary
             if info.name == family.base_method {
+                output.push_str(&line_number_prefix(node, options));
                 output.push_str(&info.signature);
                 output.push('\n');
                 if !info.call_edges.is_empty() {
@@ -263,6 +270,7 @@ fn emit_method_with_family_check(
     }
     
     // Not part of a family, emit normally
+    output.push_str(&line_number_prefix(node, options));
     output.push_str(&info.signature);
     output.push('\n');
     if !info.call_edges.is_empty() {
@@ -280,12 +288,13 @@ fn summarize_variants(variants: &[String]) -> String {
     }
 }
 
-fn extract_go_node(output: &mut String, node: Node, source: &[u8], depth: usize) {
+fn extract_go_node(output: &mut String, node: Node, source: &[u8], depth: usize, options: &SkeletonOptions) {
     let indent = "\t".repeat(depth);
 
     match node.kind() {
         "package_clause" => {
-            output.push_str(get_node_text(node, source));
+            output.push_str(&line_number_prefix(node, options));
+            output.push_str(&truncate_line(get_node_text(node, source), MAX_DEF_LINE_LEN));
             output.push('\n');
         }
 
@@ -295,31 +304,47 @@ fn extract_go_node(output: &mut String, node: Node, source: &[u8], depth: usize)
         }
 
         "type_declaration" => {
-            output.push_str(&truncate_line(get_node_text(node, source), MAX_DEF_LINE_LEN));
-            output.push('\n');
+            let mut cursor = node.walk();
+            for spec in node.children(&mut cursor) {
+                match spec.kind() {
+                    "type_spec" => extract_go_type_spec(output, spec, source, &indent, options),
+                    "type_alias" => {
+                        output.push_str(&indent);
+                        output.push_str(&line_number_prefix(spec, options));
+                        output.push_str(&truncate_line(get_node_text(spec, source), MAX_DEF_LINE_LEN));
+                        output.push('\n');
+                    }
+                    _ => {}
+                }
+            }
         }
 
         "function_declaration" => {
-            extract_go_function_skeleton(output, node, source, &indent);
+            extract_go_function_skeleton(output, node, source, &indent, options);
         }
 
         "method_declaration" => {
-            extract_go_function_skeleton(output, node, source, &indent);
+            extract_go_function_skeleton(output, node, source, &indent, options);
         }
 
         "const_declaration" | "var_declaration" => {
+            output.push_str(&line_number_prefix(node, options));
             output.push_str(&truncate_line(get_node_text(node, source), MAX_DEF_LINE_LEN));
             output.push('\n');
         }
 
         "type_spec" => {
-            output.push_str(&truncate_line(get_node_text(node, source), MAX_DEF_LINE_LEN));
-            output.push('\n');
+            extract_go_type_spec(output, node, source, &indent, options);
         }
 
         "comment" => {
             let text = get_node_text(node, source);
-            if text.starts_with("//") && text.len() > 3 {
+            // Line comments are already kept; block comments (which never
+            // start with "//") still need a TODO/FIXME/SAFETY/HACK/NOTE
+            // check so those aren't silently dropped.
+            let keep = (text.starts_with("//") && text.len() > 3)
+                || classify_comment(text, "//") == CommentType::Todo;
+            if keep {
                 output.push_str(&truncate_line(text, MAX_DEF_LINE_LEN));
                 output.push('\n');
             }
@@ -328,7 +353,7 @@ fn extract_go_node(output: &mut String, node: Node, source: &[u8], depth: usize)
         "source_file" => {
             let mut cursor = node.walk();
             for child in node.children(&mut cursor) {
-                extract_go_node(output, child, source, depth);
+                extract_go_node(output, child, source, depth, options);
             }
         }
 
@@ -336,29 +361,203 @@ fn extract_go_node(output: &mut String, node: Node, source: &[u8], depth: usize)
     }
 }
 
+// ============ Type Spec Extraction ============
+
+/// Extract a single `type_spec`: structs get a field-by-field listing,
+/// interfaces get a method-by-method listing, everything else (aliases to
+/// slices, maps, other named types, ...) stays a truncated one-liner.
+fn extract_go_type_spec(output: &mut String, node: Node, source: &[u8], indent: &str, options: &SkeletonOptions) {
+    let Some(name_node) = node.child_by_field_name("name") else {
+        output.push_str(indent);
+        output.push_str(&line_number_prefix(node, options));
+        output.push_str(&truncate_line(get_node_text(node, source), MAX_DEF_LINE_LEN));
+        output.push('\n');
+        return;
+    };
+    let name = get_node_text(name_node, source);
+    let Some(ty) = node.child_by_field_name("type") else {
+        return;
+    };
+
+    match ty.kind() {
+        "struct_type" => extract_go_struct_spec(output, node, name, ty, source, indent, options),
+        "interface_type" => extract_go_interface_spec(output, node, name, ty, source, indent, options),
+        _ => {
+            output.push_str(indent);
+            output.push_str(&line_number_prefix(node, options));
+            output.push_str(&truncate_line(get_node_text(node, source), MAX_DEF_LINE_LEN));
+            output.push('\n');
+        }
+    }
+}
+
+fn extract_go_struct_spec(output: &mut String, node: Node, name: &str, struct_node: Node, source: &[u8], indent: &str, options: &SkeletonOptions) {
+    let member_indent = format!("{indent}\t");
+
+    output.push_str(indent);
+    output.push_str(&line_number_prefix(node, options));
+    output.push_str("type ");
+    output.push_str(name);
+    output.push_str(" struct {\n");
+
+    let mut cursor = struct_node.walk();
+    let mut emitted = 0;
+    let mut truncated = false;
+    'fields: for child in struct_node.children(&mut cursor) {
+        if child.kind() != "field_declaration_list" {
+            continue;
+        }
+        let mut list_cursor = child.walk();
+        for field in child.children(&mut list_cursor) {
+            if field.kind() != "field_declaration" {
+                continue;
+            }
+            if emitted >= MAX_MEMBER_NAMES {
+                truncated = true;
+                break 'fields;
+            }
+            emit_go_struct_field(output, field, source, &member_indent);
+            emitted += 1;
+        }
+    }
+    if truncated {
+        output.push_str(&member_indent);
+        output.push_str("...\n");
+    }
+
+    output.push_str(indent);
+    output.push_str("}\n");
+}
+
+/// Emit `Name Type` (or, for an embedded field with no name, just `Type`),
+/// plus one serialization-relevant tag key if the field has one — the part
+/// of a struct tag most likely to matter for understanding request/response
+/// shapes, without dragging in every other tag or comma-separated options
+/// like `,omitempty` that would blow the line budget on wide tags. `json`
+/// wins when present (the common case); otherwise the first of `xml`,
+/// `yaml`/`yml`, or `db` is shown, so a field tagged only `xml:"Name"` still
+/// surfaces its wire name instead of being silently dropped.
+fn emit_go_struct_field(output: &mut String, field: Node, source: &[u8], indent: &str) {
+    let Some(ty) = field.child_by_field_name("type") else {
+        return;
+    };
+    let ty_text = get_node_text(ty, source);
+
+    let mut name_cursor = field.walk();
+    let names: Vec<&str> = field
+        .children_by_field_name("name", &mut name_cursor)
+        .map(|n| get_node_text(n, source))
+        .collect();
+
+    let mut line = if names.is_empty() {
+        ty_text.to_string()
+    } else {
+        format!("{} {}", names.join(", "), ty_text)
+    };
+
+    if let Some((tag_name, tag_value)) = find_go_struct_tag(field, source).and_then(extract_go_serialization_tag) {
+        line.push_str(&format!(" `{}:\"{}\"`", tag_name, tag_value));
+    }
+
+    output.push_str(indent);
+    output.push_str(&truncate_line(&line, MAX_DEF_LINE_LEN));
+    output.push('\n');
+}
+
+/// The raw (backtick-stripped) contents of a field's struct tag, if it has
+/// one — tree-sitter-go doesn't expose this as a named field, just a bare
+/// `raw_string_literal` child.
+fn find_go_struct_tag<'a>(field: Node<'a>, source: &'a [u8]) -> Option<&'a str> {
+    let mut cursor = field.walk();
+    let tag = field.children(&mut cursor).find(|child| child.kind() == "raw_string_literal")?;
+    Some(get_node_text(tag, source).trim_matches('`'))
+}
+
+/// Tag keys checked, in priority order, by `extract_go_serialization_tag`.
+const GO_SERIALIZATION_TAG_KEYS: &[&str] = &["json", "xml", "yaml", "yml", "db"];
+
+/// Pulls the first present `json`/`xml`/`yaml`/`yml`/`db` key out of a struct
+/// tag string, dropping any comma-separated options after the key (e.g.
+/// `,omitempty`). Returns the tag name alongside its value so the caller can
+/// re-emit it under its original key.
+fn extract_go_serialization_tag(tag_content: &str) -> Option<(&'static str, String)> {
+    GO_SERIALIZATION_TAG_KEYS.iter().find_map(|&tag_name| {
+        tag_content.split(' ').find_map(|part| {
+            let value = part.strip_prefix(tag_name)?.strip_prefix(":\"")?.strip_suffix('"')?;
+            let key = value.split(',').next().unwrap_or(value);
+            Some((tag_name, key.to_string()))
+        })
+    })
+}
+
+/// Emit an interface's method set one member per line, each within its own
+/// `MAX_DEF_LINE_LEN` budget, so a wide interface doesn't get truncated down
+/// to a handful of methods the way a single-blob `get_node_text` would.
+/// Embedded interfaces (`type_elem`, e.g. `io.Closer`) get a `// embeds`
+/// annotation rather than being emitted as a bare, easily-missed identifier.
+fn extract_go_interface_spec(output: &mut String, node: Node, name: &str, interface_node: Node, source: &[u8], indent: &str, options: &SkeletonOptions) {
+    let member_indent = format!("{indent}\t");
+
+    output.push_str(indent);
+    output.push_str(&line_number_prefix(node, options));
+    output.push_str("type ");
+    output.push_str(name);
+    output.push_str(" interface {\n");
+
+    let mut cursor = interface_node.walk();
+    let mut emitted = 0;
+    let mut truncated = false;
+    for member in interface_node.children(&mut cursor) {
+        if !matches!(member.kind(), "method_elem" | "type_elem") {
+            continue;
+        }
+        if emitted >= MAX_MEMBER_NAMES {
+            truncated = true;
+            break;
+        }
+        output.push_str(&member_indent);
+        if member.kind() == "type_elem" {
+            output.push_str("// embeds ");
+            output.push_str(&truncate_line(get_node_text(member, source), MAX_DEF_LINE_LEN));
+        } else {
+            output.push_str(&truncate_line(get_node_text(member, source), MAX_DEF_LINE_LEN));
+        }
+        output.push('\n');
+        emitted += 1;
+    }
+    if truncated {
+        output.push_str(&member_indent);
+        output.push_str("...\n");
+    }
+
+    output.push_str(indent);
+    output.push_str("}\n");
+}
+
 // ============ Function/Method Extraction ============
 
-fn extract_go_function_skeleton(output: &mut String, node: Node, source: &[u8], indent: &str) {
+fn extract_go_function_skeleton(output: &mut String, node: Node, source: &[u8], indent: &str, options: &SkeletonOptions) {
     let text = get_node_text(node, source);
     if let Some(brace_pos) = text.find('{') {
-        let signature = truncate_line(text[..brace_pos].trim(), MAX_DEF_LINE_LEN);
+        let signature = truncate_line(text[..brace_pos].trim(), options.max_def_line_len);
         output.push_str(indent);
+        output.push_str(&line_number_prefix(node, options));
         output.push_str(&signature);
         output.push('\n');
-        emit_go_call_edges(output, node, source, indent);
+        emit_go_call_edges(output, node, source, indent, options);
     }
 }
 
 // ============ Call Edge Collection ============
 
-fn emit_go_call_edges(output: &mut String, node: Node, source: &[u8], indent: &str) {
+fn emit_go_call_edges(output: &mut String, node: Node, source: &[u8], indent: &str, options: &SkeletonOptions) {
     let body = node
         .child_by_field_name("body")
         .or_else(|| node.child_by_field_name("block"));
     let Some(body) = body else {
         return;
     };
-    let calls = collect_go_calls(body, source);
+    let calls = collect_go_calls(body, source, options);
     if calls.entries.is_empty() {
         return;
     }
@@ -371,25 +570,25 @@ fn emit_go_call_edges(output: &mut String, node: Node, source: &[u8], indent: &s
     output.push('\n');
 }
 
-fn collect_go_calls(node: Node, source: &[u8]) -> CallEdgeList {
+fn collect_go_calls(node: Node, source: &[u8], options: &SkeletonOptions) -> CallEdgeList {
     let mut list = CallEdgeList::new();
-    collect_go_calls_rec(node, source, &mut list);
+    collect_go_calls_rec(node, source, &mut list, options);
     list
 }
 
-fn collect_go_calls_rec(node: Node, source: &[u8], list: &mut CallEdgeList) {
+fn collect_go_calls_rec(node: Node, source: &[u8], list: &mut CallEdgeList, options: &SkeletonOptions) {
     if list.truncated {
         return;
     }
     list.visited += 1;
-    if list.visited > MAX_CALL_EDGE_NODES {
+    if list.visited > options.max_call_edge_nodes {
         list.truncated = true;
         return;
     }
 
     if let Some(name) = go_call_name(node, source) {
         add_unique_entry(&mut list.entries, name);
-        if list.entries.len() >= MAX_CALL_EDGE_NAMES {
+        if list.entries.len() >= options.max_call_edge_names {
             list.truncated = true;
             return;
         }
@@ -402,7 +601,7 @@ fn collect_go_calls_rec(node: Node, source: &[u8], list: &mut CallEdgeList) {
 
     let mut cursor = node.walk();
     for child in node.children(&mut cursor) {
-        collect_go_calls_rec(child, source, list);
+        collect_go_calls_rec(child, source, list, options);
         if list.truncated {
             break;
         }
@@ -507,11 +706,127 @@ func (s *Server) Start() error {
         let code = r#"package main
 
 type Reader interface {
+    io.Closer
     Read(p []byte) (n int, err error)
+    Write(p []byte) (n int, err error)
 }
 "#;
         let skeleton = parse_go(code);
         assert!(skeleton.contains("type Reader interface"));
+        assert!(skeleton.contains("io.Closer"));
+        assert!(skeleton.contains("Read(p []byte) (n int, err error)"));
+        assert!(skeleton.contains("Write(p []byte) (n int, err error)"));
+    }
+
+    #[test]
+    fn test_go_struct_fields_and_embeds() {
+        let code = r#"package main
+
+type User struct {
+    sync.Mutex
+    Name  string `json:"name"`
+    Email string `json:"email"`
+    Age   int
+}
+"#;
+        let skeleton = parse_go(code);
+        assert!(skeleton.contains("type User struct"));
+        assert!(skeleton.contains("sync.Mutex"));
+        assert!(skeleton.contains("Name string"));
+        assert!(skeleton.contains("Email string"));
+        assert!(skeleton.contains("Age int"));
+        assert!(skeleton.contains("json:\"name\""));
+        assert!(skeleton.contains("json:\"email\""));
+    }
+
+    #[test]
+    fn test_go_struct_shows_json_tag_keys_without_options() {
+        let fields: String = (0..5)
+            .map(|i| format!("    Field{i} string `json:\"field_{i},omitempty\" db:\"field_{i}\"`\n"))
+            .collect();
+        let code = format!("package main\n\ntype Payload struct {{\n{fields}}}\n");
+        let skeleton = parse_go(&code);
+        for i in 0..5 {
+            assert!(skeleton.contains(&format!("Field{i} string")), "missing Field{i} in {skeleton}");
+            assert!(skeleton.contains(&format!("json:\"field_{i}\"")), "missing json key for Field{i} in {skeleton}");
+        }
+        assert!(!skeleton.contains("omitempty"), "tag options after the key should be dropped: {skeleton}");
+    }
+
+    #[test]
+    fn test_go_struct_falls_back_to_xml_tag_without_json() {
+        let code = r#"package main
+
+type Envelope struct {
+    Name string `xml:"name"`
+}
+"#;
+        let skeleton = parse_go(code);
+        assert!(skeleton.contains("xml:\"name\""), "missing xml tag key in {skeleton}");
+    }
+
+    #[test]
+    fn test_go_interface_shows_all_method_signatures_within_cap() {
+        // Stays within MAX_MEMBER_NAMES so every method spec (each with its
+        // own MAX_DEF_LINE_LEN budget) shows up untruncated.
+        let methods: String = (0..MAX_MEMBER_NAMES)
+            .map(|i| format!("    Method{i}(arg int) error\n"))
+            .collect();
+        let code = format!("package main\n\ntype Wide interface {{\n{methods}}}\n");
+        let skeleton = parse_go(&code);
+        for i in 0..MAX_MEMBER_NAMES {
+            assert!(skeleton.contains(&format!("Method{i}(arg int) error")), "missing Method{i} in {skeleton}");
+        }
+        assert!(!skeleton.contains("..."), "interface within the cap shouldn't be truncated: {skeleton}");
+    }
+
+    #[test]
+    fn test_go_interface_embed_gets_annotation() {
+        let code = r#"package main
+
+type Reader interface {
+    io.Closer
+    Read(p []byte) (n int, err error)
+}
+"#;
+        let skeleton = parse_go(code);
+        assert!(skeleton.contains("// embeds io.Closer"), "expected embed annotation in {skeleton}");
+        assert!(skeleton.contains("Read(p []byte) (n int, err error)"));
+    }
+
+    #[test]
+    fn test_go_interface_with_many_methods_is_capped() {
+        let methods: String = (0..15)
+            .map(|i| format!("    Method{i}() error\n"))
+            .collect();
+        let code = format!("package main\n\ntype Wide interface {{\n{methods}}}\n");
+        let skeleton = parse_go(&code);
+        for i in 0..MAX_MEMBER_NAMES {
+            assert!(skeleton.contains(&format!("Method{i}()")), "missing Method{i} in {skeleton}");
+        }
+        assert!(!skeleton.contains(&format!("Method{}()", MAX_MEMBER_NAMES)));
+        assert!(skeleton.contains("..."), "expected truncation marker in {skeleton}");
+    }
+
+    #[test]
+    fn test_go_struct_with_many_fields_is_capped() {
+        let fields: String = (0..15)
+            .map(|i| format!("    Field{i} int\n"))
+            .collect();
+        let code = format!("package main\n\ntype Wide struct {{\n{fields}}}\n");
+        let skeleton = parse_go(&code);
+        for i in 0..MAX_MEMBER_NAMES {
+            assert!(skeleton.contains(&format!("Field{i} int")), "missing Field{i} in {skeleton}");
+        }
+        assert!(!skeleton.contains(&format!("Field{} int", MAX_MEMBER_NAMES)));
+        assert!(skeleton.contains("..."), "expected truncation marker in {skeleton}");
+    }
+
+    #[test]
+    fn test_go_type_alias_stays_one_line() {
+        let code = "package main\n\ntype Handler = func(w http.ResponseWriter, r *http.Request)\n";
+        let skeleton = parse_go(code);
+        assert!(skeleton.contains("type Handler = func(w http.ResponseWriter, r *http.Request)"));
     }
 
     #[test]
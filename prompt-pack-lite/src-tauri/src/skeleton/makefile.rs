@@ -0,0 +1,98 @@
+//! Makefile skeleton extraction.
+//!
+//! Like `dockerfile.rs`, this is plain line-based text processing since
+//! there's no tree-sitter grammar for Make vendored in this crate -
+//! `mod.rs` special-cases `Makefile` before the tree-sitter dispatch. Rule
+//! lines (`target: deps`) and variable assignments are kept; indented recipe
+//! bodies (the shell commands run to build a target) are elided.
+
+use super::common::{truncate_line, MAX_DEF_LINE_LEN};
+
+pub fn extract_skeleton(content: &str) -> String {
+    let mut output = String::new();
+    for line in content.lines() {
+        // Recipe lines are indented (conventionally with a tab) under their
+        // rule - that's exactly the body we want to drop.
+        if line.starts_with('\t') || line.starts_with("    ") {
+            continue;
+        }
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if is_rule_line(trimmed) || is_variable_assignment(trimmed) || is_directive(trimmed) {
+            output.push_str(&truncate_line(trimmed, MAX_DEF_LINE_LEN));
+            output.push('\n');
+        }
+    }
+    output.trim_end().to_string()
+}
+
+/// `target: deps` and `target:: deps` rule lines, e.g. `build: main.o`.
+/// Excludes variable assignments that happen to contain a colon before the
+/// `=`, like `CFLAGS := -Wall`, by requiring the colon come before any `=`.
+fn is_rule_line(trimmed: &str) -> bool {
+    let Some(colon_pos) = trimmed.find(':') else {
+        return false;
+    };
+    if trimmed[..colon_pos].trim().is_empty() {
+        return false;
+    }
+    match trimmed[colon_pos..].find('=') {
+        Some(eq_offset) => eq_offset > 1, // `:=`/`::=` land at offset 0/1, not a rule
+        None => true,
+    }
+}
+
+/// `NAME = value`, `NAME := value`, `NAME ?= value`, `NAME += value`.
+fn is_variable_assignment(trimmed: &str) -> bool {
+    let Some(eq_pos) = trimmed.find('=') else {
+        return false;
+    };
+    let name_part = trimmed[..eq_pos].trim_end_matches([':', '?', '+']);
+    !name_part.trim().is_empty() && !name_part.trim().contains(char::is_whitespace)
+}
+
+/// `include foo.mk`, `.PHONY: build test`, and other top-level directives.
+fn is_directive(trimmed: &str) -> bool {
+    trimmed.starts_with("include ")
+        || trimmed.starts_with("-include ")
+        || trimmed.starts_with(".PHONY")
+        || trimmed.starts_with("ifeq")
+        || trimmed.starts_with("ifneq")
+        || trimmed.starts_with("ifdef")
+        || trimmed.starts_with("ifndef")
+        || trimmed.starts_with("else")
+        || trimmed.starts_with("endif")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keeps_rules_and_variables_drops_recipe_bodies() {
+        let code = "\
+# comment
+CC = gcc
+CFLAGS := -Wall -O2
+
+build: main.o utils.o
+\t$(CC) $(CFLAGS) -o build main.o utils.o
+
+clean:
+\trm -f *.o build
+
+.PHONY: clean
+";
+        let skeleton = extract_skeleton(code);
+        assert!(skeleton.contains("CC = gcc"), "skeleton was:\n{skeleton}");
+        assert!(skeleton.contains("CFLAGS := -Wall -O2"), "skeleton was:\n{skeleton}");
+        assert!(skeleton.contains("build: main.o utils.o"), "skeleton was:\n{skeleton}");
+        assert!(skeleton.contains("clean:"), "skeleton was:\n{skeleton}");
+        assert!(skeleton.contains(".PHONY: clean"), "skeleton was:\n{skeleton}");
+        assert!(!skeleton.contains("$(CC)"), "skeleton was:\n{skeleton}");
+        assert!(!skeleton.contains("rm -f"), "skeleton was:\n{skeleton}");
+        assert!(!skeleton.contains("# comment"), "skeleton was:\n{skeleton}");
+    }
+}
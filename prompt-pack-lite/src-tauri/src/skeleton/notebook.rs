@@ -0,0 +1,173 @@
+//! Jupyter notebook (`.ipynb`) skeleton extraction.
+//!
+//! Notebooks are JSON, so without this module they'd go through the generic
+//! JSON skeletonizer and produce useless structure like `cells: array[58]`.
+//! Instead we parse the notebook JSON ourselves, run the Python skeletonizer
+//! over each code cell independently and prefix its output with a
+//! `# Cell N` marker, and summarize markdown cells by their headings. Cells
+//! are skeletonized one at a time (rather than as one concatenated blob) so
+//! the `# Cell N` markers are plain output text instead of source comments
+//! that the Python extractor's own comment-classification could drop.
+//! `outputs`, `execution_count`, and `metadata` are never read, so base64
+//! images and execution state are dropped for free.
+
+use super::common::SkeletonOptions;
+use super::python;
+use serde_json::Value;
+use tree_sitter::Parser;
+
+/// Above this size a notebook's embedded outputs (base64 images, huge
+/// dataframe reprs) make full JSON parsing wasteful, so we fall back to a
+/// cheap cell-count summary instead of extracting real skeletons.
+const MAX_NOTEBOOK_BYTES: usize = 5 * 1024 * 1024;
+
+/// Result of extracting a notebook's skeleton, kept separate from
+/// [`SkeletonResult`](super::SkeletonResult) so `mod.rs` can fold in the
+/// line counts alongside its own bookkeeping.
+pub struct NotebookExtraction {
+    pub skeleton: String,
+    /// Total source line count across all non-empty code cells, reported as
+    /// `original_lines` so notebooks compare like other languages instead of
+    /// showing the size of the surrounding JSON envelope.
+    pub code_lines: usize,
+}
+
+/// Extract a skeleton from a Jupyter notebook's raw JSON `content`.
+pub fn extract_skeleton(content: &str, options: &SkeletonOptions) -> NotebookExtraction {
+    if content.len() > MAX_NOTEBOOK_BYTES {
+        return large_notebook_summary(content);
+    }
+
+    let Ok(notebook) = serde_json::from_str::<Value>(content) else {
+        return NotebookExtraction { skeleton: "# [unparsable notebook]".to_string(), code_lines: 0 };
+    };
+
+    let Some(cells) = notebook.get("cells").and_then(Value::as_array) else {
+        return NotebookExtraction { skeleton: "# [notebook has no cells]".to_string(), code_lines: 0 };
+    };
+
+    let mut skeleton = String::new();
+    let mut code_lines = 0;
+
+    for (index, cell) in cells.iter().enumerate() {
+        let cell_number = index + 1;
+        match cell.get("cell_type").and_then(Value::as_str) {
+            Some("code") => {
+                let source = cell_source_text(cell);
+                if source.trim().is_empty() {
+                    continue;
+                }
+                code_lines += source.lines().count();
+                skeleton.push_str(&format!("# Cell {cell_number}\n{}\n", skeletonize_python_cell(&source, options)));
+            }
+            Some("markdown") => {
+                for heading in markdown_headings(&cell_source_text(cell)) {
+                    skeleton.push_str(&format!("# Cell {cell_number}: {heading}\n"));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    NotebookExtraction { skeleton: skeleton.trim_end().to_string(), code_lines }
+}
+
+/// Run the Python skeletonizer over a single code cell's source. Falls back
+/// to the raw source if the Python grammar can't be set up, which should
+/// never happen in practice.
+fn skeletonize_python_cell(source: &str, options: &SkeletonOptions) -> String {
+    let mut parser = Parser::new();
+    if parser.set_language(&tree_sitter_python::LANGUAGE.into()).is_err() {
+        return source.to_string();
+    }
+    let Some(tree) = parser.parse(source, None) else {
+        return source.to_string();
+    };
+    python::extract_skeleton_with_options(source, tree.root_node(), source.as_bytes(), options)
+}
+
+/// A cell's `source` field is either a single string or an array of
+/// line-strings that need joining (no separator - each line already ends
+/// with its own `\n`).
+fn cell_source_text(cell: &Value) -> String {
+    match cell.get("source") {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Array(lines)) => lines.iter().filter_map(Value::as_str).collect(),
+        _ => String::new(),
+    }
+}
+
+/// Pull out markdown heading lines (`# Foo`, `## Bar`, ...) from a markdown
+/// cell's source, stripped of their leading `#`s.
+fn markdown_headings(source: &str) -> Vec<String> {
+    source
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with('#'))
+        .map(|line| line.trim_start_matches('#').trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+/// A one-line "too big to skeletonize" summary for notebooks over
+/// `MAX_NOTEBOOK_BYTES`, built from a cheap substring scan instead of a full
+/// JSON parse.
+fn large_notebook_summary(content: &str) -> NotebookExtraction {
+    let cell_count = content.matches("\"cell_type\"").count();
+    let mb = content.len() / (1024 * 1024);
+    NotebookExtraction {
+        skeleton: format!("# large notebook ({mb} MB, ~{cell_count} cells) - skipped"),
+        code_lines: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notebook_json(cells: &str) -> String {
+        format!(r#"{{"cells": [{cells}], "metadata": {{}}, "nbformat": 4}}"#)
+    }
+
+    #[test]
+    fn test_extracts_code_and_markdown_cells() {
+        let content = notebook_json(concat!(
+            r##"{"cell_type": "markdown", "source": ["# Intro\n", "some text\n"]},"##,
+            r##"{"cell_type": "code", "source": "def add(a, b):\n    return a + b\n", "outputs": [], "execution_count": 1},"##,
+            r###"{"cell_type": "markdown", "source": "## Usage\n"}"###,
+        ));
+        let result = extract_skeleton(&content, &SkeletonOptions::default());
+        assert!(result.skeleton.contains("# Cell 1: Intro"), "skeleton was:\n{}", result.skeleton);
+        assert!(result.skeleton.contains("# Cell 3: Usage"), "skeleton was:\n{}", result.skeleton);
+        assert!(result.skeleton.contains("# Cell 2"), "skeleton was:\n{}", result.skeleton);
+        assert!(result.skeleton.contains("def add(a, b):"), "skeleton was:\n{}", result.skeleton);
+        assert_eq!(result.code_lines, 2);
+    }
+
+    #[test]
+    fn test_skips_empty_code_cells() {
+        let content = notebook_json(r#"{"cell_type": "code", "source": "   \n"}"#);
+        let result = extract_skeleton(&content, &SkeletonOptions::default());
+        assert!(!result.skeleton.contains("Cell 1"), "skeleton was:\n{}", result.skeleton);
+        assert_eq!(result.code_lines, 0);
+    }
+
+    #[test]
+    fn test_drops_outputs_and_metadata() {
+        let content = notebook_json(
+            r##"{"cell_type": "code", "source": "x = 1\n", "outputs": [{"data": {"image/png": "base64garbage"}}], "metadata": {"tags": ["secret"]}}"##,
+        );
+        let result = extract_skeleton(&content, &SkeletonOptions::default());
+        assert!(!result.skeleton.contains("base64garbage"), "skeleton was:\n{}", result.skeleton);
+        assert!(!result.skeleton.contains("secret"), "skeleton was:\n{}", result.skeleton);
+    }
+
+    #[test]
+    fn test_large_notebook_falls_back_to_summary() {
+        let padding = "x".repeat(MAX_NOTEBOOK_BYTES + 1);
+        let content = format!(r#"{{"cells": [], "padding": "{padding}"}}"#);
+        let result = extract_skeleton(&content, &SkeletonOptions::default());
+        assert!(result.skeleton.starts_with("# large notebook"), "skeleton was:\n{}", result.skeleton);
+        assert_eq!(result.code_lines, 0);
+    }
+}
@@ -13,11 +13,11 @@ use tree_sitter::Node;
 
 use super::common::{
     get_node_text, truncate_line, trim_docstring,
-    classify_comment, should_keep_comment, collect_summary_phrases,
+    classify_comment, should_keep_comment, is_shebang_or_encoding_comment, collect_summary_phrases,
     looks_like_path,
-    CallEdgeList, StateContract,
+    CallEdgeList, StateContract, SkeletonOptions, line_number_prefix,
     MAX_DEF_LINE_LEN, MAX_CLASS_ATTR_LEN, MAX_SIMPLE_ASSIGNMENT_LEN,
-    MAX_CALL_EDGE_NAMES, MAX_CALL_EDGE_NAME_LEN, MAX_CALL_EDGE_NODES,
+    MAX_CALL_EDGE_NAME_LEN,
 };
 
 // ============ Context ============
@@ -27,6 +27,11 @@ use super::common::{
 pub struct PythonContext<'a> {
     pub external_bindings: Option<&'a HashSet<String>>,
     pub is_nested: bool,
+    pub options: SkeletonOptions,
+    /// The module's declared public API (`__all__ = [...]`), if any —
+    /// analogous to `JsTsContext.exported_names` for JS/TS. When present,
+    /// top-level definitions not listed here are omitted.
+    pub dunder_all: Option<&'a HashSet<String>>,
 }
 
 impl<'a> PythonContext<'a> {
@@ -34,6 +39,8 @@ impl<'a> PythonContext<'a> {
         Self {
             external_bindings,
             is_nested: false,
+            options: SkeletonOptions::default(),
+            dunder_all: None,
         }
     }
 
@@ -49,14 +56,70 @@ impl<'a> PythonContext<'a> {
 
 /// Extract skeleton from Python source code
 pub fn extract_skeleton(_content: &str, root: Node, source: &[u8]) -> String {
+    extract_skeleton_with_options(_content, root, source, &SkeletonOptions::default())
+}
+
+/// Extract skeleton from Python source code with caller-supplied call-edge limits.
+pub fn extract_skeleton_with_options(_content: &str, root: Node, source: &[u8], options: &SkeletonOptions) -> String {
     let imports = collect_imports(root, source);
-    let ctx = PythonContext::new(Some(&imports));
+    let dunder_all = collect_dunder_all(root, source);
+    let mut ctx = PythonContext::new(Some(&imports));
+    ctx.options = *options;
+    ctx.dunder_all = dunder_all.as_ref();
 
     let mut output = String::new();
     extract_python_skeleton(&mut output, root, source, 0, ctx);
     output
 }
 
+/// Scans a module's top-level statements for `__all__ = [...]` (or a tuple
+/// literal) and collects its string elements — the module's declared public
+/// API, mirroring how `JsTsContext.exported_names` narrows JS/TS output to
+/// what's actually exported.
+fn collect_dunder_all(root: Node, source: &[u8]) -> Option<HashSet<String>> {
+    let mut cursor = root.walk();
+    for child in root.children(&mut cursor) {
+        let assignment = match child.kind() {
+            "assignment" => child,
+            "expression_statement" => match child.child(0) {
+                Some(inner) if inner.kind() == "assignment" => inner,
+                _ => continue,
+            },
+            _ => continue,
+        };
+
+        let Some(left) = assignment.child_by_field_name("left") else { continue };
+        if get_node_text(left, source) != "__all__" {
+            continue;
+        }
+
+        let Some(right) = assignment.child_by_field_name("right") else { continue };
+        return Some(collect_string_list_elements(right, source));
+    }
+    None
+}
+
+/// Collects the unquoted string contents of a `list`/`tuple`/`set` literal's
+/// string elements; non-string elements are ignored.
+fn collect_string_list_elements(node: Node, source: &[u8]) -> HashSet<String> {
+    let mut names = HashSet::new();
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.kind() == "string" {
+            names.insert(extract_string_content(get_node_text(child, source)).to_string());
+        }
+    }
+    names
+}
+
+/// True if `node` (a `function_definition` or `class_definition`) is a named
+/// item excluded by the module's `__all__` list.
+fn excluded_by_dunder_all(node: Node, source: &[u8], dunder_all: Option<&HashSet<String>>) -> bool {
+    let Some(names) = dunder_all else { return false };
+    let Some(name) = node.child_by_field_name("name") else { return false };
+    !names.contains(get_node_text(name, source))
+}
+
 /// Internal recursive skeleton extraction
 fn extract_python_skeleton(
     output: &mut String,
@@ -78,16 +141,29 @@ fn extract_python_skeleton(
 
         // Function definitions
         "function_definition" => {
+            if !ctx.is_nested && excluded_by_dunder_all(node, source, ctx.dunder_all) {
+                return;
+            }
             extract_function_skeleton(output, node, source, depth, ctx);
         }
 
         // Decorated definitions (functions or classes with decorators)
         "decorated_definition" => {
+            if !ctx.is_nested {
+                let mut peek_cursor = node.walk();
+                let inner = node.children(&mut peek_cursor)
+                    .find(|c| matches!(c.kind(), "function_definition" | "class_definition"));
+                if inner.is_some_and(|inner| excluded_by_dunder_all(inner, source, ctx.dunder_all)) {
+                    return;
+                }
+            }
+
             let mut cursor = node.walk();
             for child in node.children(&mut cursor) {
                 match child.kind() {
                     "decorator" => {
                         output.push_str(&indent);
+                        output.push_str(&line_number_prefix(child, &ctx.options));
                         output.push_str(&truncate_line(get_node_text(child, source), MAX_DEF_LINE_LEN));
                         output.push('\n');
                     }
@@ -104,6 +180,9 @@ fn extract_python_skeleton(
 
         // Class definitions
         "class_definition" => {
+            if !ctx.is_nested && excluded_by_dunder_all(node, source, ctx.dunder_all) {
+                return;
+            }
             extract_class_skeleton(output, node, source, depth, ctx);
         }
 
@@ -119,9 +198,15 @@ fn extract_python_skeleton(
                 }
             }
 
-            if is_simple_assignment(node, source, MAX_SIMPLE_ASSIGNMENT_LEN) {
+            // `__all__` is the public-API declaration itself, so it's always
+            // shown regardless of the usual assignment-keeping heuristics.
+            let is_dunder_all = node.kind() == "assignment"
+                && node.child_by_field_name("left").is_some_and(|left| get_node_text(left, source) == "__all__");
+
+            if is_dunder_all || is_simple_assignment(node, source, MAX_SIMPLE_ASSIGNMENT_LEN) {
                 output.push_str(&indent);
-                output.push_str(text);
+                output.push_str(&line_number_prefix(node, &ctx.options));
+                output.push_str(&format_assignment_line(text, MAX_SIMPLE_ASSIGNMENT_LEN));
                 output.push('\n');
             }
         }
@@ -130,6 +215,7 @@ fn extract_python_skeleton(
         "type_alias_statement" => {
             if !ctx.is_nested {
                 output.push_str(&indent);
+                output.push_str(&line_number_prefix(node, &ctx.options));
                 output.push_str(get_node_text(node, source));
                 output.push('\n');
             }
@@ -138,9 +224,12 @@ fn extract_python_skeleton(
         // Comments - now with classification!
         "comment" => {
             let text = get_node_text(node, source);
-            let comment_type = classify_comment(text, "#");
+            // Shebang / PEP 263 encoding declarations are small but
+            // load-bearing, so keep them at the top of the file regardless
+            // of the usual classification.
+            let is_top_marker = node.start_position().row <= 1 && is_shebang_or_encoding_comment(text);
 
-            if should_keep_comment(comment_type) {
+            if is_top_marker || should_keep_comment(classify_comment(text, "#")) {
                 output.push_str(&indent);
                 output.push_str(&truncate_line(text, MAX_DEF_LINE_LEN));
                 output.push('\n');
@@ -214,8 +303,9 @@ fn extract_function_skeleton(
     }
 
     // Output signature
-    let signature = truncate_line(&signature, MAX_DEF_LINE_LEN);
+    let signature = truncate_line(&signature, ctx.options.max_def_line_len);
     output.push_str(&indent);
+    output.push_str(&line_number_prefix(node, &ctx.options));
     output.push_str(&signature);
     output.push_str(":\n");
 
@@ -253,7 +343,7 @@ fn extract_function_skeleton(
         let body_text = get_node_text(body, source);
 
         // Emit call edges
-        emit_call_edges(output, body, source, &body_indent, ctx.external_bindings);
+        emit_call_edges(output, body, source, &body_indent, ctx.external_bindings, ctx.options);
 
         // Emit file path reads/writes (data flow)
         let contract = build_state_contract(body, source);
@@ -315,6 +405,7 @@ fn extract_class_skeleton(
             "block" | "class_body" => {
                 let header = truncate_line(&header, MAX_DEF_LINE_LEN);
                 output.push_str(&indent);
+                output.push_str(&line_number_prefix(node, &ctx.options));
                 output.push_str(&header);
                 output.push_str(":\n");
 
@@ -324,6 +415,9 @@ fn extract_class_skeleton(
                     match member.kind() {
                         "function_definition" => {
                             extract_function_skeleton(output, member, source, depth + 1, ctx);
+                            if is_init_method(member, source) {
+                                emit_init_attrs(output, member, source, &member_indent, ctx.options);
+                            }
                         }
                         "decorated_definition" => {
                             let mut dec_cursor = member.walk();
@@ -331,11 +425,15 @@ fn extract_class_skeleton(
                                 match dec_child.kind() {
                                     "decorator" => {
                                         output.push_str(&member_indent);
+                                        output.push_str(&line_number_prefix(dec_child, &ctx.options));
                                         output.push_str(&truncate_line(get_node_text(dec_child, source), MAX_DEF_LINE_LEN));
                                         output.push('\n');
                                     }
                                     "function_definition" => {
                                         extract_function_skeleton(output, dec_child, source, depth + 1, ctx);
+                                        if is_init_method(dec_child, source) {
+                                            emit_init_attrs(output, dec_child, source, &member_indent, ctx.options);
+                                        }
                                     }
                                     "class_definition" => {
                                         extract_class_skeleton(output, dec_child, source, depth + 1, ctx);
@@ -357,7 +455,8 @@ fn extract_class_skeleton(
 
                             if is_simple_assignment(member, source, MAX_CLASS_ATTR_LEN) {
                                 output.push_str(&member_indent);
-                                output.push_str(text);
+                                output.push_str(&line_number_prefix(member, &ctx.options));
+                                output.push_str(&format_assignment_line(text, MAX_CLASS_ATTR_LEN));
                                 output.push('\n');
                             }
                         }
@@ -379,6 +478,82 @@ fn extract_class_skeleton(
     }
 }
 
+/// True if a `function_definition` node is named `__init__`
+fn is_init_method(node: Node, source: &[u8]) -> bool {
+    node.child_by_field_name("name")
+        .is_some_and(|name| get_node_text(name, source) == "__init__")
+}
+
+/// Scan `__init__`'s body for `self.<name> = ...` assignments and emit a
+/// one-line `# attrs: ...` summary, giving the model a class's state shape
+/// without it having to read through the constructor's logic.
+fn emit_init_attrs(output: &mut String, init_node: Node, source: &[u8], indent: &str, options: SkeletonOptions) {
+    let Some(body) = init_node.child_by_field_name("body") else {
+        return;
+    };
+
+    let mut names = Vec::new();
+    let mut total = 0;
+    collect_self_attrs(body, source, &mut names, &mut total, options);
+
+    if names.is_empty() {
+        return;
+    }
+
+    let mut joined = names.join(", ");
+    if total > names.len() {
+        joined.push_str(", ...");
+    }
+    output.push_str(indent);
+    output.push_str("# attrs: ");
+    output.push_str(&truncate_line(&joined, options.max_def_line_len));
+    output.push('\n');
+}
+
+/// Recursively collect `self.<name>` assignment targets from a block,
+/// descending into `if`/`for`/`while`/`with`/`try` bodies so attributes set
+/// conditionally in `__init__` still surface.
+fn collect_self_attrs(node: Node, source: &[u8], names: &mut Vec<String>, total: &mut usize, options: SkeletonOptions) {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        match child.kind() {
+            "expression_statement" => {
+                let mut inner_cursor = child.walk();
+                for inner in child.children(&mut inner_cursor) {
+                    if inner.kind() == "assignment" {
+                        if let Some(name) = self_attr_name(inner, source) {
+                            *total += 1;
+                            if names.len() < options.max_member_names && !names.contains(&name) {
+                                names.push(name);
+                            }
+                        }
+                    }
+                }
+            }
+            "block" | "if_statement" | "elif_clause" | "else_clause" | "for_statement"
+            | "while_statement" | "with_statement" | "try_statement" | "except_clause"
+            | "finally_clause" => {
+                collect_self_attrs(child, source, names, total, options);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// If `assignment` targets `self.<name>`, return `<name>`
+fn self_attr_name(assignment: Node, source: &[u8]) -> Option<String> {
+    let left = assignment.child_by_field_name("left")?;
+    if left.kind() != "attribute" {
+        return None;
+    }
+    let object = left.child_by_field_name("object")?;
+    if get_node_text(object, source) != "self" {
+        return None;
+    }
+    let attr = left.child_by_field_name("attribute")?;
+    Some(get_node_text(attr, source).to_string())
+}
+
 // ============ Call Edge Emission ============
 
 /// Emit function call edges for a function body
@@ -388,8 +563,9 @@ fn emit_call_edges(
     source: &[u8],
     indent: &str,
     external_bindings: Option<&HashSet<String>>,
+    options: SkeletonOptions,
 ) {
-    let calls = collect_calls(node, source, external_bindings);
+    let calls = collect_calls(node, source, external_bindings, options);
     if calls.is_empty() {
         return;
     }
@@ -405,7 +581,7 @@ fn emit_call_edges(
         });
 
         if is_external {
-            if prioritized.len() < MAX_CALL_EDGE_NAMES {
+            if prioritized.len() < options.max_call_edge_names {
                 prioritized.push(name.clone());
             }
         } else {
@@ -415,7 +591,7 @@ fn emit_call_edges(
 
     // Fill remaining slots with local calls
     for name in local {
-        if prioritized.len() >= MAX_CALL_EDGE_NAMES {
+        if prioritized.len() >= options.max_call_edge_names {
             break;
         }
         prioritized.push(name);
@@ -435,9 +611,10 @@ fn collect_calls(
     node: Node,
     source: &[u8],
     external_bindings: Option<&HashSet<String>>,
+    options: SkeletonOptions,
 ) -> CallEdgeList {
     let mut list = CallEdgeList::new();
-    collect_calls_rec(node, source, &mut list, external_bindings);
+    collect_calls_rec(node, source, &mut list, external_bindings, options);
     list
 }
 
@@ -446,19 +623,20 @@ fn collect_calls_rec(
     source: &[u8],
     list: &mut CallEdgeList,
     _external_bindings: Option<&HashSet<String>>,
+    options: SkeletonOptions,
 ) {
     if list.truncated {
         return;
     }
     list.visited += 1;
-    if list.visited > MAX_CALL_EDGE_NODES {
+    if list.visited > options.max_call_edge_nodes {
         list.truncated = true;
         return;
     }
 
     if let Some(name) = call_name(node, source) {
         if !list.entries.contains(&name) {
-            if list.entries.len() < MAX_CALL_EDGE_NAMES * 2 {
+            if list.entries.len() < options.max_call_edge_names * 2 {
                 list.entries.push(name);
             } else {
                 list.truncated = true;
@@ -472,7 +650,7 @@ fn collect_calls_rec(
 
     let mut cursor = node.walk();
     for child in node.children(&mut cursor) {
-        collect_calls_rec(child, source, list, _external_bindings);
+        collect_calls_rec(child, source, list, _external_bindings, options);
         if list.truncated {
             break;
         }
@@ -608,11 +786,26 @@ fn collect_import_identifiers_rec(node: Node, source: &[u8], names: &mut HashSet
 
 // ============ Helper Functions ============
 
+/// Formats an assignment/annotation line for skeleton output. A type
+/// annotation (`x: int`, `y: str = "hi"`, a `@dataclass` field) is exempt
+/// from truncation since the whole point of keeping it is the type info,
+/// which is often exactly what gets cut off past `max_len`.
+fn format_assignment_line(text: &str, max_len: usize) -> String {
+    if text.contains(':') {
+        text.trim().to_string()
+    } else {
+        truncate_line(text, max_len)
+    }
+}
+
 /// Check if an assignment is simple enough to keep
 fn is_simple_assignment(node: Node, source: &[u8], max_len: usize) -> bool {
     let text = get_node_text(node, source);
 
-    // Keep type annotations
+    // Type-annotated assignments (e.g. dataclass fields like
+    // `items: List[Foo] = field(default_factory=list)`) are always kept,
+    // regardless of parens in the value; the caller truncates to `max_len`
+    // when rendering, same as any other kept assignment.
     if text.contains(':') {
         return true;
     }
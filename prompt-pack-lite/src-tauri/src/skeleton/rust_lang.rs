@@ -12,26 +12,33 @@ use tree_sitter::Node;
 
 use super::common::{
     get_node_text, truncate_line, compact_text_prefix, trim_doc_comment,
-    CallEdgeList,
-    MAX_DEF_LINE_LEN, MAX_SIMPLE_CONST_LEN, MAX_MEMBER_NAMES,
-    MAX_CALL_EDGE_NAMES, MAX_CALL_EDGE_NAME_LEN, MAX_CALL_EDGE_NODES,
+    classify_comment, CommentType, CallEdgeList, SkeletonOptions,
+    line_number_prefix,
+    MAX_DEF_LINE_LEN, MAX_SIMPLE_CONST_LEN,
+    MAX_CALL_EDGE_NAME_LEN,
 };
 
 // ============ Main Entry Point ============
 
 /// Extract skeleton from Rust source code
 pub fn extract_skeleton(content: &str, root: Node, source: &[u8]) -> String {
+    extract_skeleton_with_options(content, root, source, &SkeletonOptions::default())
+}
+
+/// Extract skeleton from Rust source code with caller-supplied call-edge limits.
+pub fn extract_skeleton_with_options(content: &str, root: Node, source: &[u8], options: &SkeletonOptions) -> String {
     let _ = content; // Used for potential future enhancements
     let mut output = String::new();
-    extract_rust_skeleton(&mut output, root, source, 0);
+    extract_rust_skeleton(&mut output, root, source, 0, options);
     output
 }
 
 /// Internal recursive skeleton extraction
-fn extract_rust_skeleton(output: &mut String, node: Node, source: &[u8], depth: usize) {
+fn extract_rust_skeleton(output: &mut String, node: Node, source: &[u8], depth: usize, options: &SkeletonOptions) {
     match node.kind() {
         // Keep use statements
         "use_declaration" => {
+            output.push_str(&line_number_prefix(node, options));
             output.push_str(&truncate_line(get_node_text(node, source), MAX_DEF_LINE_LEN));
             output.push('\n');
         }
@@ -41,56 +48,68 @@ fn extract_rust_skeleton(output: &mut String, node: Node, source: &[u8], depth:
             let text = get_node_text(node, source);
             if text.contains('{') {
                 // Inline module - extract contents
-                extract_rust_mod_skeleton(output, node, source, depth);
+                extract_rust_mod_skeleton(output, node, source, depth, options);
             } else {
                 // External module reference
-                output.push_str(text);
+                output.push_str(&truncate_line(text, MAX_DEF_LINE_LEN));
                 output.push('\n');
             }
         }
 
         // Struct definitions
         "struct_item" => {
-            output.push_str(&summarize_rust_struct(node, source));
+            output.push_str(&line_number_prefix(node, options));
+            output.push_str(&summarize_rust_struct(node, source, options));
             output.push('\n');
         }
 
         // Enum definitions
         "enum_item" => {
-            output.push_str(&summarize_rust_enum(node, source));
+            output.push_str(&line_number_prefix(node, options));
+            output.push_str(&summarize_rust_enum(node, source, options));
             output.push('\n');
         }
 
         // Type aliases
         "type_item" => {
+            output.push_str(&line_number_prefix(node, options));
             output.push_str(&summarize_assignment(get_node_text(node, source)));
             output.push('\n');
         }
 
         // Trait definitions
         "trait_item" => {
-            extract_rust_trait_skeleton(output, node, source, depth);
+            extract_rust_trait_skeleton(output, node, source, depth, options);
         }
 
         // Impl blocks
         "impl_item" => {
-            extract_rust_impl_skeleton(output, node, source, depth);
+            extract_rust_impl_skeleton(output, node, source, depth, options);
         }
 
         // Function definitions
         "function_item" => {
-            extract_rust_function_skeleton(output, node, source, depth);
+            let emit_calls = is_pub_rust_item(node);
+            extract_rust_function_skeleton(output, node, source, depth, emit_calls, options);
         }
 
         // Constants and statics
         "const_item" | "static_item" => {
+            output.push_str(&line_number_prefix(node, options));
             output.push_str(&summarize_assignment(get_node_text(node, source)));
             output.push('\n');
         }
 
+        // Macro invocations used as items (lazy_static! { ... },
+        // thread_local! { ... }, tauri::generate_handler![...], etc.)
+        "macro_invocation" => {
+            emit_macro_invocation_item(output, node, source, depth);
+        }
+
         // Macro definitions (keep signature)
         "macro_definition" => {
             let text = get_node_text(node, source);
+            output.push_str(&line_number_prefix(node, options));
             if let Some(brace_pos) = text.find('{') {
                 output.push_str(&truncate_line(text[..brace_pos].trim(), MAX_DEF_LINE_LEN));
                 output.push('\n');
@@ -102,25 +121,27 @@ fn extract_rust_skeleton(output: &mut String, node: Node, source: &[u8], depth:
 
         // Attributes (keep them, they're important)
         "attribute_item" | "inner_attribute_item" => {
+            output.push_str(&line_number_prefix(node, options));
             output.push_str(&truncate_line(get_node_text(node, source), MAX_DEF_LINE_LEN));
             output.push('\n');
         }
 
-        // Line/block comments with docs
+        // Line/block comments with docs, plus TODO/FIXME/SAFETY/HACK/NOTE
+        // comments even when they aren't doc comments.
         "line_comment" | "block_comment" => {
             let text = get_node_text(node, source);
             if let Some(summary) = trim_doc_comment(text) {
                 output.push_str(&summary);
                 output.push('\n');
+            } else if classify_comment(text, "//") == CommentType::Todo {
+                output.push_str(&truncate_line(text, MAX_DEF_LINE_LEN));
+                output.push('\n');
             }
         }
 
         // Source file root
         "source_file" => {
-            let mut cursor = node.walk();
-            for child in node.children(&mut cursor) {
-                extract_rust_skeleton(output, child, source, depth);
-            }
+            extract_children_with_cfg_tracking(output, node, source, depth, options);
         }
 
         _ => {
@@ -128,7 +149,7 @@ fn extract_rust_skeleton(output: &mut String, node: Node, source: &[u8], depth:
             if node.child_count() > 0 {
                 let mut cursor = node.walk();
                 for child in node.children(&mut cursor) {
-                    extract_rust_skeleton(output, child, source, depth);
+                    extract_rust_skeleton(output, child, source, depth, options);
                 }
             }
         }
@@ -138,7 +159,7 @@ fn extract_rust_skeleton(output: &mut String, node: Node, source: &[u8], depth:
 // ============ Module Extraction ============
 
 /// Extract Rust module skeleton
-fn extract_rust_mod_skeleton(output: &mut String, node: Node, source: &[u8], depth: usize) {
+fn extract_rust_mod_skeleton(output: &mut String, node: Node, source: &[u8], depth: usize, options: &SkeletonOptions) {
     let indent = "    ".repeat(depth);
     let mut cursor = node.walk();
 
@@ -160,11 +181,171 @@ fn extract_rust_mod_skeleton(output: &mut String, node: Node, source: &[u8], dep
             }
             "declaration_list" => {
                 output.push('\n');
-                let mut list_cursor = child.walk();
-                for item in child.children(&mut list_cursor) {
-                    extract_rust_skeleton(output, item, source, depth + 1);
+                extract_children_with_cfg_tracking(output, child, source, depth + 1, options);
+            }
+            _ => {}
+        }
+    }
+}
+
+// ============ Attribute / cfg Tracking ============
+
+/// Whether an attribute's text is specifically `#[cfg(test)]`.
+fn is_cfg_test_attribute(text: &str) -> bool {
+    let inner = text.trim_start_matches('#').trim_start_matches('!').trim();
+    inner == "[cfg(test)]"
+}
+
+/// Whether an attribute's text is a `#[derive(...)]` list.
+fn is_derive_attribute(text: &str) -> bool {
+    let inner = text.trim_start_matches('#').trim_start_matches('!').trim();
+    inner.starts_with("[derive(")
+}
+
+/// Formats an attribute for skeleton output. `#[derive(...)]` is exempt from
+/// truncation since every derived trait is context worth keeping (unlike a
+/// long doc comment or macro attribute, where truncation is a fine trade-off).
+fn format_attribute_line(text: &str) -> String {
+    if is_derive_attribute(text) {
+        text.trim().to_string()
+    } else {
+        truncate_line(text, MAX_DEF_LINE_LEN)
+    }
+}
+
+/// Walk `parent`'s children, carrying each attribute forward so it's emitted
+/// immediately before the item it actually gates instead of being dropped
+/// (in impl/trait member lists) or drifting apart from it. A `#[cfg(test)]`
+/// module is special-cased: instead of expanding its full body, we collapse
+/// it to a one-line test count summary, since its contents are exhaustively
+/// covered elsewhere and rarely worth the skeleton budget.
+fn extract_children_with_cfg_tracking(output: &mut String, parent: Node, source: &[u8], depth: usize, options: &SkeletonOptions) {
+    let mut cursor = parent.walk();
+    let mut pending_attrs: Vec<String> = Vec::new();
+    let mut pending_is_cfg_test = false;
+
+    for child in parent.children(&mut cursor) {
+        match child.kind() {
+            "attribute_item" | "inner_attribute_item" => {
+                let text = get_node_text(child, source);
+                if is_cfg_test_attribute(text) {
+                    pending_is_cfg_test = true;
                 }
+                pending_attrs.push(format_attribute_line(text));
+            }
+            "mod_item" if pending_is_cfg_test => {
+                emit_collapsed_test_mod(output, child, source, depth);
+                pending_attrs.clear();
+                pending_is_cfg_test = false;
+            }
+            _ => {
+                for attr in pending_attrs.drain(..) {
+                    let indent = "    ".repeat(depth);
+                    output.push_str(&indent);
+                    output.push_str(&attr);
+                    output.push('\n');
+                }
+                pending_is_cfg_test = false;
+                extract_rust_skeleton(output, child, source, depth, options);
+            }
+        }
+    }
+
+    // A trailing attribute with nothing left to gate (rare) still gets printed.
+    for attr in pending_attrs.drain(..) {
+        let indent = "    ".repeat(depth);
+        output.push_str(&indent);
+        output.push_str(&attr);
+        output.push('\n');
+    }
+}
+
+/// Collapse a `#[cfg(test)] mod { ... }` block to a single summary line
+/// instead of expanding every test function inside it.
+fn emit_collapsed_test_mod(output: &mut String, mod_node: Node, source: &[u8], depth: usize) {
+    let indent = "    ".repeat(depth);
+    let name = mod_node
+        .child_by_field_name("name")
+        .map(|n| get_node_text(n, source))
+        .unwrap_or("tests");
+    let test_count = count_test_functions(mod_node, source);
+
+    output.push_str(&indent);
+    output.push_str(&format!("mod {name} {{ /* {test_count} test{} */ }}", if test_count == 1 { "" } else { "s" }));
+    output.push('\n');
+}
+
+fn count_test_functions(node: Node, source: &[u8]) -> usize {
+    let mut count = 0;
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        match child.kind() {
+            "function_item" => count += 1,
+            "attribute_item" | "inner_attribute_item" => {}
+            _ if child.kind().ends_with("_list") || child.kind() == "declaration_list" => {
+                count += count_test_functions(child, source);
+            }
+            _ => {}
+        }
+    }
+    count
+}
+
+/// Whether a Rust item node (function, etc.) carries a `pub` visibility
+/// modifier of any form (`pub`, `pub(crate)`, `pub(super)`, ...).
+fn is_pub_rust_item(node: Node) -> bool {
+    let mut cursor = node.walk();
+    node.children(&mut cursor).any(|c| c.kind() == "visibility_modifier")
+}
+
+// ============ Macro Invocation Items ============
+
+const MAX_MACRO_ARG_NAMES: usize = 12;
+
+/// Item-level macro invocations (`lazy_static! { ... }`, `thread_local! { ... }`,
+/// a bare `tauri::generate_handler![...]`, etc.) have no structure tree-sitter
+/// can hand us beyond a flat token stream, so the generic item dispatch drops
+/// them entirely. Keep the macro name and pull out the identifiers among its
+/// arguments instead of losing the item outright.
+fn emit_macro_invocation_item(output: &mut String, node: Node, source: &[u8], depth: usize) {
+    let indent = "    ".repeat(depth);
+    let Some(macro_node) = node.child_by_field_name("macro") else {
+        return;
+    };
+    let name = get_node_text(macro_node, source);
+
+    let mut names = Vec::new();
+    let mut cursor = node.walk();
+    if let Some(token_tree) = node.children(&mut cursor).find(|c| c.kind() == "token_tree") {
+        collect_macro_arg_identifiers(token_tree, source, &mut names);
+    }
+
+    let args = if names.is_empty() {
+        "...".to_string()
+    } else {
+        let mut joined = names.join(", ");
+        if names.len() >= MAX_MACRO_ARG_NAMES {
+            joined.push_str(", ...");
+        }
+        joined
+    };
+
+    output.push_str(&indent);
+    output.push_str(&truncate_line(&format!("{name}!({args})"), MAX_DEF_LINE_LEN));
+    output.push('\n');
+}
+
+fn collect_macro_arg_identifiers(node: Node, source: &[u8], names: &mut Vec<String>) {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if names.len() >= MAX_MACRO_ARG_NAMES {
+            return;
+        }
+        match child.kind() {
+            "identifier" | "scoped_identifier" => {
+                names.push(get_node_text(child, source).to_string());
             }
+            "token_tree" => collect_macro_arg_identifiers(child, source, names),
             _ => {}
         }
     }
@@ -172,34 +353,81 @@ fn extract_rust_mod_skeleton(output: &mut String, node: Node, source: &[u8], dep
 
 // ============ Function Extraction ============
 
-/// Extract Rust function skeleton
-fn extract_rust_function_skeleton(output: &mut String, node: Node, source: &[u8], depth: usize) {
+/// Extract Rust function skeleton. `emit_calls` gates whether the function's
+/// call edges are worth surfacing: by default only public functions (and
+/// trait impl methods, whose visibility is governed by the trait rather
+/// than an explicit `pub`) get them, since call edges for private helpers
+/// mostly just restate what's a few lines below in the same skeleton.
+fn extract_rust_function_skeleton(output: &mut String, node: Node, source: &[u8], depth: usize, emit_calls: bool, options: &SkeletonOptions) {
     let indent = "    ".repeat(depth);
     let text = get_node_text(node, source);
 
-    // Find the function body start
-    if let Some(brace_pos) = text.find('{') {
-        let signature = truncate_line(text[..brace_pos].trim(), MAX_DEF_LINE_LEN);
-        output.push_str(&indent);
-        output.push_str(&signature);
-        output.push('\n');
-        emit_rust_call_edges(output, node, source, &indent);
-    } else {
-        // No body (trait method signature)
-        let signature = truncate_line(text, MAX_DEF_LINE_LEN);
-        output.push_str(&indent);
-        output.push_str(&signature);
-        output.push('\n');
-        emit_rust_call_edges(output, node, source, &indent);
+    let signature = truncate_line(&rust_extract_fn_signature(text), MAX_DEF_LINE_LEN);
+    output.push_str(&indent);
+    output.push_str(&line_number_prefix(node, options));
+    output.push_str(&signature);
+    if function_body_contains_unsafe_block(node) {
+        output.push_str(" // [unsafe]");
+    }
+    output.push('\n');
+    if emit_calls {
+        emit_rust_call_edges(output, node, source, &indent, options);
+    }
+}
+
+/// Whether `node` (a function/method item) contains an `unsafe { ... }`
+/// block in its body — worth flagging separately from an `unsafe fn`
+/// signature, which already shows `unsafe` in the signature text itself.
+fn function_body_contains_unsafe_block(node: Node) -> bool {
+    node.child_by_field_name("body").is_some_and(contains_unsafe_block)
+}
+
+/// Recursively checks for an `unsafe_block` descendant, stopping at the same
+/// scope boundaries as call-edge collection so a nested closure or function's
+/// own unsafe usage isn't misattributed to the outer function.
+fn contains_unsafe_block(node: Node) -> bool {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.kind() == "unsafe_block" {
+            return true;
+        }
+        if rust_is_scope_boundary(child.kind()) {
+            continue;
+        }
+        if contains_unsafe_block(child) {
+            return true;
+        }
     }
+    false
+}
+
+/// Extracts a function/method signature from its full node text by finding
+/// the `{` that opens the body, tracking bracket depth so a `{` nested
+/// inside generic parameters, parameter types, or array lengths (e.g. a
+/// const generic's default value, `fn foo<const N: usize = { 1 + 1 }>(...)`)
+/// isn't mistaken for the body's own opening brace — which would otherwise
+/// truncate everything after it, including a `where` clause. Falls back to
+/// the whole trimmed text when there's no body at all (a trait method
+/// signature ending in `;`).
+fn rust_extract_fn_signature(text: &str) -> String {
+    let mut depth: i32 = 0;
+    for (idx, ch) in text.char_indices() {
+        match ch {
+            '<' | '(' | '[' => depth += 1,
+            '>' | ')' | ']' => depth -= 1,
+            '{' if depth <= 0 => return text[..idx].trim().to_string(),
+            _ => {}
+        }
+    }
+    text.trim().to_string()
 }
 
 /// Emit call edges for a Rust function
-fn emit_rust_call_edges(output: &mut String, node: Node, source: &[u8], indent: &str) {
+fn emit_rust_call_edges(output: &mut String, node: Node, source: &[u8], indent: &str, options: &SkeletonOptions) {
     let Some(body) = node.child_by_field_name("body") else {
         return;
     };
-    let calls = collect_rust_calls(body, source);
+    let calls = collect_rust_calls(body, source, options);
     if calls.entries.is_empty() {
         return;
     }
@@ -213,25 +441,25 @@ fn emit_rust_call_edges(output: &mut String, node: Node, source: &[u8], indent:
 }
 
 /// Collect function calls from a Rust node
-fn collect_rust_calls(node: Node, source: &[u8]) -> CallEdgeList {
+fn collect_rust_calls(node: Node, source: &[u8], options: &SkeletonOptions) -> CallEdgeList {
     let mut list = CallEdgeList::new();
-    collect_rust_calls_rec(node, source, &mut list);
+    collect_rust_calls_rec(node, source, &mut list, options);
     list
 }
 
-fn collect_rust_calls_rec(node: Node, source: &[u8], list: &mut CallEdgeList) {
+fn collect_rust_calls_rec(node: Node, source: &[u8], list: &mut CallEdgeList, options: &SkeletonOptions) {
     if list.truncated {
         return;
     }
     list.visited += 1;
-    if list.visited > MAX_CALL_EDGE_NODES {
+    if list.visited > options.max_call_edge_nodes {
         list.truncated = true;
         return;
     }
 
     if let Some(name) = rust_call_name(node, source) {
         if !list.entries.contains(&name) {
-            if list.entries.len() < MAX_CALL_EDGE_NAMES {
+            if list.entries.len() < options.max_call_edge_names {
                 list.entries.push(name);
             } else {
                 list.truncated = true;
@@ -246,25 +474,39 @@ fn collect_rust_calls_rec(node: Node, source: &[u8], list: &mut CallEdgeList) {
 
     let mut cursor = node.walk();
     for child in node.children(&mut cursor) {
-        collect_rust_calls_rec(child, source, list);
+        collect_rust_calls_rec(child, source, list, options);
         if list.truncated {
             break;
         }
     }
 }
 
-/// Extract the name of a Rust function call
+/// Extract the name of a Rust function call, or a macro invocation (e.g.
+/// `tauri::generate_handler![...]`) — the latter surfaced with a trailing
+/// `!` so it reads distinctly from an ordinary call in the edge list.
 fn rust_call_name(node: Node, source: &[u8]) -> Option<String> {
-    if node.kind() != "call_expression" {
-        return None;
-    }
-    let func = node.child_by_field_name("function")?;
-    let (compact, _) = compact_text_prefix(get_node_text(func, source), MAX_CALL_EDGE_NAME_LEN);
-    let name = compact.trim();
-    if name.is_empty() {
-        return None;
+    match node.kind() {
+        "call_expression" => {
+            let func = node.child_by_field_name("function")?;
+            let (compact, _) = compact_text_prefix(get_node_text(func, source), MAX_CALL_EDGE_NAME_LEN);
+            let name = compact.trim();
+            if name.is_empty() {
+                None
+            } else {
+                Some(truncate_line(name, MAX_CALL_EDGE_NAME_LEN))
+            }
+        }
+        "macro_invocation" => {
+            let macro_node = node.child_by_field_name("macro")?;
+            let name = get_node_text(macro_node, source).trim();
+            if name.is_empty() {
+                None
+            } else {
+                Some(truncate_line(&format!("{name}!"), MAX_CALL_EDGE_NAME_LEN))
+            }
+        }
+        _ => None,
     }
-    Some(truncate_line(name, MAX_CALL_EDGE_NAME_LEN))
 }
 
 /// Check if a node kind represents a scope boundary
@@ -275,7 +517,7 @@ fn rust_is_scope_boundary(kind: &str) -> bool {
 // ============ Trait Extraction ============
 
 /// Extract Rust trait skeleton
-fn extract_rust_trait_skeleton(output: &mut String, node: Node, source: &[u8], depth: usize) {
+fn extract_rust_trait_skeleton(output: &mut String, node: Node, source: &[u8], depth: usize, options: &SkeletonOptions) {
     let indent = "    ".repeat(depth);
     let member_indent = "    ".repeat(depth + 1);
 
@@ -301,32 +543,47 @@ fn extract_rust_trait_skeleton(output: &mut String, node: Node, source: &[u8], d
             }
             "declaration_list" => {
                 output.push_str(&indent);
+                output.push_str(&line_number_prefix(node, options));
                 output.push_str(&truncate_line(&header, MAX_DEF_LINE_LEN));
                 output.push('\n');
 
                 let mut list_cursor = child.walk();
+                let mut pending_attrs: Vec<String> = Vec::new();
                 for item in child.children(&mut list_cursor) {
                     match item.kind() {
+                        "attribute_item" | "inner_attribute_item" => {
+                            pending_attrs.push(truncate_line(get_node_text(item, source), MAX_DEF_LINE_LEN));
+                        }
+                        // Doc comments sitting between an attribute and the
+                        // member it gates (e.g. `#[test]` then `/// why`)
+                        // shouldn't flush the attribute early.
+                        "line_comment" | "block_comment" => {}
                         "function_signature_item" | "function_item" => {
+                            for attr in pending_attrs.drain(..) {
+                                output.push_str(&member_indent);
+                                output.push_str(&attr);
+                                output.push('\n');
+                            }
                             let text = get_node_text(item, source);
                             output.push_str(&member_indent);
-                            if text.contains('{') {
-                                if let Some(brace_pos) = text.find('{') {
-                                    let signature = truncate_line(text[..brace_pos].trim(), MAX_DEF_LINE_LEN);
-                                    output.push_str(&signature);
-                                }
-                            } else {
-                                let signature = truncate_line(text, MAX_DEF_LINE_LEN);
-                                output.push_str(&signature);
-                            }
+                            output.push_str(&line_number_prefix(item, options));
+                            output.push_str(&truncate_line(&rust_extract_fn_signature(text), MAX_DEF_LINE_LEN));
                             output.push('\n');
                         }
                         "associated_type" | "const_item" => {
+                            for attr in pending_attrs.drain(..) {
+                                output.push_str(&member_indent);
+                                output.push_str(&attr);
+                                output.push('\n');
+                            }
                             output.push_str(&member_indent);
-                            output.push_str(get_node_text(item, source));
+                            output.push_str(&line_number_prefix(item, options));
+                            output.push_str(&truncate_line(get_node_text(item, source), MAX_DEF_LINE_LEN));
                             output.push('\n');
                         }
-                        _ => {}
+                        _ => {
+                            pending_attrs.clear();
+                        }
                     }
                 }
             }
@@ -338,16 +595,22 @@ fn extract_rust_trait_skeleton(output: &mut String, node: Node, source: &[u8], d
 // ============ Impl Extraction ============
 
 /// Extract Rust impl skeleton
-fn extract_rust_impl_skeleton(output: &mut String, node: Node, source: &[u8], depth: usize) {
+fn extract_rust_impl_skeleton(output: &mut String, node: Node, source: &[u8], depth: usize, options: &SkeletonOptions) {
     let indent = "    ".repeat(depth);
     let member_indent = "    ".repeat(depth + 1);
 
     let text = get_node_text(node, source);
+    // A trait impl (`impl Trait for Type`) has a `trait` field; its methods'
+    // visibility is governed by the trait, not by an (often absent) `pub` on
+    // the method itself, so they always get call edges. An inherent impl
+    // (`impl Type`) only gets call edges for explicitly `pub` methods.
+    let is_trait_impl = node.child_by_field_name("trait").is_some();
 
     // Find impl header up to the opening brace
     if let Some(brace_pos) = text.find('{') {
         let header = truncate_line(text[..brace_pos].trim(), MAX_DEF_LINE_LEN);
         output.push_str(&indent);
+        output.push_str(&line_number_prefix(node, options));
         output.push_str(&header);
         output.push('\n');
 
@@ -356,24 +619,51 @@ fn extract_rust_impl_skeleton(output: &mut String, node: Node, source: &[u8], de
         for child in node.children(&mut cursor) {
             if child.kind() == "declaration_list" {
                 let mut list_cursor = child.walk();
+                let mut pending_attrs: Vec<String> = Vec::new();
                 for item in child.children(&mut list_cursor) {
                     match item.kind() {
+                        "attribute_item" | "inner_attribute_item" => {
+                            pending_attrs.push(truncate_line(get_node_text(item, source), MAX_DEF_LINE_LEN));
+                        }
+                        // Doc comments sitting between an attribute and the
+                        // member it gates (e.g. `#[test]` then `/// why`)
+                        // shouldn't flush the attribute early.
+                        "line_comment" | "block_comment" => {}
                         "function_item" => {
+                            for attr in pending_attrs.drain(..) {
+                                output.push_str(&member_indent);
+                                output.push_str(&attr);
+                                output.push('\n');
+                            }
                             let fn_text = get_node_text(item, source);
-                            if let Some(fn_brace) = fn_text.find('{') {
-                                let signature = truncate_line(fn_text[..fn_brace].trim(), MAX_DEF_LINE_LEN);
+                            if fn_text.contains('{') {
+                                let signature = truncate_line(&rust_extract_fn_signature(fn_text), MAX_DEF_LINE_LEN);
                                 output.push_str(&member_indent);
+                                output.push_str(&line_number_prefix(item, options));
                                 output.push_str(&signature);
+                                if function_body_contains_unsafe_block(item) {
+                                    output.push_str(" // [unsafe]");
+                                }
                                 output.push('\n');
-                                emit_rust_call_edges(output, item, source, &member_indent);
+                                if is_trait_impl || is_pub_rust_item(item) {
+                                    emit_rust_call_edges(output, item, source, &member_indent, options);
+                                }
                             }
                         }
                         "const_item" | "type_item" => {
+                            for attr in pending_attrs.drain(..) {
+                                output.push_str(&member_indent);
+                                output.push_str(&attr);
+                                output.push('\n');
+                            }
                             output.push_str(&member_indent);
-                            output.push_str(get_node_text(item, source));
+                            output.push_str(&line_number_prefix(item, options));
+                            output.push_str(&truncate_line(get_node_text(item, source), MAX_DEF_LINE_LEN));
                             output.push('\n');
                         }
-                        _ => {}
+                        _ => {
+                            pending_attrs.clear();
+                        }
                     }
                 }
             }
@@ -401,11 +691,11 @@ fn summarize_assignment(text: &str) -> String {
 }
 
 /// Summarize a Rust struct definition
-fn summarize_rust_struct(node: Node, source: &[u8]) -> String {
+fn summarize_rust_struct(node: Node, source: &[u8], options: &SkeletonOptions) -> String {
     let text = get_node_text(node, source);
     if let Some(brace_pos) = text.find('{') {
         let header = text[..brace_pos].trim_end();
-        let (names, truncated) = rust_collect_struct_fields(node, source);
+        let (names, truncated) = rust_collect_struct_fields(node, source, options);
         let body = if names.is_empty() {
             "...".to_string()
         } else {
@@ -413,23 +703,23 @@ fn summarize_rust_struct(node: Node, source: &[u8]) -> String {
             if truncated {
                 joined.push_str(", ...");
             }
-            truncate_line(&joined, MAX_DEF_LINE_LEN)
+            truncate_line(&joined, options.max_def_line_len)
         };
-        return truncate_line(&format!("{header} {{ {body} }}"), MAX_DEF_LINE_LEN);
+        return truncate_line(&format!("{header} {{ {body} }}"), options.max_def_line_len);
     }
     if let Some(paren_pos) = text.find('(') {
         let header = text[..paren_pos].trim_end();
-        return truncate_line(&format!("{header} (...)"), MAX_DEF_LINE_LEN);
+        return truncate_line(&format!("{header} (...)"), options.max_def_line_len);
     }
-    truncate_line(text, MAX_DEF_LINE_LEN)
+    truncate_line(text, options.max_def_line_len)
 }
 
 /// Summarize a Rust enum definition
-fn summarize_rust_enum(node: Node, source: &[u8]) -> String {
+fn summarize_rust_enum(node: Node, source: &[u8], options: &SkeletonOptions) -> String {
     let text = get_node_text(node, source);
     if let Some(brace_pos) = text.find('{') {
         let header = text[..brace_pos].trim_end();
-        let (names, truncated) = rust_collect_enum_variants(node, source);
+        let (names, truncated) = rust_collect_enum_variants(node, source, options);
         let body = if names.is_empty() {
             "...".to_string()
         } else {
@@ -437,15 +727,15 @@ fn summarize_rust_enum(node: Node, source: &[u8]) -> String {
             if truncated {
                 joined.push_str(", ...");
             }
-            truncate_line(&joined, MAX_DEF_LINE_LEN)
+            truncate_line(&joined, options.max_def_line_len)
         };
-        return truncate_line(&format!("{header} {{ {body} }}"), MAX_DEF_LINE_LEN);
+        return truncate_line(&format!("{header} {{ {body} }}"), options.max_def_line_len);
     }
-    truncate_line(text, MAX_DEF_LINE_LEN)
+    truncate_line(text, options.max_def_line_len)
 }
 
 /// Collect field names from a Rust struct
-fn rust_collect_struct_fields(node: Node, source: &[u8]) -> (Vec<String>, bool) {
+fn rust_collect_struct_fields(node: Node, source: &[u8], options: &SkeletonOptions) -> (Vec<String>, bool) {
     let mut names = Vec::new();
     let mut total = 0;
     let mut cursor = node.walk();
@@ -461,12 +751,12 @@ fn rust_collect_struct_fields(node: Node, source: &[u8]) -> (Vec<String>, bool)
                 let mut field_cursor = field.walk();
                 let mut name = None;
                 for fchild in field.children(&mut field_cursor) {
-                    if fchild.kind() == "identifier" {
+                    if fchild.kind() == "field_identifier" {
                         name = Some(get_node_text(fchild, source).to_string());
                         break;
                     }
                 }
-                if names.len() < MAX_MEMBER_NAMES {
+                if names.len() < options.max_member_names {
                     if let Some(name) = name {
                         names.push(name);
                     }
@@ -480,7 +770,7 @@ fn rust_collect_struct_fields(node: Node, source: &[u8]) -> (Vec<String>, bool)
 }
 
 /// Collect variant names from a Rust enum
-fn rust_collect_enum_variants(node: Node, source: &[u8]) -> (Vec<String>, bool) {
+fn rust_collect_enum_variants(node: Node, source: &[u8], options: &SkeletonOptions) -> (Vec<String>, bool) {
     let mut names = Vec::new();
     let mut total = 0;
     let mut cursor = node.walk();
@@ -495,7 +785,7 @@ fn rust_collect_enum_variants(node: Node, source: &[u8]) -> (Vec<String>, bool)
                 total += 1;
                 let mut var_cursor = variant.walk();
                 for vchild in variant.children(&mut var_cursor) {
-                    if vchild.kind() == "identifier" && names.len() < MAX_MEMBER_NAMES {
+                    if vchild.kind() == "identifier" && names.len() < options.max_member_names {
                         names.push(get_node_text(vchild, source).to_string());
                         break;
                     }
@@ -510,11 +800,239 @@ fn rust_collect_enum_variants(node: Node, source: &[u8]) -> (Vec<String>, bool)
 
 #[cfg(test)]
 mod tests {
-    #[allow(unused_imports)]
     use super::*;
+    use tree_sitter::Parser;
+
+    fn parse_rust(code: &str) -> String {
+        let mut parser = Parser::new();
+        parser.set_language(&tree_sitter_rust::LANGUAGE.into()).unwrap();
+        let tree = parser.parse(code, None).unwrap();
+        extract_skeleton(code, tree.root_node(), code.as_bytes())
+    }
+
+    fn parse_rust_with_options(code: &str, options: &SkeletonOptions) -> String {
+        let mut parser = Parser::new();
+        parser.set_language(&tree_sitter_rust::LANGUAGE.into()).unwrap();
+        let tree = parser.parse(code, None).unwrap();
+        extract_skeleton_with_options(code, tree.root_node(), code.as_bytes(), options)
+    }
 
     #[test]
     fn test_module_compiles() {
         // Ensure the module compiles correctly
     }
+
+    #[test]
+    fn fn_signature_extraction_keeps_lifetime_and_where_clause() {
+        let code = "fn foo<'a, T: Clone + 'a>(x: &'a T) -> &'a T where T: Debug {\n    x\n}\n";
+        let skeleton = parse_rust(code);
+        assert!(
+            skeleton.contains("fn foo<'a, T: Clone + 'a>(x: &'a T) -> &'a T where T: Debug"),
+            "skeleton was:\n{skeleton}"
+        );
+    }
+
+    #[test]
+    fn fn_signature_extraction_ignores_braces_nested_in_generics() {
+        let signature = rust_extract_fn_signature(
+            "fn foo<const N: usize = { 1 + 1 }>(x: [u8; N]) -> [u8; N] where [u8; N]: Default {\n    x\n}",
+        );
+        assert_eq!(
+            signature,
+            "fn foo<const N: usize = { 1 + 1 }>(x: [u8; N]) -> [u8; N] where [u8; N]: Default"
+        );
+    }
+
+    #[test]
+    fn fn_signature_extraction_falls_back_to_whole_text_without_a_body() {
+        let signature = rust_extract_fn_signature("fn hello(&self) -> String;");
+        assert_eq!(signature, "fn hello(&self) -> String;");
+    }
+
+    #[test]
+    fn unsafe_fn_signature_keeps_unsafe_keyword() {
+        let code = "unsafe fn danger() {\n    *(0 as *mut u8) = 1;\n}\n";
+        let skeleton = parse_rust(code);
+        assert!(skeleton.contains("unsafe fn danger()"), "skeleton was:\n{skeleton}");
+    }
+
+    #[test]
+    fn safe_function_with_unsafe_block_is_annotated() {
+        let code = "fn wraps_unsafe() {\n    unsafe {\n        *(0 as *mut u8) = 1;\n    }\n}\n";
+        let skeleton = parse_rust(code);
+        assert!(skeleton.contains("fn wraps_unsafe() // [unsafe]"), "skeleton was:\n{skeleton}");
+    }
+
+    #[test]
+    fn safe_function_without_unsafe_block_has_no_annotation() {
+        let code = "fn totally_safe() {\n    let x = 1;\n    println!(\"{x}\");\n}\n";
+        let skeleton = parse_rust(code);
+        assert!(!skeleton.contains("[unsafe]"), "skeleton was:\n{skeleton}");
+    }
+
+    #[test]
+    fn impl_method_with_unsafe_block_is_annotated() {
+        let code = "struct S;\nimpl S {\n    pub fn wraps_unsafe(&self) {\n        unsafe {\n            *(0 as *mut u8) = 1;\n        }\n    }\n}\n";
+        let skeleton = parse_rust(code);
+        assert!(skeleton.contains("fn wraps_unsafe(&self) // [unsafe]"), "skeleton was:\n{skeleton}");
+    }
+
+    #[test]
+    fn pub_free_function_gets_call_edges() {
+        let code = "pub fn run() {\n    helper();\n}\n";
+        let skeleton = parse_rust(code);
+        assert!(skeleton.contains("// Calls: helper"));
+    }
+
+    #[test]
+    fn private_free_function_has_no_call_edges() {
+        let code = "fn run() {\n    helper();\n}\n";
+        let skeleton = parse_rust(code);
+        assert!(!skeleton.contains("// Calls"));
+    }
+
+    #[test]
+    fn private_inherent_impl_method_has_no_call_edges() {
+        let code = "struct Foo;\nimpl Foo {\n    fn run(&self) {\n        helper();\n    }\n}\n";
+        let skeleton = parse_rust(code);
+        assert!(!skeleton.contains("// Calls"));
+    }
+
+    #[test]
+    fn trait_impl_method_gets_call_edges_without_pub() {
+        let code = "struct Foo;\nimpl Display for Foo {\n    fn fmt(&self) {\n        helper();\n    }\n}\n";
+        let skeleton = parse_rust(code);
+        assert!(skeleton.contains("// Calls: helper"));
+    }
+
+    #[test]
+    fn cfg_attribute_is_carried_onto_impl_method_signature() {
+        let code = "struct Foo;\nimpl Foo {\n    #[cfg(feature = \"x\")]\n    pub fn run(&self) {}\n}\n";
+        let skeleton = parse_rust(code);
+        let cfg_pos = skeleton.find("#[cfg(feature = \"x\")]").expect("cfg attribute kept");
+        let fn_pos = skeleton.find("pub fn run").expect("function signature kept");
+        assert!(cfg_pos < fn_pos);
+    }
+
+    #[test]
+    fn item_level_macro_invocation_keeps_name_and_args() {
+        let code = "lazy_static! {\n    static ref FOO: Bar = Bar::new();\n}\n";
+        let skeleton = parse_rust(code);
+        assert!(skeleton.contains("lazy_static!("));
+        assert!(skeleton.contains("FOO"));
+    }
+
+    #[test]
+    fn item_level_paren_macro_invocation_defining_a_type_is_kept() {
+        let code = "define_foo!(Bar);\n";
+        let skeleton = parse_rust(code);
+        assert!(skeleton.contains("define_foo!(Bar)"), "skeleton was:\n{skeleton}");
+    }
+
+    #[test]
+    fn macro_invocation_inside_function_body_appears_as_call_edge() {
+        let code = "pub fn run() {\n    tauri::Builder::default()\n        .invoke_handler(tauri::generate_handler![greet])\n        .run();\n}\n";
+        let skeleton = parse_rust(code);
+        assert!(skeleton.contains("generate_handler!"));
+    }
+
+    #[test]
+    fn cfg_test_mod_is_collapsed() {
+        let code = "#[cfg(test)]\nmod tests {\n    #[test]\n    fn one() {}\n    #[test]\n    fn two() {}\n}\n";
+        let skeleton = parse_rust(code);
+        assert!(skeleton.contains("mod tests { /* 2 tests */ }"));
+        assert!(!skeleton.contains("fn one"));
+    }
+
+    #[test]
+    fn raising_max_member_names_shows_every_field_of_a_wide_struct() {
+        let fields: String = (0..15).map(|i| format!("    field_{i}: u32,\n")).collect();
+        let code = format!("struct Wide {{\n{fields}}}\n");
+        let options = SkeletonOptions {
+            max_member_names: 20,
+            ..SkeletonOptions::default()
+        };
+        let skeleton = parse_rust_with_options(&code, &options);
+        for i in 0..15 {
+            assert!(skeleton.contains(&format!("field_{i}")), "missing field_{i} in {skeleton}");
+        }
+        assert!(!skeleton.contains("..."));
+    }
+
+    #[test]
+    fn line_numbers_match_source_line_of_function_signature() {
+        let code = "fn one() {}\n\nfn two() {}\n\npub fn three() {\n    helper();\n}\n";
+        let options = SkeletonOptions {
+            include_line_numbers: true,
+            ..SkeletonOptions::default()
+        };
+        let skeleton = parse_rust_with_options(code, &options);
+        assert!(skeleton.contains("  1: fn one()"), "skeleton was:\n{skeleton}");
+        assert!(skeleton.contains("  3: fn two()"), "skeleton was:\n{skeleton}");
+        assert!(skeleton.contains("  5: pub fn three()"), "skeleton was:\n{skeleton}");
+    }
+
+    #[test]
+    fn line_numbers_absent_when_disabled() {
+        let code = "fn one() {}\n";
+        let skeleton = parse_rust(code);
+        assert!(!skeleton.contains(": fn one"));
+    }
+
+    #[test]
+    fn derive_attribute_sits_directly_above_its_struct_with_no_blank_line() {
+        let code = "#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\nstruct Point {\n    x: i32,\n    y: i32,\n}\n";
+        let skeleton = parse_rust(code);
+        assert!(
+            skeleton.contains("#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\nstruct Point"),
+            "skeleton was:\n{skeleton}"
+        );
+    }
+
+    #[test]
+    fn derive_attribute_is_not_truncated() {
+        let code = "#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Default, PartialOrd, Ord)]\nenum Status {\n    Active,\n    Inactive,\n}\n";
+        let skeleton = parse_rust(code);
+        assert!(
+            skeleton.contains("#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Default, PartialOrd, Ord)]"),
+            "skeleton was:\n{skeleton}"
+        );
+    }
+
+    #[test]
+    fn top_level_attributed_async_generic_function_keeps_attrs_and_qualifiers() {
+        let code = "#[derive(Debug)]\n#[tokio::test]\nasync fn run_test() {}\n\npub(crate) unsafe fn danger<T: Clone>(x: T) -> T {\n    x\n}\n";
+        let skeleton = parse_rust(code);
+        let derive_pos = skeleton.find("#[derive(Debug)]").expect("derive attribute kept");
+        let tokio_pos = skeleton.find("#[tokio::test]").expect("tokio::test attribute kept");
+        let fn_pos = skeleton.find("async fn run_test()").expect("async signature kept");
+        assert!(derive_pos < tokio_pos && tokio_pos < fn_pos);
+        assert!(skeleton.contains("pub(crate) unsafe fn danger<T: Clone>(x: T) -> T"));
+    }
+
+    #[test]
+    fn stacked_attributes_on_impl_method_are_all_kept() {
+        let code = "struct Foo;\nimpl Foo {\n    #[cfg(test)]\n    #[test]\n    fn bar() {}\n}\n";
+        let skeleton = parse_rust(code);
+        assert!(skeleton.contains("#[cfg(test)]"), "skeleton was:\n{skeleton}");
+        assert!(skeleton.contains("#[test]"), "skeleton was:\n{skeleton}");
+        assert!(skeleton.contains("fn bar()"));
+    }
+
+    #[test]
+    fn attribute_survives_doc_comment_before_impl_method() {
+        let code = "struct Foo;\nimpl Foo {\n    #[some_attr]\n    /// why this exists\n    pub fn bar() {}\n}\n";
+        let skeleton = parse_rust(code);
+        assert!(skeleton.contains("#[some_attr]"), "skeleton was:\n{skeleton}");
+        assert!(skeleton.contains("pub fn bar()"));
+    }
+
+    #[test]
+    fn stacked_attributes_on_trait_method_are_all_kept() {
+        let code = "trait Greet {\n    #[deprecated]\n    #[must_use]\n    fn hello(&self) -> String;\n}\n";
+        let skeleton = parse_rust(code);
+        assert!(skeleton.contains("#[deprecated]"), "skeleton was:\n{skeleton}");
+        assert!(skeleton.contains("#[must_use]"), "skeleton was:\n{skeleton}");
+        assert!(skeleton.contains("fn hello(&self) -> String"));
+    }
 }
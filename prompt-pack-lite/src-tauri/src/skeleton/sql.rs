@@ -0,0 +1,168 @@
+//! SQL-specific skeleton extraction using tree-sitter AST.
+//!
+//! Handles `.sql` files with focus on:
+//! - `CREATE TABLE` / `CREATE INDEX` / `CREATE VIEW` headers, columns kept
+//! - `ALTER TABLE` statements
+//! - Long `INSERT` statements summarized to a row count
+//! - Stored procedure/function signatures kept, bodies dropped
+
+use tree_sitter::Node;
+
+use super::common::{get_node_text, truncate_line, MAX_DEF_LINE_LEN};
+
+const MAX_SQL_INSERT_INLINE_LEN: usize = 120;
+
+// ============ Main Entry Point ============
+
+pub fn extract_skeleton(_content: &str, root: Node, source: &[u8]) -> String {
+    let mut output = String::new();
+    let mut cursor = root.walk();
+    for child in root.children(&mut cursor) {
+        extract_sql_statement(&mut output, child, source);
+    }
+    output.trim_end().to_string()
+}
+
+/// `program` children are wrapped in `statement`/`block`/`transaction` nodes;
+/// unwrap those to reach the real statement before dispatching on its kind.
+fn extract_sql_statement(output: &mut String, node: Node, source: &[u8]) {
+    match node.kind() {
+        "statement" => {
+            if let Some(inner) = node.named_child(0) {
+                extract_sql_statement(output, inner, source);
+            }
+        }
+        "block" | "transaction" => {
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                extract_sql_statement(output, child, source);
+            }
+        }
+        "create_table" => emit_create_table(output, node, source),
+        "create_index" | "create_view" | "alter_table" => {
+            output.push_str(&truncate_line(get_node_text(node, source), MAX_DEF_LINE_LEN));
+            output.push_str(";\n\n");
+        }
+        "insert" => emit_insert(output, node, source),
+        "create_function" => emit_create_function(output, node, source),
+        "comment_statement" => {}
+        _ => {}
+    }
+}
+
+// ============ CREATE TABLE ============
+
+fn emit_create_table(output: &mut String, node: Node, source: &[u8]) {
+    let name = object_reference_text(node, source).unwrap_or_default();
+    output.push_str(&format!("CREATE TABLE {} (\n", name));
+
+    if let Some(columns) = find_child_by_kind(node, "column_definitions") {
+        let mut cursor = columns.walk();
+        for child in columns.children(&mut cursor) {
+            if child.kind() != "column_definition" {
+                continue;
+            }
+            let text = get_node_text(child, source).trim();
+            output.push_str("    ");
+            output.push_str(&truncate_line(text, MAX_DEF_LINE_LEN));
+            output.push_str(",\n");
+        }
+    }
+
+    output.push_str(");\n\n");
+}
+
+fn object_reference_text<'a>(node: Node<'a>, source: &'a [u8]) -> Option<&'a str> {
+    find_child_by_kind(node, "object_reference").map(|n| get_node_text(n, source))
+}
+
+fn find_child_by_kind<'a>(node: Node<'a>, kind: &str) -> Option<Node<'a>> {
+    let mut cursor = node.walk();
+    node.children(&mut cursor).find(|c| c.kind() == kind)
+}
+
+// ============ INSERT ============
+
+/// Short inserts are kept verbatim; long ones (bulk `VALUES` lists) are
+/// summarized as `INSERT INTO table (N rows)` so the skeleton doesn't
+/// balloon with literal data.
+fn emit_insert(output: &mut String, node: Node, source: &[u8]) {
+    let text = get_node_text(node, source);
+
+    if text.chars().count() <= MAX_SQL_INSERT_INLINE_LEN {
+        output.push_str(text.trim());
+        output.push_str(";\n\n");
+        return;
+    }
+
+    let table = node
+        .child_by_field_name("name")
+        .map(|n| get_node_text(n, source))
+        .or_else(|| object_reference_text(node, source))
+        .unwrap_or("?");
+    let rows = count_value_rows(node);
+
+    output.push_str(&format!("INSERT INTO {} ({} rows)\n\n", table, rows));
+}
+
+fn count_value_rows(node: Node) -> usize {
+    let mut cursor = node.walk();
+    node.children(&mut cursor)
+        .filter(|c| c.kind() == "list")
+        .count()
+        .max(1)
+}
+
+// ============ CREATE FUNCTION / PROCEDURE ============
+
+/// Stored procedures/functions keep their signature (name, arguments,
+/// return type) but drop the body — it's implementation detail, not shape.
+fn emit_create_function(output: &mut String, node: Node, source: &[u8]) {
+    let name = object_reference_text(node, source).unwrap_or_default();
+    let args = find_child_by_kind(node, "function_arguments")
+        .map(|n| get_node_text(n, source))
+        .unwrap_or("()");
+
+    output.push_str(&format!("CREATE FUNCTION {}{}", name, args));
+
+    if let Some(returns) = find_child_by_kind(node, "column_definitions") {
+        output.push_str(&format!(" RETURNS {}", get_node_text(returns, source).trim()));
+    }
+
+    output.push_str(" { /* ... */ }\n\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tree_sitter::Parser;
+
+    fn parse_sql(code: &str) -> String {
+        let mut parser = Parser::new();
+        parser.set_language(&tree_sitter_sequel::LANGUAGE.into()).unwrap();
+        let tree = parser.parse(code, None).unwrap();
+        extract_skeleton(code, tree.root_node(), code.as_bytes())
+    }
+
+    #[test]
+    fn test_create_table_keeps_columns() {
+        let sql = "CREATE TABLE users (\n    id INT PRIMARY KEY,\n    name VARCHAR(255)\n);\n";
+        let skeleton = parse_sql(sql);
+        assert!(skeleton.contains("CREATE TABLE users ("));
+        assert!(skeleton.contains("id INT PRIMARY KEY"));
+        assert!(skeleton.contains("name VARCHAR(255)"));
+    }
+
+    #[test]
+    fn test_long_insert_is_summarized() {
+        let mut values = Vec::new();
+        for i in 0..50 {
+            values.push(format!("({}, 'user{}')", i, i));
+        }
+        let sql = format!("INSERT INTO users (id, name) VALUES {};\n", values.join(", "));
+        let skeleton = parse_sql(&sql);
+        assert!(skeleton.contains("INSERT INTO users"));
+        assert!(skeleton.contains("rows)"));
+        assert!(!skeleton.contains("user1"));
+    }
+}
@@ -0,0 +1,161 @@
+//! Svelte component (`.svelte`) skeleton extraction.
+//!
+//! Like a Vue SFC, a `.svelte` file glues together markup, a `<script>`
+//! block, and a `<style>` block that no single tree-sitter grammar parses
+//! as one tree. We reuse the same section-splitting and markup-outline
+//! helpers as `vue.rs`, and additionally surface `$:` reactive statements,
+//! which carry most of a Svelte component's actual behavior.
+
+use tree_sitter::Parser;
+
+use super::common::{extract_markup_component_outline, find_markup_section, truncate_line};
+use super::config::extract_css_skeleton;
+use super::typescript;
+
+const MAX_REACTIVE_STATEMENTS: usize = 12;
+
+pub fn extract_skeleton(content: &str, _root: tree_sitter::Node, _source: &[u8]) -> String {
+    let mut output = String::new();
+
+    if let Some(section) = find_markup_section(content, "script") {
+        let is_ts = section.attrs.contains("lang=\"ts\"") || section.attrs.contains("lang='ts'");
+        output.push_str(if is_ts { "// <script lang=\"ts\">\n" } else { "// <script>\n" });
+        output.push_str(&extract_script_section(section.body, is_ts));
+        output.push('\n');
+
+        let reactive = extract_reactive_statements(section.body);
+        if !reactive.is_empty() {
+            output.push_str(&reactive);
+            output.push('\n');
+        }
+        output.push('\n');
+    }
+
+    if let Some(section) = find_markup_section(content, "style") {
+        output.push_str("// <style>\n");
+        output.push_str(&extract_style_section(section.body));
+        output.push_str("\n\n");
+    }
+
+    // Whatever's left outside <script>/<style> is the markup; splice those
+    // two sections out by their byte spans (not a content-based `.replace`,
+    // which would also strip any other occurrence of the section's text
+    // elsewhere in the file, and wouldn't remove the tags themselves) and
+    // outline what remains.
+    let mut removed: Vec<(usize, usize)> = [find_markup_section(content, "script"), find_markup_section(content, "style")]
+        .into_iter()
+        .flatten()
+        .map(|section| (section.start, section.end))
+        .collect();
+    removed.sort_by_key(|&(start, _)| start);
+
+    let mut markup = String::with_capacity(content.len());
+    let mut last_end = 0;
+    for (start, end) in removed {
+        markup.push_str(&content[last_end..start]);
+        last_end = end;
+    }
+    markup.push_str(&content[last_end..]);
+
+    output.push_str("// (markup)\n");
+    output.push_str(&extract_markup_component_outline(&markup));
+
+    output.trim_end().to_string()
+}
+
+// ============ <script> ============
+
+fn extract_script_section(body: &str, is_ts: bool) -> String {
+    let mut parser = Parser::new();
+    let language = if is_ts {
+        tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into()
+    } else {
+        tree_sitter_javascript::LANGUAGE.into()
+    };
+    if parser.set_language(&language).is_err() {
+        return truncate_line(body.trim(), 200);
+    }
+    let Some(tree) = parser.parse(body, None) else {
+        return truncate_line(body.trim(), 200);
+    };
+    typescript::extract_skeleton(body, tree.root_node(), body.as_bytes(), None, false)
+}
+
+/// Reactive statements (`$: doubled = count * 2`, `$: { ... }`) are the
+/// heart of a Svelte component's behavior but are just ordinary labeled
+/// statements to the JS grammar, easy for a generic extractor to drop.
+/// Pull them out of the raw script text directly rather than teaching the
+/// shared JS/TS extractor about a Svelte-only construct.
+fn extract_reactive_statements(body: &str) -> String {
+    let mut lines = Vec::new();
+    for line in body.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("$:") {
+            lines.push(truncate_line(trimmed, 200));
+            if lines.len() >= MAX_REACTIVE_STATEMENTS {
+                break;
+            }
+        }
+    }
+    if lines.is_empty() {
+        return String::new();
+    }
+    format!("// Reactive:\n{}", lines.join("\n"))
+}
+
+// ============ <style> ============
+
+fn extract_style_section(body: &str) -> String {
+    let mut parser = Parser::new();
+    if parser.set_language(&tree_sitter_css::LANGUAGE.into()).is_err() {
+        return truncate_line(body.trim(), 200);
+    }
+    let Some(tree) = parser.parse(body, None) else {
+        return truncate_line(body.trim(), 200);
+    };
+    extract_css_skeleton(body, tree.root_node(), body.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_svelte(code: &str) -> String {
+        let dummy_source = code.as_bytes();
+        let mut html_parser = Parser::new();
+        html_parser.set_language(&tree_sitter_html::LANGUAGE.into()).unwrap();
+        let dummy_tree = html_parser.parse(code, None).unwrap();
+        extract_skeleton(code, dummy_tree.root_node(), dummy_source)
+    }
+
+    #[test]
+    fn test_labels_sections_and_keeps_reactive_statements() {
+        let svelte = "<script>\n  let count = 0;\n  $: doubled = count * 2;\n</script>\n\n<Button on:click={() => count++}>\n  {doubled}\n</Button>\n\n<style>\n  button { color: red; }\n</style>\n";
+        let skeleton = parse_svelte(svelte);
+        assert!(skeleton.contains("<script>"));
+        assert!(skeleton.contains("$: doubled = count * 2;"));
+        assert!(skeleton.contains("<style>"));
+        assert!(skeleton.contains("Button"));
+    }
+
+    #[test]
+    fn test_empty_script_body_does_not_strip_unrelated_newlines_from_markup() {
+        let svelte = "<script>\n</script>\n\n<Header />\n<main>\n  <p>one</p>\n  <p>two</p>\n</main>\n";
+        let skeleton = parse_svelte(svelte);
+        // A naive `markup.replace(section.body, "")` on an empty <script>
+        // body of just "\n" would strip every newline in the whole file
+        // instead of only the one inside the <script> tag.
+        assert!(skeleton.contains("Header"));
+        assert!(skeleton.lines().count() > 1);
+    }
+
+    #[test]
+    fn test_script_and_style_tags_do_not_leak_into_markup_outline() {
+        let svelte = "<script>\n  let count = 0;\n</script>\n\n<Button />\n\n<style>\n  button { color: red; }\n</style>\n";
+        let skeleton = parse_svelte(svelte);
+        let markup_section = skeleton.split("// (markup)").nth(1).expect("markup section");
+        assert!(!markup_section.contains("<script"));
+        assert!(!markup_section.contains("<style"));
+        assert!(markup_section.contains("Button"));
+    }
+}
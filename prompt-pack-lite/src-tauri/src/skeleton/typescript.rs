@@ -9,7 +9,7 @@ use tree_sitter::Node;
 use crate::skeleton::common::{
     get_node_text, truncate_line, compact_text_prefix, trim_doc_comment,
     MAX_DEF_LINE_LEN, MAX_SIMPLE_CONST_LEN, MAX_CALL_EDGE_NAMES,
-    MAX_CALL_EDGE_NAME_LEN, MAX_CALL_EDGE_NODES,
+    MAX_CALL_EDGE_NAME_LEN, MAX_CALL_EDGE_NODES, MAX_MEMBER_NAMES, SkeletonOptions, line_number_prefix,
 };
 
 // ============ Constants ============
@@ -25,6 +25,8 @@ const ENABLE_JS_TS_INSIGHTS: bool = true;
 const MAX_JSX_RETURN_NODES: usize = 2000;
 const MAX_IMPORT_SUMMARY_MODULES: usize = 20;
 const MAX_IMPORT_SUMMARY_NAMES: usize = 12;
+const MAX_NAMESPACE_NESTING: usize = 3;
+const MAX_JS_CONTEXT_KEYS: usize = 8;
 
 // ============ Context Types ============
 
@@ -38,6 +40,7 @@ pub struct JsTsContext<'a> {
     pub entrypoint_mode: bool,
     pub import_summary_only: bool,
     pub unwrap_top_level_iife: bool,
+    pub options: SkeletonOptions,
 }
 
 pub struct JsTsExports {
@@ -94,6 +97,20 @@ pub fn extract_skeleton(
     source: &[u8],
     file_path: Option<&str>,
     is_tsx: bool,
+) -> String {
+    extract_skeleton_with_options(content, root, source, file_path, is_tsx, &SkeletonOptions::default())
+}
+
+/// Extract skeleton from JavaScript/TypeScript source code, additionally
+/// letting the caller raise call-edge limits or turn on line-number
+/// anchoring via `options`.
+pub fn extract_skeleton_with_options(
+    content: &str,
+    root: Node,
+    source: &[u8],
+    file_path: Option<&str>,
+    is_tsx: bool,
+    options: &SkeletonOptions,
 ) -> String {
     let exports = collect_js_ts_exports(root, source);
     let external_imports = collect_js_ts_external_imports(root, source);
@@ -122,6 +139,7 @@ pub fn extract_skeleton(
         entrypoint_mode,
         import_summary_only,
         unwrap_top_level_iife,
+        options: *options,
     };
 
     let mut output = String::new();
@@ -441,6 +459,8 @@ fn extract_js_ts_skeleton<'a>(
             if skip_non_export && !js_ts_decl_is_exported(node, source, ctx) {
                 return;
             }
+            output.push_str(&indent);
+            output.push_str(&line_number_prefix(node, &ctx.options));
             output.push_str(&summarize_ts_declaration(node, source));
             output.push('\n');
         }
@@ -452,10 +472,12 @@ fn extract_js_ts_skeleton<'a>(
             }
             if let Some(sig) = extract_js_function_signature(node, source) {
                 output.push_str(&indent);
+                output.push_str(&line_number_prefix(node, &ctx.options));
                 output.push_str(&sig);
                 output.push('\n');
             }
-            emit_js_function_details(output, node, source, &indent, ctx);
+            let name = node.child_by_field_name("name").map(|n| get_node_text(n, source));
+            emit_js_function_details(output, node, source, &indent, ctx, name);
         }
 
         "arrow_function" | "function_expression" => {
@@ -470,9 +492,10 @@ fn extract_js_ts_skeleton<'a>(
             let sig = truncate_line(&sig, MAX_DEF_LINE_LEN);
             if !sig.is_empty() {
                 output.push_str(&indent);
+                output.push_str(&line_number_prefix(node, &ctx.options));
                 output.push_str(&sig);
                 output.push('\n');
-                emit_js_function_details(output, node, source, &indent, ctx);
+                emit_js_function_details(output, node, source, &indent, ctx, None);
             }
         }
 
@@ -486,7 +509,7 @@ fn extract_js_ts_skeleton<'a>(
             if skip_non_export && !js_ts_decl_is_exported(node, source, ctx) {
                 return;
             }
-            extract_js_class_skeleton(output, node, source, depth);
+            extract_js_class_skeleton(output, node, source, depth, &ctx.options);
         }
 
         // Comments at top level
@@ -501,13 +524,27 @@ fn extract_js_ts_skeleton<'a>(
             }
         }
 
-        // Module/namespace declarations
-        "module" | "namespace_declaration" | "ambient_declaration" => {
+        // Module/namespace declarations, including `declare module 'x' { ... }`
+        // augmentation blocks. Recurse into the body with the same skeleton
+        // rules as everywhere else, so nested types/interfaces/functions/
+        // classes keep their full signatures instead of just their names.
+        "module" | "internal_module" | "namespace_declaration" | "ambient_declaration" => {
             if skip_non_export && !js_ts_decl_is_exported(node, source, ctx) {
                 return;
             }
             output.push_str(&summarize_block_declaration(get_node_text(node, source)));
             output.push('\n');
+
+            if depth >= MAX_NAMESPACE_NESTING {
+                return;
+            }
+            if let Some(body) = js_ts_module_body(node) {
+                let member_ctx = JsTsContext { in_export: false, ..ctx };
+                let mut cursor = body.walk();
+                for member in body.named_children(&mut cursor) {
+                    extract_js_ts_skeleton(output, member, source, depth + 1, member_ctx);
+                }
+            }
         }
 
         // Program root - recurse into children
@@ -534,6 +571,11 @@ fn extract_js_ts_skeleton<'a>(
             if text.starts_with("module.exports") || text.starts_with("exports.") {
                 output.push_str(&truncate_line(text, MAX_DEF_LINE_LEN));
                 output.push('\n');
+            } else if let Some(module) = node.named_child(0).filter(|c| c.kind() == "internal_module") {
+                // `namespace Foo { ... }` without `export`/`declare` parses as a bare
+                // expression statement wrapping the module node - unwrap it so it's
+                // still treated as a namespace instead of being silently dropped.
+                extract_js_ts_skeleton(output, module, source, depth, ctx);
             } else if depth == 0 && !skip_non_export {
                 if ctx.unwrap_top_level_iife {
                     if let Some(iife_fn) = find_iife_function_in_statement(node, source) {
@@ -577,7 +619,7 @@ fn extract_js_function_signature(node: Node, source: &[u8]) -> Option<String> {
             "formal_parameters" | "call_signature" => {
                 parts.push(get_node_text(child, source).to_string());
             }
-            "type_annotation" => {
+            "type_annotation" | "type_predicate_annotation" | "asserts_annotation" => {
                 parts.push(get_node_text(child, source).to_string());
             }
             "type_parameters" => {
@@ -647,6 +689,7 @@ fn emit_js_function_details<'a>(
     source: &'a [u8],
     indent: &str,
     ctx: JsTsContext<'a>,
+    name: Option<&str>,
 ) {
     // Check for JSX return
     if let Some(jsx_node) = find_jsx_return_node(node, source) {
@@ -654,10 +697,25 @@ fn emit_js_function_details<'a>(
         return;
     }
 
+    // Custom hooks (`useFoo`) encapsulate hook calls for reuse and never
+    // return JSX themselves, so give them the same state/effect model a
+    // component gets instead of requiring a JSX return to unlock it.
+    if name.is_some_and(is_custom_hook_name) {
+        emit_js_hooks(output, node, source, indent);
+        emit_js_effects(output, node, source, indent, ctx.external_bindings);
+    }
+
     // Emit insights (includes Invokes, Listens, Opens, Render)
     emit_js_ts_insights(output, node, source, indent, ctx.external_imports, ctx.external_bindings, true);
 }
 
+/// React's convention for custom hooks: `use` followed by a capitalized
+/// word, e.g. `useAuth`, `useLocalStorage`. Matches the naming rule
+/// `eslint-plugin-react-hooks` itself enforces.
+fn is_custom_hook_name(name: &str) -> bool {
+    name.len() > 3 && name.starts_with("use") && name[3..].starts_with(|c: char| c.is_uppercase())
+}
+
 fn emit_js_call_edges(output: &mut String, node: Node, source: &[u8], indent: &str) {
     // Use "Calls" format for internal calls (same as Go/Rust)
     let body = node
@@ -783,16 +841,19 @@ fn emit_js_variable_declarations<'a>(
                 continue;
             }
             output.push_str(indent);
+            output.push_str(&line_number_prefix(child, &ctx.options));
             output.push_str(export_prefix);
             output.push_str(&sig);
             output.push('\n');
-            emit_js_function_details(output, func_node, source, indent, ctx);
+            let name = js_declarator_name(child, source);
+            emit_js_function_details(output, func_node, source, indent, ctx, name.as_deref());
             emitted = true;
             continue;
         }
 
         if let Some(summary) = summarize_js_variable_declarator(child, source, keyword) {
             output.push_str(indent);
+            output.push_str(&line_number_prefix(child, &ctx.options));
             output.push_str(export_prefix);
             output.push_str(&summary);
             output.push('\n');
@@ -965,7 +1026,7 @@ fn summarize_js_variable_declaration(node: Node, source: &[u8]) -> String {
 
 // ============ Class Extraction ============
 
-fn extract_js_class_skeleton(output: &mut String, node: Node, source: &[u8], depth: usize) {
+fn extract_js_class_skeleton(output: &mut String, node: Node, source: &[u8], depth: usize, options: &SkeletonOptions) {
     let indent = "  ".repeat(depth);
     let member_indent = "  ".repeat(depth + 1);
 
@@ -987,6 +1048,7 @@ fn extract_js_class_skeleton(output: &mut String, node: Node, source: &[u8], dep
             }
             "class_body" => {
                 output.push_str(&indent);
+                output.push_str(&line_number_prefix(node, options));
                 output.push_str(&truncate_line(&header_parts.join(" "), MAX_DEF_LINE_LEN));
                 output.push('\n');
 
@@ -1022,7 +1084,7 @@ fn extract_js_class_skeleton(output: &mut String, node: Node, source: &[u8], dep
                             }
                         }
                         "class_declaration" => {
-                            extract_js_class_skeleton(output, member, source, depth + 1);
+                            extract_js_class_skeleton(output, member, source, depth + 1, options);
                         }
                         "constructor_definition" | "constructor" => {
                             if let Some(sig) = extract_js_constructor_signature(member, source) {
@@ -1868,6 +1930,13 @@ fn emit_js_ts_insights(
         return;
     }
 
+    if let Some(context) = detect_js_context_creation(node, source) {
+        output.push_str(indent);
+        output.push_str("// Context: ");
+        output.push_str(&context);
+        output.push('\n');
+    }
+
     let mut invokes = collect_js_invokes(node, source, external_bindings);
     invokes.entries.retain(|entry| {
         let lower = entry.to_ascii_lowercase();
@@ -1923,6 +1992,72 @@ fn emit_js_ts_insights(
     }
 }
 
+/// Detects `const MyContext = createContext(default)` (and
+/// `React.createContext(default)`) declarators and formats the "Context:"
+/// annotation, listing the default value's top-level object-literal keys
+/// when present, e.g. `AuthContext { user, login, logout }`.
+fn detect_js_context_creation(node: Node, source: &[u8]) -> Option<String> {
+    if node.kind() != "variable_declarator" {
+        return None;
+    }
+    let name = js_declarator_name(node, source)?;
+    let call = node.child_by_field_name("value")?;
+    if !js_call_is_create_context(call, source) {
+        return None;
+    }
+
+    let mut annotation = name;
+    let default_value = call.child_by_field_name("arguments")?.named_child(0);
+    if let Some(keys) = default_value.filter(|v| v.kind() == "object").map(|v| collect_object_literal_keys(v, source)) {
+        if !keys.entries.is_empty() {
+            annotation.push_str(" { ");
+            annotation.push_str(&keys.entries.join(", "));
+            if keys.truncated {
+                annotation.push_str(", ...");
+            }
+            annotation.push_str(" }");
+        }
+    }
+    Some(annotation)
+}
+
+fn js_call_is_create_context(node: Node, source: &[u8]) -> bool {
+    if node.kind() != "call_expression" {
+        return false;
+    }
+    let Some(callee) = node.child_by_field_name("function") else { return false; };
+    let text = get_node_text(callee, source);
+    text == "createContext" || text.ends_with(".createContext")
+}
+
+/// Top-level key names of an object literal (`{ user, login: fn, ...rest }`
+/// -> `["user", "login", "..."]`), for the "Context:" default-value summary.
+fn collect_object_literal_keys(node: Node, source: &[u8]) -> JsInsightList {
+    let mut list = JsInsightList { entries: Vec::new(), truncated: false, visited: 0 };
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        list.visited += 1;
+        if list.visited > MAX_JS_INSIGHT_NODES {
+            list.truncated = true;
+            break;
+        }
+        let key = match child.kind() {
+            "pair" => child.child_by_field_name("key").map(|k| get_node_text(k, source).trim_matches(|c| c == '"' || c == '\'').to_string()),
+            "shorthand_property_identifier" => Some(get_node_text(child, source).to_string()),
+            "spread_element" => Some("...".to_string()),
+            _ => None,
+        };
+        if let Some(key) = key {
+            add_unique_entry(&mut list.entries, key, MAX_JS_CONTEXT_KEYS);
+            if list.entries.len() >= MAX_JS_CONTEXT_KEYS {
+                list.truncated = true;
+                break;
+            }
+        }
+    }
+    list
+}
+
 fn collect_js_invokes(
     node: Node,
     source: &[u8],
@@ -2382,6 +2517,22 @@ fn js_declared_name(node: Node, source: &[u8]) -> Option<String> {
     None
 }
 
+/// Body block of a `module`/`internal_module` node, unwrapping the
+/// `ambient_declaration` a `declare module 'x' { ... }` parses as.
+fn js_ts_module_body(node: Node) -> Option<Node> {
+    match node.kind() {
+        "module" | "internal_module" => node.child_by_field_name("body"),
+        "ambient_declaration" => {
+            let mut cursor = node.walk();
+            let module = node
+                .children(&mut cursor)
+                .find(|child| matches!(child.kind(), "module" | "internal_module"))?;
+            module.child_by_field_name("body")
+        }
+        _ => None,
+    }
+}
+
 fn js_variable_declared_names(node: Node, source: &[u8]) -> Vec<String> {
     let mut names = Vec::new();
     let mut cursor = node.walk();
@@ -2428,7 +2579,7 @@ pub fn collect_js_ts_external_imports(root: Node, source: &[u8]) -> JsTsExternal
         if specifier.starts_with("./") || specifier.starts_with("../") {
             continue;
         }
-        modules.insert(specifier);
+        modules.insert(collapse_scoped_package(&specifier));
         collect_imported_names(child, source, &mut components);
         collect_imported_bindings(child, source, &mut bindings);
     }
@@ -2439,6 +2590,21 @@ pub fn collect_js_ts_external_imports(root: Node, source: &[u8]) -> JsTsExternal
     }
 }
 
+/// Collapse a scoped package's deep import (`@org/pkg/sub/path`) down to its
+/// package specifier (`@org/pkg`), so the `// External:` block reports one
+/// entry per dependency instead of one per import path.
+fn collapse_scoped_package(specifier: &str) -> String {
+    if let Some(rest) = specifier.strip_prefix('@') {
+        let mut parts = rest.splitn(3, '/');
+        if let (Some(scope), Some(name)) = (parts.next(), parts.next()) {
+            if !scope.is_empty() && !name.is_empty() {
+                return format!("@{scope}/{name}");
+            }
+        }
+    }
+    specifier.to_string()
+}
+
 fn collect_imported_names(node: Node, source: &[u8], names: &mut HashSet<String>) {
     let mut cursor = node.walk();
     for child in node.children(&mut cursor) {
@@ -2651,14 +2817,69 @@ fn strip_js_string_quotes(raw: &str) -> Option<String> {
 }
 
 fn summarize_ts_declaration(node: Node, source: &[u8]) -> String {
+    if node.kind() == "enum_declaration" {
+        return summarize_ts_enum(node, source);
+    }
     let text = get_node_text(node, source);
     match node.kind() {
         "type_alias_declaration" => summarize_type_alias(text),
-        "interface_declaration" | "enum_declaration" => summarize_block_declaration(text),
+        "interface_declaration" => summarize_block_declaration(text),
         _ => truncate_line(text, MAX_DEF_LINE_LEN),
     }
 }
 
+/// Summarize a TypeScript `enum` (or `const enum`) declaration as
+/// `[const] enum Name { A = 0, B = 1, ... }`, showing each member's explicit
+/// value (numeric or string) when it has one and just the bare name
+/// otherwise, up to `MAX_MEMBER_NAMES`.
+fn summarize_ts_enum(node: Node, source: &[u8]) -> String {
+    let is_const = node.children(&mut node.walk()).any(|c| c.kind() == "const");
+    let name = node
+        .child_by_field_name("name")
+        .map(|n| get_node_text(n, source))
+        .unwrap_or("");
+    let prefix = if is_const { "const enum" } else { "enum" };
+
+    let Some(body) = node.child_by_field_name("body") else {
+        return truncate_line(&format!("{prefix} {name}"), MAX_DEF_LINE_LEN);
+    };
+
+    let mut members = Vec::new();
+    let mut total = 0;
+    let mut cursor = body.walk();
+    for child in body.children(&mut cursor) {
+        match child.kind() {
+            "enum_assignment" => {
+                total += 1;
+                if members.len() < MAX_MEMBER_NAMES {
+                    let member_name = child
+                        .child_by_field_name("name")
+                        .map(|n| get_node_text(n, source))
+                        .unwrap_or("");
+                    let value = child
+                        .child_by_field_name("value")
+                        .map(|n| get_node_text(n, source))
+                        .unwrap_or("");
+                    members.push(format!("{member_name} = {value}"));
+                }
+            }
+            "property_identifier" | "string" | "number" | "computed_property_name" => {
+                total += 1;
+                if members.len() < MAX_MEMBER_NAMES {
+                    members.push(get_node_text(child, source).to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut joined = members.join(", ");
+    if total > members.len() {
+        joined.push_str(&format!(", ... +{} more", total - members.len()));
+    }
+    truncate_line(&format!("{prefix} {name} {{ {joined} }}"), MAX_DEF_LINE_LEN)
+}
+
 fn summarize_type_alias(text: &str) -> String {
     let (compact, truncated) = compact_text_prefix(text, MAX_SIMPLE_CONST_LEN + 1);
     let trimmed = compact.trim_end();
@@ -2855,6 +3076,23 @@ mod tests {
         extract_skeleton(code, tree.root_node(), code.as_bytes(), None, true)
     }
 
+    #[test]
+    fn test_typescript_line_numbers_opt_in() {
+        let code = "function one() {}\n\nfunction two() {}\n";
+        let mut parser = Parser::new();
+        parser.set_language(&tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into()).unwrap();
+        let tree = parser.parse(code, None).unwrap();
+        let options = SkeletonOptions {
+            include_line_numbers: true,
+            ..SkeletonOptions::default()
+        };
+        let skeleton = extract_skeleton_with_options(
+            code, tree.root_node(), code.as_bytes(), None, false, &options,
+        );
+        assert!(skeleton.contains("1: function one ()"), "skeleton was:\n{skeleton}");
+        assert!(skeleton.contains("3: function two ()"), "skeleton was:\n{skeleton}");
+    }
+
     #[test]
     fn test_typescript_imports() {
         let code = r#"import { useState } from 'react';
@@ -2890,6 +3128,51 @@ interface User {
         assert!(skeleton.contains("interface User"));
     }
 
+    #[test]
+    fn test_typescript_numeric_enum_shows_values() {
+        let code = "enum Direction { Up = 0, Down = 1 }";
+        let skeleton = parse_ts(code);
+        assert!(skeleton.contains("enum Direction { Up = 0, Down = 1 }"), "skeleton was:\n{skeleton}");
+    }
+
+    #[test]
+    fn test_typescript_string_enum_shows_values() {
+        let code = r#"enum Color { Red = "RED", Green = "GREEN" }"#;
+        let skeleton = parse_ts(code);
+        assert!(skeleton.contains(r#"enum Color { Red = "RED", Green = "GREEN" }"#), "skeleton was:\n{skeleton}");
+    }
+
+    #[test]
+    fn test_typescript_enum_without_values() {
+        let code = "enum Direction { Up, Down, Left, Right }";
+        let skeleton = parse_ts(code);
+        assert!(skeleton.contains("enum Direction { Up, Down, Left, Right }"), "skeleton was:\n{skeleton}");
+    }
+
+    #[test]
+    fn test_typescript_mixed_enum() {
+        let code = r#"enum Mixed { A, B = "b", C = 5 }"#;
+        let skeleton = parse_ts(code);
+        assert!(skeleton.contains(r#"enum Mixed { A, B = "b", C = 5 }"#), "skeleton was:\n{skeleton}");
+    }
+
+    #[test]
+    fn test_typescript_const_enum() {
+        let code = "const enum Direction { Up = 0, Down = 1 }";
+        let skeleton = parse_ts(code);
+        assert!(skeleton.contains("const enum Direction { Up = 0, Down = 1 }"), "skeleton was:\n{skeleton}");
+    }
+
+    #[test]
+    fn test_typescript_enum_truncates_beyond_max_member_names() {
+        let members: String = (0..15).map(|i| format!("M{i} = {i}")).collect::<Vec<_>>().join(", ");
+        let code = format!("enum Wide {{ {members} }}");
+        let skeleton = parse_ts(&code);
+        assert!(skeleton.contains("M0 = 0"), "skeleton was:\n{skeleton}");
+        assert!(!skeleton.contains("M14 = 14"), "skeleton was:\n{skeleton}");
+        assert!(skeleton.contains(", ... +7 more"), "skeleton was:\n{skeleton}");
+    }
+
     #[test]
     fn test_typescript_class() {
         let code = r#"
@@ -2942,4 +3225,97 @@ export function Counter(): JSX.Element {
         assert!(skeleton.contains("function foo"));
         assert!(skeleton.contains("const a"));
     }
+
+    #[test]
+    fn test_namespace_recurses_into_members() {
+        let code = r#"
+namespace Shapes {
+    export interface Circle {
+        radius: number;
+    }
+    export interface Square {
+        side: number;
+    }
+    export function area(c: Circle): number {
+        return c.radius * c.radius * 3.14;
+    }
+}
+"#;
+        let skeleton = parse_ts(code);
+        assert!(skeleton.contains("interface Circle"), "skeleton was:\n{skeleton}");
+        assert!(skeleton.contains("interface Square"), "skeleton was:\n{skeleton}");
+        assert!(skeleton.contains("function area"), "skeleton was:\n{skeleton}");
+    }
+
+    #[test]
+    fn test_namespace_caps_nesting_depth() {
+        let code = r#"
+namespace A {
+  namespace B {
+    namespace C {
+      namespace D {
+        // padding padding padding padding padding padding padding padding
+        // padding padding padding padding padding padding padding padding
+        // padding padding padding padding padding padding padding padding
+        export function deep(a: number, b: number, c: number): number {
+          return a + b + c;
+        }
+      }
+    }
+  }
+}
+"#;
+        let skeleton = parse_ts(code);
+        assert!(skeleton.contains("namespace D {...}"), "skeleton was:\n{skeleton}");
+        assert!(!skeleton.contains("function deep"), "skeleton was:\n{skeleton}");
+    }
+
+    #[test]
+    fn test_detects_context_creation_with_default_value_keys() {
+        let code = r#"
+export const AuthContext = createContext({
+    user: null,
+    login: () => {},
+    logout: () => {},
+});
+
+export const ThemeContext = React.createContext("light");
+"#;
+        let skeleton = parse_ts(code);
+        assert!(
+            skeleton.contains("// Context: AuthContext { user, login, logout }"),
+            "skeleton was:\n{skeleton}"
+        );
+        assert!(skeleton.contains("// Context: ThemeContext"), "skeleton was:\n{skeleton}");
+        assert!(!skeleton.contains("ThemeContext {"), "skeleton was:\n{skeleton}");
+    }
+
+    #[test]
+    fn test_custom_hook_shows_state_model_without_jsx_return() {
+        let code = r#"
+export function useCounter(initial: number) {
+    const [count, setCount] = useState(initial);
+    useEffect(() => {
+        console.log(count);
+    }, [count]);
+    return count;
+}
+"#;
+        let skeleton = parse_ts(code);
+        assert!(skeleton.contains("function useCounter"), "skeleton was:\n{skeleton}");
+        assert!(skeleton.contains("// useState: count"), "skeleton was:\n{skeleton}");
+        assert!(skeleton.contains("// Effect:"), "skeleton was:\n{skeleton}");
+    }
+
+    #[test]
+    fn test_non_hook_function_has_no_state_model() {
+        let code = r#"
+export function processCounter(initial: number) {
+    const [count, setCount] = useState(initial);
+    return count;
+}
+"#;
+        let skeleton = parse_ts(code);
+        assert!(!skeleton.contains("// useState:"), "skeleton was:\n{skeleton}");
+    }
 }
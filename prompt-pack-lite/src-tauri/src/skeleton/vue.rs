@@ -0,0 +1,101 @@
+//! Vue single-file component (`.vue`) skeleton extraction.
+//!
+//! A `.vue` file is three languages glued together (`<template>`, `<script>`,
+//! `<style>`), which no single tree-sitter grammar parses as one tree. We do
+//! a lightweight text pre-pass to split out the three sections, then hand
+//! each one to the extractor that already understands it: the TS/JS
+//! extractor for `<script>`, the CSS extractor for `<style>`, and a compact
+//! tag outline (similar in spirit to the JSX "// Render:" summary) for
+//! `<template>`.
+
+use tree_sitter::{Node, Parser};
+
+use super::common::{extract_markup_component_outline, find_markup_section, truncate_line};
+use super::config::extract_css_skeleton;
+use super::typescript;
+
+pub fn extract_skeleton(content: &str, _root: Node, _source: &[u8]) -> String {
+    let mut output = String::new();
+
+    if let Some(section) = find_markup_section(content, "template") {
+        output.push_str("// <template>\n");
+        output.push_str(&extract_markup_component_outline(section.body));
+        output.push_str("\n\n");
+    }
+
+    if let Some(section) = find_markup_section(content, "script") {
+        let is_ts = section.attrs.contains("lang=\"ts\"") || section.attrs.contains("lang='ts'");
+        output.push_str(if is_ts { "// <script lang=\"ts\">\n" } else { "// <script>\n" });
+        output.push_str(&extract_script_section(section.body, is_ts));
+        output.push_str("\n\n");
+    }
+
+    if let Some(section) = find_markup_section(content, "style") {
+        output.push_str("// <style>\n");
+        output.push_str(&extract_style_section(section.body));
+        output.push_str("\n\n");
+    }
+
+    output.trim_end().to_string()
+}
+
+// ============ <script> ============
+
+fn extract_script_section(body: &str, is_ts: bool) -> String {
+    let mut parser = Parser::new();
+    let language = if is_ts {
+        tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into()
+    } else {
+        tree_sitter_javascript::LANGUAGE.into()
+    };
+    if parser.set_language(&language).is_err() {
+        return truncate_line(body.trim(), 200);
+    }
+    let Some(tree) = parser.parse(body, None) else {
+        return truncate_line(body.trim(), 200);
+    };
+    typescript::extract_skeleton(body, tree.root_node(), body.as_bytes(), None, false)
+}
+
+// ============ <style> ============
+
+fn extract_style_section(body: &str) -> String {
+    let mut parser = Parser::new();
+    if parser.set_language(&tree_sitter_css::LANGUAGE.into()).is_err() {
+        return truncate_line(body.trim(), 200);
+    }
+    let Some(tree) = parser.parse(body, None) else {
+        return truncate_line(body.trim(), 200);
+    };
+    extract_css_skeleton(body, tree.root_node(), body.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_vue(code: &str) -> String {
+        let dummy_source = code.as_bytes();
+        let mut html_parser = Parser::new();
+        html_parser.set_language(&tree_sitter_html::LANGUAGE.into()).unwrap();
+        let dummy_tree = html_parser.parse(code, None).unwrap();
+        extract_skeleton(code, dummy_tree.root_node(), dummy_source)
+    }
+
+    #[test]
+    fn test_labels_each_section() {
+        let vue = "<template>\n  <MyButton />\n</template>\n<script>\nexport function run() {}\n</script>\n<style>\n.foo { color: red; }\n</style>\n";
+        let skeleton = parse_vue(vue);
+        assert!(skeleton.contains("<template>"));
+        assert!(skeleton.contains("<script>"));
+        assert!(skeleton.contains("<style>"));
+    }
+
+    #[test]
+    fn test_template_lists_custom_components() {
+        let vue = "<template>\n  <div>\n    <MyButton />\n    <span>hi</span>\n  </div>\n</template>\n";
+        let skeleton = parse_vue(vue);
+        assert!(skeleton.contains("MyButton"));
+        assert!(skeleton.contains("Layout"));
+    }
+}
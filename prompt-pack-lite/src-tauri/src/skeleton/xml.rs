@@ -0,0 +1,199 @@
+//! XML-specific skeleton extraction using tree-sitter.
+//!
+//! XML files (`.xml`, `.csproj`, Maven's `pom.xml`) are collapsed to a tag
+//! tree showing each element's name and attribute names, with runs of
+//! repeated sibling elements (list-like content: `<dependency>` entries in a
+//! `pom.xml`, `<PackageReference>` entries in a `.csproj`) folded into a
+//! single `<tag> x N` line instead of being expanded one by one.
+
+use tree_sitter::Node;
+
+use super::common::{get_node_text, truncate_line, MAX_DEF_LINE_LEN, MAX_MEMBER_NAMES};
+
+/// A run of consecutive same-name sibling elements at or above this length is
+/// collapsed to a single `<tag> x N` line instead of being expanded.
+const MIN_XML_REPEAT_COUNT: usize = 3;
+
+/// Extract skeleton from XML source code
+pub fn extract_skeleton(content: &str, root: Node, source: &[u8]) -> String {
+    let _ = content;
+    let mut output = String::new();
+
+    let mut cursor = root.walk();
+    for child in root.children(&mut cursor) {
+        if child.kind() == "prolog" {
+            let mut prolog_cursor = child.walk();
+            for part in child.children(&mut prolog_cursor) {
+                if part.kind() == "XMLDecl" {
+                    output.push_str(&truncate_line(get_node_text(part, source), MAX_DEF_LINE_LEN));
+                    output.push('\n');
+                }
+            }
+        }
+    }
+
+    if let Some(root_element) = root.child_by_field_name("root") {
+        extract_xml_element(&mut output, root_element, source, 0);
+    }
+
+    output.trim_end().to_string()
+}
+
+/// An element's tag name, its attribute names (capped at `MAX_MEMBER_NAMES`),
+/// and whether it's self-closing (`<tag/>` vs `<tag>...</tag>`).
+fn xml_tag_info(node: Node, source: &[u8]) -> (String, Vec<String>, bool) {
+    let mut cursor = node.walk();
+    for tag in node.children(&mut cursor) {
+        if !matches!(tag.kind(), "STag" | "EmptyElemTag") {
+            continue;
+        }
+        let self_closing = tag.kind() == "EmptyElemTag";
+        let mut name = String::new();
+        let mut attrs = Vec::new();
+        let mut tag_cursor = tag.walk();
+        for part in tag.children(&mut tag_cursor) {
+            match part.kind() {
+                // The tag's own name is the first bare `Name` child; every
+                // other `Name` is nested inside an `Attribute` node instead.
+                "Name" if name.is_empty() => name = get_node_text(part, source).to_string(),
+                "Attribute" if attrs.len() < MAX_MEMBER_NAMES => {
+                    let mut attr_cursor = part.walk();
+                    let attr_name = part.children(&mut attr_cursor).find(|c| c.kind() == "Name");
+                    if let Some(attr_name) = attr_name {
+                        attrs.push(get_node_text(attr_name, source).to_string());
+                    }
+                }
+                _ => {}
+            }
+        }
+        return (name, attrs, self_closing);
+    }
+    (String::new(), Vec::new(), false)
+}
+
+fn extract_xml_element(output: &mut String, node: Node, source: &[u8], depth: usize) {
+    let indent = "  ".repeat(depth);
+    let (tag_name, attrs, self_closing) = xml_tag_info(node, source);
+
+    output.push_str(&indent);
+    output.push('<');
+    output.push_str(&tag_name);
+    for attr in &attrs {
+        output.push(' ');
+        output.push_str(attr);
+    }
+    if self_closing {
+        output.push_str(" />\n");
+        return;
+    }
+    output.push('>');
+
+    let (child_elements, has_text) = xml_content(node, source);
+
+    if child_elements.is_empty() {
+        if has_text {
+            output.push_str("...");
+        }
+    } else {
+        output.push('\n');
+        let mut i = 0;
+        while i < child_elements.len() {
+            let (run_name, _, _) = xml_tag_info(child_elements[i], source);
+            let mut j = i + 1;
+            while j < child_elements.len() && xml_tag_info(child_elements[j], source).0 == run_name {
+                j += 1;
+            }
+            let run_len = j - i;
+            if run_len >= MIN_XML_REPEAT_COUNT {
+                output.push_str(&"  ".repeat(depth + 1));
+                output.push_str(&format!("<{run_name}> x {run_len}\n"));
+            } else {
+                for element in &child_elements[i..j] {
+                    extract_xml_element(output, *element, source, depth + 1);
+                }
+            }
+            i = j;
+        }
+        output.push_str(&indent);
+    }
+
+    output.push_str("</");
+    output.push_str(&tag_name);
+    output.push_str(">\n");
+}
+
+/// An element's child elements and whether it has any non-whitespace text
+/// content, pulled from its `content` child (absent on self-closing tags).
+fn xml_content<'a>(node: Node<'a>, source: &[u8]) -> (Vec<Node<'a>>, bool) {
+    let mut cursor = node.walk();
+    let Some(content) = node.children(&mut cursor).find(|c| c.kind() == "content") else {
+        return (Vec::new(), false);
+    };
+    let mut child_cursor = content.walk();
+    let mut elements = Vec::new();
+    let mut has_text = false;
+    for child in content.children(&mut child_cursor) {
+        match child.kind() {
+            "element" => elements.push(child),
+            "CharData" => has_text = has_text || !get_node_text(child, source).trim().is_empty(),
+            _ => {}
+        }
+    }
+    (elements, has_text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tree_sitter::Parser;
+
+    fn parse_xml(code: &str) -> String {
+        let mut parser = Parser::new();
+        parser.set_language(&tree_sitter_xml::LANGUAGE_XML.into()).unwrap();
+        let tree = parser.parse(code, None).unwrap();
+        extract_skeleton(code, tree.root_node(), code.as_bytes())
+    }
+
+    #[test]
+    fn test_xml_declaration_is_preserved() {
+        let code = r#"<?xml version="1.0" encoding="UTF-8"?><root></root>"#;
+        let skeleton = parse_xml(code);
+        assert!(skeleton.contains(r#"<?xml version="1.0" encoding="UTF-8"?>"#), "skeleton was:\n{skeleton}");
+    }
+
+    #[test]
+    fn test_xml_element_with_attributes() {
+        let code = r#"<project id="1" name="demo"></project>"#;
+        let skeleton = parse_xml(code);
+        assert!(skeleton.contains("<project id name>"), "skeleton was:\n{skeleton}");
+    }
+
+    #[test]
+    fn test_xml_self_closing_element() {
+        let code = r#"<root><leaf attr="x"/></root>"#;
+        let skeleton = parse_xml(code);
+        assert!(skeleton.contains("<leaf attr />"), "skeleton was:\n{skeleton}");
+    }
+
+    #[test]
+    fn test_xml_collapses_repeated_siblings() {
+        let code = r#"<dependencies>
+    <dependency><groupId>a</groupId></dependency>
+    <dependency><groupId>b</groupId></dependency>
+    <dependency><groupId>c</groupId></dependency>
+    <dependency><groupId>d</groupId></dependency>
+</dependencies>"#;
+        let skeleton = parse_xml(code);
+        assert!(skeleton.contains("<dependency> x 4"), "skeleton was:\n{skeleton}");
+        assert!(!skeleton.contains("<groupId>"), "individual entries should be collapsed: {skeleton}");
+    }
+
+    #[test]
+    fn test_xml_keeps_small_number_of_siblings_expanded() {
+        let code = r#"<items><item></item><item></item></items>"#;
+        let skeleton = parse_xml(code);
+        assert!(!skeleton.contains(" x 2"), "two siblings shouldn't be collapsed: {skeleton}");
+        let item_count = skeleton.matches("<item>").count();
+        assert_eq!(item_count, 2, "skeleton was:\n{skeleton}");
+    }
+}
@@ -2,7 +2,8 @@
 //!
 //! These tests verify AST-based code skeletonization for various languages.
 
-use crate::skeleton::{skeletonize_with_path, SkeletonResult};
+use crate::skeleton::{skeletonize_with_overrides, skeletonize_with_path, SkeletonResult, SupportedLanguage};
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 
@@ -18,6 +19,18 @@ fn skeletonize_with_fixture_path(path: &Path) -> SkeletonResult {
     skeletonize_with_path(&content, ext, Some(path_str.as_ref()))
 }
 
+#[test]
+fn test_custom_extension_override_matches_dotted_component() {
+    let mut overrides = HashMap::new();
+    overrides.insert("blade".to_string(), SupportedLanguage::TypeScript);
+
+    let code = "export function hello(): string { return 'hi'; }";
+    let result = skeletonize_with_overrides(code, "php", Some("foo.blade.php"), &overrides);
+
+    assert_eq!(result.language, Some(SupportedLanguage::TypeScript));
+    assert!(result.skeleton.contains("export function hello"));
+}
+
 #[test]
 fn test_typescript_skeleton() {
     let code = r#"
@@ -150,6 +163,27 @@ pub fn helper() -> i32 {
     assert!(!result.skeleton.contains("HashMap::new()"));
 }
 
+#[test]
+fn test_rust_skeleton_keeps_todo_and_safety_comments() {
+    let code = r#"
+// TODO: replace with a bounded cache
+pub struct Cache {
+    data: Vec<u8>,
+}
+
+impl Cache {
+    pub fn get_unchecked(&self, index: usize) -> u8 {
+        // SAFETY: caller guarantees index is in bounds
+        unsafe { *self.data.get_unchecked(index) }
+    }
+}
+"#;
+
+    let result = skeletonize(code, "rs");
+    assert!(result.skeleton.contains("TODO: replace with a bounded cache"));
+    assert!(result.skeleton.contains("SAFETY: caller guarantees index is in bounds"));
+}
+
 #[test]
 fn test_fallback_compression() {
     let code = r#"
@@ -441,6 +475,28 @@ func Start(cfg *Config) {
     assert!(result.skeleton.contains("// Calls: fmt.Printf, os.Exit"));
 }
 
+#[test]
+fn test_go_skeleton_keeps_todo_comments_including_block_form() {
+    let code = r#"
+package service
+
+// TODO: swap this for a real config loader
+func LoadConfig() {
+}
+
+/* SAFETY: only call this once the pool is initialized */
+func Shutdown() {
+}
+
+// just describing the next line, nothing special here
+func Noop() {
+}
+"#;
+    let result = skeletonize(code, "go");
+    assert!(result.skeleton.contains("TODO: swap this for a real config loader"));
+    assert!(result.skeleton.contains("SAFETY: only call this once the pool is initialized"));
+}
+
 #[test]
 fn test_css_skeleton() {
     let code = r#"
@@ -494,7 +550,9 @@ fn test_html_skeleton() {
     assert!(result.skeleton.contains("<html>"));
     assert!(result.skeleton.contains("<head>"));
     assert!(result.skeleton.contains("<body>"));
-    assert!(result.skeleton.contains("<div> <!-- 3 children -->"));
+    assert!(result.skeleton.contains(r#"<div id="root">"#));
+    assert!(result.skeleton.contains("<ul> <!-- 2 children -->"));
+    assert!(result.skeleton.contains(r#"<script src="app.js">"#));
 }
 
 #[test]
@@ -645,6 +703,27 @@ export const process = debounce(() => {
     assert!(result.skeleton.contains("window.alert"));
 }
 
+#[test]
+fn test_js_external_imports_collapse_scoped_packages_and_dedup() {
+    let code = r#"
+import { render } from '@testing-library/react';
+import { fireEvent } from '@testing-library/react/dist/pure';
+import debounce from 'lodash/debounce';
+import throttle from 'lodash/throttle';
+
+export function setup() {
+    return render(debounce(throttle(fireEvent, 10), 10));
+}
+"#;
+    let result = skeletonize(code, "js");
+    // The two `@testing-library/react` deep imports collapse to one entry.
+    assert!(result.skeleton.contains("// External: @testing-library/react\n"));
+    assert!(!result.skeleton.contains("@testing-library/react/dist/pure"));
+    // Non-scoped subpath imports aren't collapsed, but each appears only once.
+    assert_eq!(result.skeleton.matches("// External: lodash/debounce").count(), 1);
+    assert_eq!(result.skeleton.matches("// External: lodash/throttle").count(), 1);
+}
+
 #[test]
 fn test_ts_advanced_skeleton() {
     let code = r#"
@@ -675,6 +754,38 @@ class Outer {
 
 
 
+#[test]
+fn test_ts_overload_set_and_type_predicate_preserved() {
+    let code = r#"
+function isFoo(x: unknown): x is Foo;
+function isFoo(x: unknown, opts: Options): x is Foo;
+function isFoo(x: unknown, opts?: Options): x is Foo {
+    return true;
+}
+"#;
+    let result = skeletonize(code, "ts");
+    // Every overload signature survives, not just the first.
+    assert_eq!(result.skeleton.matches("isFoo").count(), 3);
+    // The type predicate return type is kept verbatim rather than dropped.
+    assert!(result.skeleton.contains("x is Foo"));
+}
+
+#[test]
+fn test_ts_declare_module_lists_augmented_members() {
+    let code = r#"
+declare module 'my-lib' {
+    export function helper(): void;
+    export const VERSION: string;
+    export class Widget {}
+}
+"#;
+    let result = skeletonize(code, "ts");
+    assert!(result.skeleton.contains("declare module 'my-lib'"));
+    assert!(result.skeleton.contains("helper"));
+    assert!(result.skeleton.contains("VERSION"));
+    assert!(result.skeleton.contains("Widget"));
+}
+
 #[test]
 fn test_python_nested_structures() {
     let code = r#"
@@ -893,6 +1004,25 @@ class InventoryItem:
     assert!(result.skeleton.contains("def total_cost"));
 }
 
+#[test]
+fn test_python_annotated_assignments_kept_with_parens() {
+    let code = r#"
+from dataclasses import dataclass, field
+from typing import List
+
+DEFAULT_TAGS: List[str] = list(("core", "beta"))
+
+@dataclass
+class Config:
+    items: List[str] = field(default_factory=list)
+    handler: Callable = staticmethod(lambda: None)
+"#;
+    let result = skeletonize(code, "py");
+    assert!(result.skeleton.contains("DEFAULT_TAGS: List[str] = list((\"core\", \"beta\"))"));
+    assert!(result.skeleton.contains("items: List[str] = field(default_factory=list)"));
+    assert!(result.skeleton.contains("handler: Callable = staticmethod(lambda: None)"));
+}
+
 #[test]
 fn test_python_exception_handling() {
     let code = r#"
@@ -1501,8 +1631,69 @@ fn test_html_varied_structure_suite() {
     println!("Skeleton:\n{}", result.skeleton);
     assert!(result.skeleton.contains("<html>"));
     assert!(result.skeleton.contains("<body>"));
-    assert!(result.skeleton.contains("<main> <!-- 2 children -->"));
-    assert!(result.skeleton.contains("<template>"));
+    assert!(result.skeleton.contains("<main>"));
+    assert!(result.skeleton.contains("<section> <!-- 1 children -->"));
+    assert!(result.skeleton.contains("<aside> <!-- 1 children -->"));
+    assert!(result.skeleton.contains(r#"<template id="row">"#));
+    assert!(result.skeleton.contains(r#"<script src="/app.js">"#));
+}
+
+fn assert_parse_error_annotated(code: &str, extension: &str) {
+    let result = skeletonize(code, extension);
+    assert!(result.parse_errors, "expected parse_errors to be true for broken {}", extension);
+    assert!(
+        result.skeleton.contains("PARSE ERRORS"),
+        "expected parse-error annotation in {} skeleton:\n{}",
+        extension,
+        result.skeleton
+    );
+}
+
+#[test]
+fn test_parse_error_annotation_python() {
+    assert_parse_error_annotated("def foo(x, y\n    return x + y\n", "py");
+}
+
+#[test]
+fn test_parse_error_annotation_typescript() {
+    assert_parse_error_annotated("function foo() {\n    return 1;\n", "ts");
+}
+
+#[test]
+fn test_parse_error_annotation_javascript() {
+    assert_parse_error_annotated("function foo() {\n    return 1;\n", "js");
+}
+
+#[test]
+fn test_parse_error_annotation_rust() {
+    assert_parse_error_annotated("fn foo() {\n    let x = 1;\n", "rs");
+}
+
+#[test]
+fn test_parse_error_annotation_go() {
+    assert_parse_error_annotated("package main\nfunc foo() {\n    return\n", "go");
+}
+
+#[test]
+fn test_parse_error_annotation_c() {
+    assert_parse_error_annotated("int foo() {\n    return 1;\n", "c");
+}
+
+#[test]
+fn test_parse_error_annotation_json() {
+    assert_parse_error_annotated("{\"a\": 1", "json");
+}
+
+#[test]
+fn test_parse_error_annotation_css() {
+    assert_parse_error_annotated("a {\n  color: red;\n", "css");
+}
+
+#[test]
+fn test_well_formed_source_has_no_parse_error_annotation() {
+    let result = skeletonize("def foo():\n    return 1\n", "py");
+    assert!(!result.parse_errors);
+    assert!(!result.skeleton.contains("PARSE ERRORS"));
 }
 
 fn run_fixture_benchmarks(label: &str, fixtures: &[&str]) {
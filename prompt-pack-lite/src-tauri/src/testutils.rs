@@ -0,0 +1,39 @@
+//! Shared helpers for this crate's `#[cfg(test)]` modules, so a test that
+//! needs a scratch directory on disk doesn't reinvent `TestDir` yet again.
+
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A scratch directory under the OS temp dir, named `<prefix>_<pid>_<nanos>`
+/// so parallel test runs never collide with each other or a previous run,
+/// removed again on drop.
+pub struct TestDir {
+    pub path: PathBuf,
+}
+
+impl TestDir {
+    pub fn new(prefix: &str) -> Self {
+        let mut path = std::env::temp_dir();
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+        path.push(format!("{prefix}_{}_{now}", std::process::id()));
+        std::fs::create_dir_all(&path).unwrap();
+        Self { path }
+    }
+
+    /// Write `content` to `rel` under the scratch dir, creating any parent
+    /// directories it needs, and return the full path as a string.
+    pub fn write(&self, rel: &str, content: &str) -> String {
+        let file_path = self.path.join(rel);
+        if let Some(parent) = file_path.parent() {
+            std::fs::create_dir_all(parent).unwrap();
+        }
+        std::fs::write(&file_path, content).unwrap();
+        file_path.to_string_lossy().to_string()
+    }
+}
+
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}